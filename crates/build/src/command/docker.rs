@@ -82,7 +82,7 @@ pub(crate) fn create_docker_command(
         "-e".to_string(),
         "RUSTUP_TOOLCHAIN=succinct".to_string(),
         "-e".to_string(),
-        format!("CARGO_ENCODED_RUSTFLAGS={}", get_rust_compiler_flags()),
+        format!("CARGO_ENCODED_RUSTFLAGS={}", get_rust_compiler_flags(args)),
         "--entrypoint".to_string(),
         "".to_string(),
         image,
@@ -42,8 +42,8 @@ pub(crate) fn get_program_build_args(args: &BuildArgs) -> Vec<String> {
 }
 
 /// Rust flags for compilation of C libraries.
-pub(crate) fn get_rust_compiler_flags() -> String {
-    let rust_flags = [
+pub(crate) fn get_rust_compiler_flags(args: &BuildArgs) -> String {
+    let mut rust_flags = vec![
         "-C".to_string(),
         "passes=loweratomic".to_string(),
         "-C".to_string(),
@@ -51,6 +51,17 @@ pub(crate) fn get_rust_compiler_flags() -> String {
         "-C".to_string(),
         "panic=abort".to_string(),
     ];
+
+    if !args.target_features.is_empty() {
+        let features = args
+            .target_features
+            .iter()
+            .map(|feature| format!("+{}", feature.trim_start_matches('+')))
+            .collect::<Vec<_>>();
+        rust_flags.push("-C".to_string());
+        rust_flags.push(format!("target-feature={}", features.join(",")));
+    }
+
     rust_flags.join("\x1f")
 }
 
@@ -43,6 +43,14 @@ pub struct BuildArgs {
     pub ignore_rust_version: bool,
     #[clap(long, action, help = "Assert that `Cargo.lock` will remain unchanged")]
     pub locked: bool,
+    #[clap(
+        long,
+        action,
+        value_delimiter = ',',
+        help = "Comma separated list of RISC-V target features to enable, e.g. `zba,zbb` for the \
+                bit-manipulation extensions"
+    )]
+    pub target_features: Vec<String>,
     #[clap(
         alias = "bin",
         long,
@@ -76,6 +84,7 @@ impl Default for BuildArgs {
             output_directory: DEFAULT_OUTPUT_DIR.to_string(),
             locked: false,
             no_default_features: false,
+            target_features: vec![],
         }
     }
 }
@@ -2,9 +2,9 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use sp1_cli::{
     commands::{
-        build::BuildCmd, build_toolchain::BuildToolchainCmd,
-        install_toolchain::InstallToolchainCmd, new::NewCmd, prove::ProveCmd, trace::TraceCmd,
-        vkey::VkeyCmd,
+        build::BuildCmd, build_toolchain::BuildToolchainCmd, inspect::InspectCmd,
+        install_toolchain::InstallToolchainCmd, new::NewCmd, prove::ProveCmd, replay::ReplayCmd,
+        trace::TraceCmd, vk_map::VkMapCmd, vkey::VkeyCmd,
     },
     SP1_VERSION_MESSAGE,
 };
@@ -34,6 +34,9 @@ pub enum ProveCliCommands {
     InstallToolchain(InstallToolchainCmd),
     Trace(TraceCmd),
     Vkey(VkeyCmd),
+    VkMap(VkMapCmd),
+    Inspect(InspectCmd),
+    Replay(ReplayCmd),
 }
 
 fn main() -> Result<()> {
@@ -47,5 +50,8 @@ fn main() -> Result<()> {
         ProveCliCommands::InstallToolchain(cmd) => cmd.run(),
         ProveCliCommands::Trace(cmd) => cmd.run(),
         ProveCliCommands::Vkey(cmd) => cmd.run(),
+        ProveCliCommands::VkMap(cmd) => cmd.run(),
+        ProveCliCommands::Inspect(cmd) => cmd.run(),
+        ProveCliCommands::Replay(cmd) => cmd.run(),
     }
 }
@@ -0,0 +1,211 @@
+use std::{borrow::Borrow, fs::File, io::Read, path::PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+use p3_baby_bear::BabyBear;
+use p3_field::{PrimeField, PrimeField32};
+use prettytable::{format, row, Table};
+use sp1_prover::{
+    shapes::format_digest,
+    utils::{babybear_bytes_to_bn254, babybears_to_bn254, words_to_bytes},
+};
+use sp1_recursion_core::air::{RecursionPublicValues, RECURSIVE_PROOF_NUM_PV_ELTS};
+use sp1_sdk::{HashableKey, ProverClient, SP1Proof, SP1ProofKind, SP1ProofWithPublicValues};
+use sp1_stark::{
+    air::{PublicValues, SP1_PROOF_NUM_PV_ELTS},
+    Word,
+};
+
+#[derive(Parser)]
+#[command(name = "inspect", about = "Decode and print the contents of a saved proof.")]
+pub struct InspectCmd {
+    /// Path to a proof saved with `SP1ProofWithPublicValues::save`.
+    proof: PathBuf,
+
+    /// Path to the ELF the proof is for, used to compute the vkey hash of core proofs.
+    #[arg(long)]
+    elf: Option<PathBuf>,
+}
+
+/// The digests committed to by a proof, shown for every proof kind.
+#[derive(Default)]
+struct Digests {
+    vkey_hash: Option<String>,
+    committed_value_digest: Option<String>,
+    deferred_proofs_digest: Option<String>,
+}
+
+impl InspectCmd {
+    pub fn run(&self) -> Result<()> {
+        let proof = SP1ProofWithPublicValues::load(&self.proof)?;
+
+        println!("Proof kind: {:?}", SP1ProofKind::from(&proof.proof));
+        println!("SP1 version: {}", proof.sp1_version);
+        println!("Public values: 0x{}", hex::encode(proof.public_values.as_slice()));
+
+        let mut digests = match &proof.proof {
+            SP1Proof::Core(shard_proofs) => {
+                println!("Shards: {}", shard_proofs.len());
+                for shard_proof in shard_proofs {
+                    print_shard_public_values(shard_public_values(&shard_proof.public_values)?);
+
+                    // Print the chips in the order they were committed to.
+                    let mut chips = shard_proof.chip_ordering.iter().collect::<Vec<_>>();
+                    chips.sort_by_key(|(_, index)| **index);
+                    let mut table = Table::new();
+                    table.set_format(*format::consts::FORMAT_CLEAN);
+                    table.set_titles(row!["  Chip", "Height"]);
+                    for (name, index) in chips {
+                        let log_degree = shard_proof.opened_values.chips[*index].log_degree;
+                        table.add_row(row![format!("  {}", name), 1usize << log_degree]);
+                    }
+                    table.printstd();
+                }
+
+                match shard_proofs.last() {
+                    Some(shard_proof) => {
+                        let public_values = shard_public_values(&shard_proof.public_values)?;
+                        Digests {
+                            vkey_hash: None,
+                            committed_value_digest: Some(committed_value_digest(
+                                &public_values.committed_value_digest,
+                            )),
+                            deferred_proofs_digest: Some(format_digest(
+                                &public_values.deferred_proofs_digest,
+                            )),
+                        }
+                    }
+                    None => Digests::default(),
+                }
+            }
+            SP1Proof::Compressed(reduce_proof) => {
+                let public_values = recursion_public_values(&reduce_proof.proof.public_values)?;
+                print_recursion_public_values(public_values);
+                Digests {
+                    vkey_hash: Some(bn254_hex(&babybears_to_bn254(&public_values.sp1_vk_digest))),
+                    committed_value_digest: Some(committed_value_digest(
+                        &public_values.committed_value_digest,
+                    )),
+                    deferred_proofs_digest: Some(format_digest(
+                        &public_values.deferred_proofs_digest,
+                    )),
+                }
+            }
+            SP1Proof::Plonk(proof) => {
+                println!("Plonk vkey hash: 0x{}", hex::encode(proof.plonk_vkey_hash));
+                println!("Public inputs: {:?}", proof.public_inputs);
+                public_input_digests(&proof.public_inputs)
+            }
+            SP1Proof::Groth16(proof) => {
+                println!("Groth16 vkey hash: 0x{}", hex::encode(proof.groth16_vkey_hash));
+                println!("Public inputs: {:?}", proof.public_inputs);
+                public_input_digests(&proof.public_inputs)
+            }
+        };
+
+        // Core proofs don't commit to the vkey, so it can only be computed from the ELF.
+        if let Some(elf) = &self.elf {
+            let mut program = Vec::new();
+            File::open(elf)?.read_to_end(&mut program)?;
+            let (_, vk) = ProverClient::new().setup(&program);
+            digests.vkey_hash.get_or_insert_with(|| vk.bytes32());
+        }
+
+        let unavailable = || "unavailable".to_string();
+        println!("Vkey hash: {}", digests.vkey_hash.unwrap_or_else(unavailable));
+        println!(
+            "Committed value digest: {}",
+            digests.committed_value_digest.unwrap_or_else(unavailable)
+        );
+        println!(
+            "Deferred proofs digest: {}",
+            digests.deferred_proofs_digest.unwrap_or_else(unavailable)
+        );
+
+        Ok(())
+    }
+}
+
+fn print_shard_public_values(pv: &PublicValues<Word<BabyBear>, BabyBear>) {
+    println!("Shard {} (execution shard {}):", pv.shard, pv.execution_shard);
+    println!("  start_pc: {:#x}", pv.start_pc.as_canonical_u32());
+    println!("  next_pc: {:#x}", pv.next_pc.as_canonical_u32());
+    println!("  exit_code: {}", pv.exit_code);
+    println!("  previous_init_addr: {:#x}", bits_to_u32(&pv.previous_init_addr_bits));
+    println!("  last_init_addr: {:#x}", bits_to_u32(&pv.last_init_addr_bits));
+    println!("  previous_finalize_addr: {:#x}", bits_to_u32(&pv.previous_finalize_addr_bits));
+    println!("  last_finalize_addr: {:#x}", bits_to_u32(&pv.last_finalize_addr_bits));
+    println!("  committed_value_digest: {}", committed_value_digest(&pv.committed_value_digest));
+    println!("  deferred_proofs_digest: {}", format_digest(&pv.deferred_proofs_digest));
+}
+
+fn print_recursion_public_values(pv: &RecursionPublicValues<BabyBear>) {
+    println!("Recursion public values:");
+    println!("  start_pc: {:#x}", pv.start_pc.as_canonical_u32());
+    println!("  next_pc: {:#x}", pv.next_pc.as_canonical_u32());
+    println!("  start_shard: {}", pv.start_shard);
+    println!("  next_shard: {}", pv.next_shard);
+    println!("  start_execution_shard: {}", pv.start_execution_shard);
+    println!("  next_execution_shard: {}", pv.next_execution_shard);
+    println!("  previous_init_addr: {:#x}", bits_to_u32(&pv.previous_init_addr_bits));
+    println!("  last_init_addr: {:#x}", bits_to_u32(&pv.last_init_addr_bits));
+    println!("  previous_finalize_addr: {:#x}", bits_to_u32(&pv.previous_finalize_addr_bits));
+    println!("  last_finalize_addr: {:#x}", bits_to_u32(&pv.last_finalize_addr_bits));
+    println!("  sp1_vk_digest: {}", format_digest(&pv.sp1_vk_digest));
+    println!("  vk_root: {}", format_digest(&pv.vk_root));
+    println!("  committed_value_digest: {}", committed_value_digest(&pv.committed_value_digest));
+    println!("  deferred_proofs_digest: {}", format_digest(&pv.deferred_proofs_digest));
+    println!("  is_complete: {}", pv.is_complete);
+    println!("  contains_execution_shard: {}", pv.contains_execution_shard);
+    println!("  exit_code: {}", pv.exit_code);
+    println!("  digest: {}", format_digest(&pv.digest));
+}
+
+/// The public values of a shard, or an error if the proof has too few of them.
+fn shard_public_values(values: &[BabyBear]) -> Result<&PublicValues<Word<BabyBear>, BabyBear>> {
+    if values.len() < SP1_PROOF_NUM_PV_ELTS {
+        anyhow::bail!(
+            "the shard has {} public values, expected {SP1_PROOF_NUM_PV_ELTS}",
+            values.len()
+        );
+    }
+    Ok(values.borrow())
+}
+
+/// The public values of a recursion proof, or an error if the proof has too few of them.
+fn recursion_public_values(values: &[BabyBear]) -> Result<&RecursionPublicValues<BabyBear>> {
+    if values.len() < RECURSIVE_PROOF_NUM_PV_ELTS {
+        anyhow::bail!(
+            "the recursion proof has {} public values, expected {RECURSIVE_PROOF_NUM_PV_ELTS}",
+            values.len()
+        );
+    }
+    Ok(values.borrow())
+}
+
+/// Format the committed value digest as the Bn254 element the onchain verifier receives.
+fn committed_value_digest(words: &[Word<BabyBear>]) -> String {
+    let bytes: [BabyBear; 32] = words_to_bytes(words).try_into().unwrap();
+    bn254_hex(&babybear_bytes_to_bn254(&bytes))
+}
+
+/// The public inputs of Plonk and Groth16 proofs are the vkey hash and committed value digest.
+fn public_input_digests(public_inputs: &[String; 2]) -> Digests {
+    let to_hex = |input: &String| {
+        num_bigint::BigUint::parse_bytes(input.as_bytes(), 10)
+            .map(|value| format!("0x{:0>64}", value.to_str_radix(16)))
+    };
+    Digests {
+        vkey_hash: to_hex(&public_inputs[0]),
+        committed_value_digest: to_hex(&public_inputs[1]),
+        deferred_proofs_digest: None,
+    }
+}
+
+fn bn254_hex(value: &impl PrimeField) -> String {
+    format!("0x{:0>64}", value.as_canonical_biguint().to_str_radix(16))
+}
+
+fn bits_to_u32(bits: &[BabyBear; 32]) -> u32 {
+    bits.iter().enumerate().map(|(i, bit)| bit.as_canonical_u32() << i).sum()
+}
@@ -1,7 +1,10 @@
 pub mod build;
 pub mod build_toolchain;
+pub mod inspect;
 pub mod install_toolchain;
 pub mod new;
 pub mod prove;
+pub mod replay;
 pub mod trace;
+pub mod vk_map;
 pub mod vkey;
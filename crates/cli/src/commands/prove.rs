@@ -2,6 +2,7 @@ use anstyle::*;
 use anyhow::Result;
 use clap::Parser;
 use sp1_build::{execute_build_program, BuildArgs};
+use sp1_core_executor::Program;
 use sp1_core_machine::{
     io::SP1Stdin,
     utils::{setup_logger, setup_tracer},
@@ -92,6 +93,12 @@ impl ProveCmd {
             .read_to_end(&mut elf)
             .expect("failed to read from input file");
 
+        // Catch programs that can't run before spending anything on proving them.
+        let report = Program::from(&elf)?.validate();
+        if !report.is_valid() {
+            anyhow::bail!("the program is invalid:\n{report}");
+        }
+
         let mut stdin = SP1Stdin::new();
         if let Some(ref input) = self.input {
             match input {
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use p3_baby_bear::BabyBear;
+use sp1_core_machine::utils::{replay_trace_dump, setup_logger, ShardTraceDump};
+
+#[derive(Parser)]
+#[command(
+    name = "replay",
+    about = "Replay a chip of a shard dumped with TRACE_DUMP_DIR set while proving."
+)]
+pub struct ReplayCmd {
+    /// Path to a shard dump, `shard-<n>.bin` in the dump directory.
+    dump: PathBuf,
+
+    /// The name of the chip to replay. The dumped chips are listed if omitted.
+    #[arg(long)]
+    chip: Option<String>,
+}
+
+impl ReplayCmd {
+    pub fn run(&self) -> Result<()> {
+        setup_logger();
+        let Some(chip) = &self.chip else {
+            let dump = ShardTraceDump::<BabyBear>::load(&self.dump)?;
+            println!("Shard {} chips:", dump.shard);
+            for chip in &dump.chips {
+                println!("  {}", chip.name);
+            }
+            return Ok(());
+        };
+
+        let report = replay_trace_dump(&self.dump, chip)?;
+        println!("{report}");
+        if !report.is_ok() {
+            bail!("replaying {} failed", chip);
+        }
+        Ok(())
+    }
+}
@@ -20,11 +20,11 @@
 
 use anyhow::Result;
 use clap::Parser;
-use goblin::elf::{sym::STT_FUNC, Elf};
 use indicatif::{ProgressBar, ProgressStyle};
 use prettytable::{format, Cell, Row, Table};
 use regex::Regex;
 use rustc_demangle::demangle;
+use sp1_core_executor::Program;
 use std::{
     cmp::Ordering,
     collections::HashMap,
@@ -167,26 +167,31 @@ fn _build_radare2_lookups(
     Ok(())
 }
 
-fn build_goblin_lookups(
+fn build_lookups(
     start_lookup: &mut HashMap<u64, String>,
     end_lookup: &mut HashMap<u64, String>,
     func_range_lookup: &mut HashMap<String, (u64, u64)>,
     elf_name: &str,
-) -> std::io::Result<()> {
-    let buffer = std::fs::read(elf_name).unwrap();
-    let elf = Elf::parse(&buffer).unwrap();
-
-    for sym in &elf.syms {
-        if sym.st_type() == STT_FUNC {
-            let name = elf.strtab.get_at(sym.st_name).unwrap_or("");
-            let demangled_name = demangle(name);
-            let size = sym.st_size;
-            let start_address = sym.st_value;
-            let end_address = start_address + size - 4;
-            start_lookup.insert(start_address, demangled_name.to_string());
-            end_lookup.insert(end_address, demangled_name.to_string());
-            func_range_lookup.insert(demangled_name.to_string(), (start_address, end_address));
+) -> Result<()> {
+    let buffer = std::fs::read(elf_name)?;
+    let program = Program::from_with_debug_info(&buffer)?;
+    let functions = program.debug_info.as_ref().map(|info| info.functions()).unwrap_or_default();
+
+    for (index, symbol) in functions.iter().enumerate() {
+        // A function without a recorded size extends up to the next one.
+        let size = match (symbol.size, functions.get(index + 1)) {
+            (0, Some(next)) => next.address - symbol.address,
+            (size, _) => size,
+        };
+        if size == 0 {
+            continue;
         }
+        let demangled_name = demangle(&symbol.name);
+        let start_address = symbol.address as u64;
+        let end_address = start_address + size as u64 - 4;
+        start_lookup.insert(start_address, demangled_name.to_string());
+        end_lookup.insert(end_address, demangled_name.to_string());
+        func_range_lookup.insert(demangled_name.to_string(), (start_address, end_address));
     }
     Ok(())
 }
@@ -220,8 +225,7 @@ impl TraceCmd {
         let mut start_lookup = HashMap::new();
         let mut end_lookup = HashMap::new();
         let mut func_range_lookup = HashMap::new();
-        build_goblin_lookups(&mut start_lookup, &mut end_lookup, &mut func_range_lookup, &elf_path)
-            .unwrap();
+        build_lookups(&mut start_lookup, &mut end_lookup, &mut func_range_lookup, &elf_path)?;
 
         let mut function_ranges: Vec<(u64, u64, String)> =
             func_range_lookup.iter().map(|(f, &(start, end))| (start, end, f.clone())).collect();
@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use sp1_core_machine::utils::setup_logger;
+use sp1_prover::{
+    components::DefaultProverComponents,
+    shapes::{
+        build_vk_map_to_file, format_digest, parse_digest, pinned_vk_root, vk_map_dir, VkMap,
+        ALLOWED_VK_MAP_FILE, DUMMY_VK_MAP_FILE,
+    },
+    InnerSC, REDUCE_BATCH_SIZE,
+};
+use sp1_recursion_circuit::merkle_tree::MerkleTree;
+
+#[derive(Parser)]
+#[command(name = "vk-map", about = "Build and inspect the allowed recursion verifying key map.")]
+pub struct VkMapCmd {
+    #[clap(subcommand)]
+    command: VkMapCommands,
+}
+
+#[derive(Subcommand)]
+enum VkMapCommands {
+    /// Build the vk map and write it to the vk map directory.
+    Build {
+        /// The directory to write the vk map to. Defaults to `VK_MAP_DIR` or
+        /// `~/.sp1/shapes/<circuit version>`.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+
+        /// Build a dummy vk map instead of setting up every recursion program.
+        #[arg(long)]
+        dummy: bool,
+
+        /// The number of proofs reduced together in each recursion step.
+        #[arg(long, default_value_t = REDUCE_BATCH_SIZE)]
+        reduce_batch_size: usize,
+
+        /// The number of workers compiling the recursion programs.
+        #[arg(long, default_value_t = 1)]
+        num_compiler_workers: usize,
+
+        /// The number of workers setting up the recursion programs.
+        #[arg(long, default_value_t = 1)]
+        num_setup_workers: usize,
+
+        /// Only build the shapes starting at this index.
+        #[arg(long)]
+        start: Option<usize>,
+
+        /// Only build the shapes up to this index.
+        #[arg(long)]
+        end: Option<usize>,
+    },
+    /// Print the number of verifying keys and the root of a vk map.
+    Inspect {
+        /// Path to the vk map. Defaults to the allowed vk map in the vk map directory.
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Check whether a verifying key digest is in a vk map and print its Merkle proof.
+    Membership {
+        /// The digest of the verifying key, as printed by `inspect`.
+        digest: String,
+
+        /// Path to the vk map. Defaults to the allowed vk map in the vk map directory.
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+}
+
+impl VkMapCmd {
+    pub fn run(&self) -> Result<()> {
+        setup_logger();
+        match &self.command {
+            VkMapCommands::Build {
+                dir,
+                dummy,
+                reduce_batch_size,
+                num_compiler_workers,
+                num_setup_workers,
+                start,
+                end,
+            } => {
+                // Building the vk map requires the fixed core and recursion shapes.
+                std::env::set_var("FIX_CORE_SHAPES", "true");
+                std::env::set_var("FIX_RECURSION_SHAPES", "true");
+
+                let dir = dir.clone().unwrap_or_else(vk_map_dir);
+                build_vk_map_to_file::<DefaultProverComponents>(
+                    dir.clone(),
+                    *reduce_batch_size,
+                    *dummy,
+                    *num_compiler_workers,
+                    *num_setup_workers,
+                    *start,
+                    *end,
+                )?;
+
+                let file = if *dummy { DUMMY_VK_MAP_FILE } else { ALLOWED_VK_MAP_FILE };
+                let vk_map = VkMap::read(&dir.join(file))?;
+                println!("Wrote {}", dir.join(file).display());
+                println!("Root: {}", format_digest(&vk_map.root));
+            }
+            VkMapCommands::Inspect { path } => {
+                let vk_map = read_vk_map(path)?;
+                println!("Verifying keys: {}", vk_map.map.len());
+                println!("Merkle tree height: {}", vk_map.tree.height);
+                println!("Root: {}", format_digest(&vk_map.root));
+                match pinned_vk_root()? {
+                    Some(pinned) if pinned == vk_map.root => println!("Matches VK_ROOT"),
+                    Some(pinned) => println!("Does not match VK_ROOT {}", format_digest(&pinned)),
+                    None => {}
+                }
+            }
+            VkMapCommands::Membership { digest, path } => {
+                let vk_map = read_vk_map(path)?;
+                let digest = parse_digest(digest)?;
+                let Some(proof) = vk_map.open(&digest) else {
+                    bail!("{} is not in the vk map", format_digest(&digest));
+                };
+
+                println!("Index: {}", proof.index);
+                println!("Path:");
+                for sibling in &proof.path {
+                    println!("  {}", format_digest(sibling));
+                }
+                MerkleTree::<_, InnerSC>::verify(proof, digest, vk_map.root)
+                    .map_err(|_| anyhow::anyhow!("the Merkle proof does not verify"))?;
+                println!("Verified against root {}", format_digest(&vk_map.root));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_vk_map(path: &Option<PathBuf>) -> Result<VkMap> {
+    let path = path.clone().unwrap_or_else(|| vk_map_dir().join(ALLOWED_VK_MAP_FILE));
+    Ok(VkMap::read(&path)?)
+}
@@ -0,0 +1,41 @@
+// The riscv-arch-test target model of the SP1 executor.
+//
+// Tests halt with the HALT syscall and exit code 0, and the signature is the memory between the
+// `begin_signature` and `end_signature` symbols, which the executor reads back after halting.
+
+#ifndef _COMPLIANCE_MODEL_H
+#define _COMPLIANCE_MODEL_H
+
+#define RVMODEL_DATA_SECTION
+
+#define RVMODEL_BOOT
+
+#define RVMODEL_HALT \
+  li t0, 0;          \
+  li a0, 0;          \
+  ecall;
+
+#define RVMODEL_DATA_BEGIN \
+  RVMODEL_DATA_SECTION     \
+  .align 4;                \
+  .global begin_signature; \
+  begin_signature:
+
+#define RVMODEL_DATA_END \
+  .align 4;              \
+  .global end_signature; \
+  end_signature:
+
+#define RVMODEL_IO_INIT
+#define RVMODEL_IO_WRITE_STR(_R, _STR)
+#define RVMODEL_IO_CHECK()
+#define RVMODEL_IO_ASSERT_GPR_EQ(_S, _R, _I)
+#define RVMODEL_IO_ASSERT_SFPR_EQ(_F, _R, _I)
+#define RVMODEL_IO_ASSERT_DFPR_EQ(_D, _R, _I)
+
+#define RVMODEL_SET_MSW_INT
+#define RVMODEL_CLEAR_MSW_INT
+#define RVMODEL_CLEAR_MTIMER_INT
+#define RVMODEL_CLEAR_MEXT_INT
+
+#endif
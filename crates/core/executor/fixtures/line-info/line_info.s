@@ -0,0 +1,11 @@
+# A program with a DWARF line table for `debug_info::tests::resolves_source_lines`, which expects
+# the instructions below at the lines they are on. Rebuild `line_info.elf` with `make`.
+
+    .text
+    .globl _start
+_start:
+    li a0, 1
+    li a1, 2
+    add a0, a0, a1
+    li t0, 0
+    ecall
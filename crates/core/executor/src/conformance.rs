@@ -0,0 +1,334 @@
+//! Conformance of the executor to the RV32IM reference signatures.
+//!
+//! The tests of [riscv-arch-test](https://github.com/riscv-non-isa/riscv-arch-test) write their
+//! results to a memory region between the `begin_signature` and `end_signature` symbols, which is
+//! compared word by word against a `.reference_output` file produced by the reference model. Tests
+//! in the style of [riscv-tests](https://github.com/riscv-software-src/riscv-tests) instead leave
+//! their result in registers, which are compared against a `.registers` file.
+//!
+//! A suite is a directory of `<test>.elf` files, each with a `<test>.reference_output` file, a
+//! `<test>.registers` file, or both. See `crates/core/executor/conformance` for how to build one.
+
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use elf::{endian::LittleEndian, ElfBytes};
+use sp1_stark::SP1CoreOpts;
+use thiserror::Error;
+
+use crate::{ExecutionError, Executor, Program};
+
+/// The symbol marking the start of the signature region.
+pub const BEGIN_SIGNATURE: &str = "begin_signature";
+
+/// The symbol marking the end of the signature region.
+pub const END_SIGNATURE: &str = "end_signature";
+
+/// An error that occurred while running a conformance test.
+#[derive(Error, Debug)]
+pub enum ConformanceError {
+    /// The test or one of its references could not be read.
+    #[error("failed to read {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+
+    /// The ELF could not be parsed.
+    #[error("invalid ELF: {0}")]
+    Elf(String),
+
+    /// The ELF doesn't define a symbol of the signature region.
+    #[error("the ELF has no `{0}` symbol")]
+    MissingSymbol(&'static str),
+
+    /// The executor failed to run the test.
+    #[error("execution failed: {0}")]
+    Execution(#[from] ExecutionError),
+
+    /// A line of a reference file could not be parsed.
+    #[error("invalid reference at line {line}: {text:?}")]
+    InvalidReference {
+        /// The line number, starting at 1.
+        line: usize,
+        /// The contents of the line.
+        text: String,
+    },
+
+    /// The test has neither a memory nor a register reference.
+    #[error("no reference signature")]
+    NoReference,
+
+    /// The signature region has a different number of words than the reference.
+    #[error("the signature has {actual} words, but the reference has {expected}")]
+    LengthMismatch {
+        /// The number of words in the reference.
+        expected: usize,
+        /// The number of words in the signature region.
+        actual: usize,
+    },
+
+    /// A word of the signature differs from the reference.
+    #[error("signature word {index} at {addr:#x} is {actual:#010x}, expected {expected:#010x}")]
+    MemoryMismatch {
+        /// The index of the word in the signature.
+        index: usize,
+        /// The address of the word.
+        addr: u32,
+        /// The word in the reference.
+        expected: u32,
+        /// The word written by the test.
+        actual: u32,
+    },
+
+    /// A register differs from the reference.
+    #[error("register x{register} is {actual:#010x}, expected {expected:#010x}")]
+    RegisterMismatch {
+        /// The index of the register.
+        register: usize,
+        /// The value in the reference.
+        expected: u32,
+        /// The value left by the test.
+        actual: u32,
+    },
+}
+
+/// The final state of a conformance test.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    /// The address of the first word of the signature region.
+    pub begin: u32,
+    /// The words of the signature region, empty if the ELF doesn't define one.
+    pub memory: Vec<u32>,
+    /// The registers at the end of execution.
+    pub registers: [u32; 32],
+}
+
+impl Signature {
+    /// Check the signature region against a reference, word by word.
+    pub fn check_memory(&self, reference: &[u32]) -> Result<(), ConformanceError> {
+        if self.memory.len() != reference.len() {
+            return Err(ConformanceError::LengthMismatch {
+                expected: reference.len(),
+                actual: self.memory.len(),
+            });
+        }
+        for (index, (&actual, &expected)) in self.memory.iter().zip(reference).enumerate() {
+            if actual != expected {
+                let addr = self.begin + 4 * index as u32;
+                return Err(ConformanceError::MemoryMismatch { index, addr, expected, actual });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the registers against the values of a reference.
+    pub fn check_registers(&self, reference: &[(usize, u32)]) -> Result<(), ConformanceError> {
+        for &(register, expected) in reference {
+            let actual = self.registers[register];
+            if actual != expected {
+                return Err(ConformanceError::RegisterMismatch { register, expected, actual });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Find the bounds of the signature region of an ELF, if it defines one.
+pub fn signature_bounds(elf: &[u8]) -> Result<Option<(u32, u32)>, ConformanceError> {
+    let elf = ElfBytes::<LittleEndian>::minimal_parse(elf)
+        .map_err(|error| ConformanceError::Elf(error.to_string()))?;
+    let Some((symbols, strings)) =
+        elf.symbol_table().map_err(|error| ConformanceError::Elf(error.to_string()))?
+    else {
+        return Ok(None);
+    };
+    let find = |name: &'static str| {
+        symbols.iter().find_map(|symbol| {
+            let symbol_name = strings.get(symbol.st_name as usize).ok()?;
+            (symbol_name == name).then_some(symbol.st_value as u32)
+        })
+    };
+    match (find(BEGIN_SIGNATURE), find(END_SIGNATURE)) {
+        (Some(begin), Some(end)) => Ok(Some((begin, end))),
+        (None, None) => Ok(None),
+        (None, Some(_)) => Err(ConformanceError::MissingSymbol(BEGIN_SIGNATURE)),
+        (Some(_), None) => Err(ConformanceError::MissingSymbol(END_SIGNATURE)),
+    }
+}
+
+/// Run a test with [`Executor::run_fast`] and read its final registers and signature region.
+pub fn run_signature(elf: &[u8]) -> Result<Signature, ConformanceError> {
+    let bounds = signature_bounds(elf)?;
+    let program = Program::from(elf).map_err(|error| ConformanceError::Elf(error.to_string()))?;
+    let mut runtime = Executor::new(program, SP1CoreOpts::default());
+    runtime.run_fast()?;
+
+    let (begin, end) = bounds.unwrap_or_default();
+    let memory = (begin..end).step_by(4).map(|addr| runtime.word(addr)).collect();
+    Ok(Signature { begin, memory, registers: runtime.registers() })
+}
+
+/// Parse a `.reference_output` file, which has one hexadecimal word per line.
+pub fn parse_memory_reference(text: &str) -> Result<Vec<u32>, ConformanceError> {
+    reference_lines(text)
+        .map(|(line, text)| {
+            u32::from_str_radix(text, 16).map_err(|_| invalid_reference(line, text))
+        })
+        .collect()
+}
+
+/// Parse a `.registers` file, which has a register and its hexadecimal value per line, e.g.
+/// `x3 00000001`.
+pub fn parse_register_reference(text: &str) -> Result<Vec<(usize, u32)>, ConformanceError> {
+    reference_lines(text)
+        .map(|(line, text)| {
+            let (register, value) = text
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| invalid_reference(line, text))?;
+            let register = register
+                .strip_prefix('x')
+                .and_then(|index| index.parse::<usize>().ok())
+                .filter(|&index| index < 32)
+                .ok_or_else(|| invalid_reference(line, text))?;
+            let value = value.trim().trim_start_matches("0x");
+            let value =
+                u32::from_str_radix(value, 16).map_err(|_| invalid_reference(line, text))?;
+            Ok((register, value))
+        })
+        .collect()
+}
+
+/// The non-empty lines of a reference file, with their line numbers.
+fn reference_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn invalid_reference(line: usize, text: &str) -> ConformanceError {
+    ConformanceError::InvalidReference { line, text: text.to_string() }
+}
+
+/// The outcome of a test of a suite.
+#[derive(Debug)]
+pub struct ConformanceResult {
+    /// The name of the test, the file stem of its ELF.
+    pub name: String,
+    /// Whether the test matched its references.
+    pub result: Result<(), ConformanceError>,
+}
+
+impl Display for ConformanceResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.result {
+            Ok(()) => write!(f, "{}: ok", self.name),
+            Err(error) => write!(f, "{}: {error}", self.name),
+        }
+    }
+}
+
+/// Run a single test against the references next to its ELF.
+pub fn run_conformance_test(elf_path: &Path) -> Result<(), ConformanceError> {
+    let read =
+        |path: &Path| fs::read(path).map_err(|error| ConformanceError::Io(path.into(), error));
+    let read_reference = |extension: &str| {
+        let path = elf_path.with_extension(extension);
+        path.exists()
+            .then(|| read(&path).map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+            .transpose()
+    };
+    let memory_reference = read_reference("reference_output")?
+        .map(|text| parse_memory_reference(&text))
+        .transpose()?;
+    let register_reference =
+        read_reference("registers")?.map(|text| parse_register_reference(&text)).transpose()?;
+    if memory_reference.is_none() && register_reference.is_none() {
+        return Err(ConformanceError::NoReference);
+    }
+
+    let signature = run_signature(&read(elf_path)?)?;
+    if let Some(reference) = memory_reference {
+        signature.check_memory(&reference)?;
+    }
+    if let Some(reference) = register_reference {
+        signature.check_registers(&reference)?;
+    }
+    Ok(())
+}
+
+/// Run every `.elf` test of a suite directory, in the order of their names.
+pub fn run_conformance_suite(dir: &Path) -> Result<Vec<ConformanceResult>, ConformanceError> {
+    let entries = fs::read_dir(dir).map_err(|error| ConformanceError::Io(dir.into(), error))?;
+    let mut elfs = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "elf"))
+        .collect::<Vec<_>>();
+    elfs.sort();
+    Ok(elfs
+        .iter()
+        .map(|path| ConformanceResult {
+            name: path.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+            result: run_conformance_test(path),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_references() {
+        let memory = parse_memory_reference("00000001\n\nffffffff\n").unwrap();
+        assert_eq!(memory, vec![1, u32::MAX]);
+        let registers = parse_register_reference("# pass\nx3 00000001\nx10 0x0\n").unwrap();
+        assert_eq!(registers, vec![(3, 1), (10, 0)]);
+        assert!(matches!(
+            parse_register_reference("x32 0"),
+            Err(ConformanceError::InvalidReference { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn signature_mismatch_is_reported() {
+        let signature = Signature { begin: 0x1000, memory: vec![1, 2, 3], registers: [0; 32] };
+        assert!(signature.check_memory(&[1, 2, 3]).is_ok());
+        assert!(matches!(
+            signature.check_memory(&[1, 2, 4]),
+            Err(ConformanceError::MemoryMismatch {
+                index: 2,
+                addr: 0x1008,
+                expected: 4,
+                actual: 3
+            })
+        ));
+        assert!(matches!(
+            signature.check_memory(&[1, 2]),
+            Err(ConformanceError::LengthMismatch { expected: 2, actual: 3 })
+        ));
+        assert!(matches!(
+            signature.check_registers(&[(3, 1)]),
+            Err(ConformanceError::RegisterMismatch { register: 3, expected: 1, actual: 0 })
+        ));
+    }
+
+    /// Run the riscv-arch-test suite built with `make -C crates/core/executor/conformance`.
+    #[test]
+    fn rv32im_conformance() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("conformance/rv32im");
+        assert!(
+            dir.exists(),
+            "{} is missing, build it with `make -C crates/core/executor/conformance`",
+            dir.display()
+        );
+        let results = run_conformance_suite(&dir).unwrap();
+        assert!(!results.is_empty(), "{} has no tests", dir.display());
+        let failures = results.iter().filter(|result| result.result.is_err()).collect::<Vec<_>>();
+        for failure in &failures {
+            eprintln!("{failure}");
+        }
+        assert!(failures.is_empty(), "{} of {} tests failed", failures.len(), results.len());
+    }
+}
@@ -1,11 +1,21 @@
 use core::mem::take;
-use std::sync::Arc;
+use std::{
+    future::Future,
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use hashbrown::HashMap;
+use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
-    hook::{hookify, BoxedHook, HookEnv, HookRegistry},
+    hook::{hookify, hookify_async, typed_hook, BoxedHook, HookEnv, HookRegistry},
+    input::{BoxedInputProvider, InputProvider},
+    output::OutputSink,
+    progress::{CancellationToken, ProgressListener, ProverEvent, ProverObserver},
     subproof::SubproofVerifier,
+    syscalls::{Syscall, SyscallCode},
 };
 
 /// Context to run a program inside SP1.
@@ -21,6 +31,31 @@ pub struct SP1Context<'a> {
 
     /// The maximum number of cpu cycles to use for execution.
     pub max_cycles: Option<u64>,
+
+    /// Reports progress and tracks cancellation and the deadline of the proving request.
+    pub observer: ProverObserver<'a>,
+
+    /// The syscalls registered on the host, by one of the reserved codes [`SyscallCode::HOST_0`]
+    /// to [`SyscallCode::HOST_3`].
+    ///
+    /// A program that invokes one of them can be executed, but not proven.
+    pub syscalls: HashMap<SyscallCode, Arc<dyn Syscall>>,
+
+    /// The source of input items once the input stream has been read to the end.
+    pub input_provider: Option<BoxedInputProvider<'a>>,
+
+    /// Where the program's stdout goes.
+    ///
+    /// Note: `None` prints it to the host's stdout, each line prefixed with `stdout:`.
+    pub stdout: Option<OutputSink<'a>>,
+
+    /// Where the program's stderr goes.
+    ///
+    /// Note: `None` prints it to the host's stdout, each line prefixed with `stderr:`.
+    pub stderr: Option<OutputSink<'a>>,
+
+    /// Whether to also keep the program's stdout and stderr in the [`crate::ExecutionReport`].
+    pub capture_output: bool,
 }
 
 /// A builder for [`SP1Context`].
@@ -30,6 +65,14 @@ pub struct SP1ContextBuilder<'a> {
     hook_registry_entries: Vec<(u32, BoxedHook<'a>)>,
     subproof_verifier: Option<Arc<dyn SubproofVerifier + 'a>>,
     max_cycles: Option<u64>,
+    progress_listener: Option<ProgressListener<'a>>,
+    cancellation_token: Option<CancellationToken>,
+    deadline: Option<Instant>,
+    syscalls: HashMap<SyscallCode, Arc<dyn Syscall>>,
+    input_provider: Option<BoxedInputProvider<'a>>,
+    stdout: Option<OutputSink<'a>>,
+    stderr: Option<OutputSink<'a>>,
+    capture_output: bool,
 }
 
 impl<'a> SP1Context<'a> {
@@ -38,6 +81,13 @@ impl<'a> SP1Context<'a> {
     pub fn builder() -> SP1ContextBuilder<'a> {
         SP1ContextBuilder::new()
     }
+
+    /// Get a [`ProverObserver`] sharing this context's progress listener, cancellation token,
+    /// deadline and statistics.
+    #[must_use]
+    pub fn observer(&self) -> ProverObserver<'a> {
+        self.observer.clone()
+    }
 }
 
 impl<'a> SP1ContextBuilder<'a> {
@@ -68,7 +118,25 @@ impl<'a> SP1ContextBuilder<'a> {
             });
         let subproof_verifier = take(&mut self.subproof_verifier);
         let cycle_limit = take(&mut self.max_cycles);
-        SP1Context { hook_registry, subproof_verifier, max_cycles: cycle_limit }
+        let progress_listener = take(&mut self.progress_listener);
+        let cancellation_token = take(&mut self.cancellation_token);
+        let deadline = take(&mut self.deadline);
+        let syscalls = take(&mut self.syscalls);
+        let input_provider = take(&mut self.input_provider);
+        let stdout = take(&mut self.stdout);
+        let stderr = take(&mut self.stderr);
+        let capture_output = take(&mut self.capture_output);
+        SP1Context {
+            hook_registry,
+            subproof_verifier,
+            max_cycles: cycle_limit,
+            observer: ProverObserver::new(progress_listener, cancellation_token, deadline),
+            syscalls,
+            input_provider,
+            stdout,
+            stderr,
+            capture_output,
+        }
     }
 
     /// Add a runtime [Hook](super::Hook) into the context.
@@ -85,6 +153,46 @@ impl<'a> SP1ContextBuilder<'a> {
         self
     }
 
+    /// Handle the syscall `code` on the host with `syscall`.
+    ///
+    /// This is meant for execution only, like simulations, gas estimation or test doubles: the
+    /// guest can invoke the reserved codes [`SyscallCode::HOST_0`] to [`SyscallCode::HOST_3`].
+    /// Proving a program that invokes a syscall registered here fails with
+    /// [`crate::ExecutionError::UnprovableSyscall`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `code` is not one of the reserved codes, as the other syscalls are part of the
+    /// zkVM and can't be replaced.
+    pub fn syscall(&mut self, code: SyscallCode, syscall: Arc<dyn Syscall>) -> &mut Self {
+        assert!(code.is_host(), "{code} is not reserved for a syscall registered on the host");
+        self.syscalls.insert(code, syscall);
+        self
+    }
+
+    /// Add a runtime hook whose result is computed by a future, like a query to a database.
+    ///
+    /// See [`hookify_async`] for how the future is run.
+    pub fn async_hook<Fut: Future<Output = Vec<Vec<u8>>> + 'a>(
+        &mut self,
+        fd: u32,
+        f: impl FnMut(HookEnv, &[u8]) -> Fut + Send + Sync + 'a,
+    ) -> &mut Self {
+        self.hook_registry_entries.push((fd, hookify_async(f)));
+        self
+    }
+
+    /// Add a runtime hook from a deserializable request to a serializable response.
+    ///
+    /// The hook may be called from within SP1 with `sp1_zkvm::io::hook`, using the same types.
+    pub fn typed_hook<Req: DeserializeOwned + 'a, Resp: Serialize + 'a>(
+        &mut self,
+        fd: u32,
+        f: impl FnMut(HookEnv, Req) -> Resp + Send + Sync + 'a,
+    ) -> &mut Self {
+        self.hook(fd, typed_hook(f))
+    }
+
     /// Avoid registering the default hooks in the runtime.
     ///
     /// It is not necessary to call this to override hooks --- instead, simply
@@ -94,6 +202,33 @@ impl<'a> SP1ContextBuilder<'a> {
         self
     }
 
+    /// Pull the input items that come after the ones in `SP1Stdin` from `provider`.
+    ///
+    /// Items are pulled when the guest has read all of the input written so far, and those
+    /// pulled while proving are recorded in the checkpoints, so they are only pulled once.
+    pub fn input_provider(&mut self, provider: impl InputProvider + 'a) -> &mut Self {
+        self.input_provider = Some(Arc::new(Mutex::new(provider)));
+        self
+    }
+
+    /// Write the program's stdout to `sink` instead of printing it.
+    pub fn stdout(&mut self, sink: impl Write + Send + 'a) -> &mut Self {
+        self.stdout = Some(Arc::new(Mutex::new(sink)));
+        self
+    }
+
+    /// Write the program's stderr to `sink` instead of printing it.
+    pub fn stderr(&mut self, sink: impl Write + Send + 'a) -> &mut Self {
+        self.stderr = Some(Arc::new(Mutex::new(sink)));
+        self
+    }
+
+    /// Also keep the program's stdout and stderr in the [`crate::ExecutionReport`].
+    pub fn capture_output(&mut self) -> &mut Self {
+        self.capture_output = true;
+        self
+    }
+
     /// Add a subproof verifier.
     ///
     /// The verifier is used to sanity check `verify_sp1_proof` during runtime.
@@ -110,21 +245,60 @@ impl<'a> SP1ContextBuilder<'a> {
         self.max_cycles = Some(max_cycles);
         self
     }
+
+    /// Set a listener that is called with every [`ProverEvent`] emitted while proving.
+    pub fn on_progress(&mut self, f: impl Fn(ProverEvent) + Send + Sync + 'a) -> &mut Self {
+        self.progress_listener = Some(Arc::new(f));
+        self
+    }
+
+    /// Set the token used to cancel proving.
+    pub fn cancellation_token(&mut self, token: CancellationToken) -> &mut Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Set the instant after which proving stops with a timeout.
+    pub fn deadline(&mut self, deadline: Instant) -> &mut Self {
+        self.deadline = Some(deadline);
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
-    use crate::{subproof::DefaultSubproofVerifier, SP1Context};
+    use crate::{
+        subproof::DefaultSubproofVerifier,
+        syscalls::{default_syscall_map, SyscallCode},
+        CancellationToken, SP1Context,
+    };
 
     #[test]
     fn defaults() {
-        let SP1Context { hook_registry, subproof_verifier, max_cycles: cycle_limit } =
-            SP1Context::builder().build();
+        let SP1Context {
+            hook_registry,
+            subproof_verifier,
+            max_cycles: cycle_limit,
+            observer,
+            syscalls,
+            input_provider,
+            stdout,
+            stderr,
+            capture_output,
+        } = SP1Context::builder().build();
         assert!(hook_registry.is_none());
         assert!(subproof_verifier.is_none());
         assert!(cycle_limit.is_none());
+        assert!(observer.listener.is_none());
+        assert!(observer.cancellation_token.is_none());
+        assert!(observer.deadline.is_none());
+        assert!(syscalls.is_empty());
+        assert!(input_provider.is_none());
+        assert!(stdout.is_none());
+        assert!(stderr.is_none());
+        assert!(!capture_output);
     }
 
     #[test]
@@ -155,4 +329,28 @@ mod tests {
             .build();
         assert!(subproof_verifier.is_some());
     }
+
+    #[test]
+    fn with_custom_syscall() {
+        let halt = default_syscall_map()[&SyscallCode::HALT].clone();
+        let SP1Context { syscalls, .. } =
+            SP1Context::builder().syscall(SyscallCode::HOST_0, halt).build();
+        assert_eq!(&syscalls.into_keys().collect::<Vec<_>>(), &[SyscallCode::HOST_0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "HALT is not reserved for a syscall registered on the host")]
+    fn rejects_core_syscall() {
+        let halt = default_syscall_map()[&SyscallCode::HALT].clone();
+        SP1Context::builder().syscall(SyscallCode::HALT, halt);
+    }
+
+    #[test]
+    fn cancellation_token() {
+        let token = CancellationToken::new();
+        let context = SP1Context::builder().cancellation_token(token.clone()).build();
+        assert!(!context.observer().is_cancelled());
+        token.cancel();
+        assert!(context.observer().is_cancelled());
+    }
 }
@@ -0,0 +1,274 @@
+use elf::{
+    abi::{SHF_ALLOC, SHF_EXECINSTR, STB_LOCAL, STT_FUNC, STT_NOTYPE},
+    endian::LittleEndian,
+    ElfBytes,
+};
+use gimli::{EndianSlice, SectionId};
+use hashbrown::HashMap;
+
+use crate::ElfError;
+
+/// A function of a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// The name of the symbol, as it appears in the ELF.
+    pub name: String,
+    /// The address of the first instruction of the function.
+    pub address: u32,
+    /// The size of the function in bytes, zero if the ELF doesn't record it.
+    pub size: u32,
+}
+
+impl Symbol {
+    /// The demangled name of the symbol, without the hash suffix of Rust symbols.
+    #[must_use]
+    pub fn demangled(&self) -> String {
+        format!("{:#}", rustc_demangle::demangle(&self.name))
+    }
+}
+
+/// A section of a program that is loaded into memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// The name of the section, like `.text` or `.rodata`.
+    pub name: String,
+    /// The address of the section.
+    pub address: u32,
+    /// The size of the section in bytes.
+    pub size: u32,
+    /// Whether the section holds code.
+    pub executable: bool,
+}
+
+impl Section {
+    /// Whether the section contains the address.
+    #[must_use]
+    pub fn contains(&self, addr: u32) -> bool {
+        addr.wrapping_sub(self.address) < self.size
+    }
+}
+
+/// A source file and line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation<'a> {
+    /// The path of the source file.
+    pub file: &'a str,
+    /// The line in the source file, zero if unknown.
+    pub line: u32,
+}
+
+/// A row of the DWARF line table: the instructions from `address` up to the next row are from
+/// the given file and line, or from nowhere if `file` is `None`.
+#[derive(Debug, Clone, Copy)]
+struct LineRow {
+    address: u32,
+    file: Option<u32>,
+    line: u32,
+}
+
+/// The symbols, sections and line table of an ELF, used to map addresses of a [`crate::Program`]
+/// back to functions and source code.
+#[derive(Debug, Clone, Default)]
+pub struct DebugInfo {
+    /// The functions, sorted by address.
+    functions: Vec<Symbol>,
+    /// The loaded sections, sorted by address.
+    sections: Vec<Section>,
+    /// The paths of the source files the line table refers to.
+    files: Vec<String>,
+    /// The rows of the line table, sorted by address.
+    lines: Vec<LineRow>,
+}
+
+impl DebugInfo {
+    /// Read the symbol table, section headers and DWARF line table of an ELF.
+    ///
+    /// Any of them may be missing, for example from a stripped ELF or one built without debug
+    /// info, in which case lookups into them return `None`.
+    pub fn decode(input: &[u8]) -> Result<Self, ElfError> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(input)?;
+
+        let mut sections = Vec::new();
+        if let (Some(headers), Some(strings)) = elf.section_headers_with_strtab()? {
+            for header in headers.iter().filter(|header| header.sh_flags & SHF_ALLOC as u64 != 0) {
+                sections.push(Section {
+                    name: strings.get(header.sh_name as usize)?.to_string(),
+                    address: header.sh_addr as u32,
+                    size: header.sh_size as u32,
+                    executable: header.sh_flags & SHF_EXECINSTR as u64 != 0,
+                });
+            }
+        }
+        sections.sort_by_key(|section| section.address);
+
+        // Assembly entrypoints like `_start` are global symbols without a type, so those count as
+        // functions when they are in code.
+        let mut functions = Vec::new();
+        if let Some((symbols, strings)) = elf.symbol_table()? {
+            for symbol in symbols.iter() {
+                let is_function = symbol.st_symtype() == STT_FUNC
+                    || (symbol.st_symtype() == STT_NOTYPE && symbol.st_bind() != STB_LOCAL);
+                let address = symbol.st_value as u32;
+                let in_code =
+                    sections.iter().any(|section| section.executable && section.contains(address));
+                let name = strings.get(symbol.st_name as usize)?;
+                if is_function && in_code && !name.is_empty() {
+                    functions.push(Symbol {
+                        name: name.to_string(),
+                        address,
+                        size: symbol.st_size as u32,
+                    });
+                }
+            }
+        }
+        functions.sort_by_key(|symbol| (symbol.address, symbol.size));
+
+        let mut info = Self { functions, sections, ..Self::default() };
+        info.read_lines(&elf)?;
+        Ok(info)
+    }
+
+    /// Read the line table from the DWARF sections of an ELF.
+    fn read_lines(&mut self, elf: &ElfBytes<'_, LittleEndian>) -> Result<(), ElfError> {
+        // Missing and compressed sections are read as empty, which leaves the line table empty.
+        let load = |id: SectionId| -> Result<_, ElfError> {
+            let data: &[u8] = match elf.section_header_by_name(id.name())? {
+                Some(header) => match elf.section_data(&header)? {
+                    (data, None) => data,
+                    (_, Some(_)) => &[],
+                },
+                None => &[],
+            };
+            Ok(EndianSlice::new(data, gimli::LittleEndian))
+        };
+        let dwarf = gimli::Dwarf::load(load)?;
+
+        let mut file_indices = HashMap::new();
+        let mut units = dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            let Some(program) = unit.line_program.clone() else { continue };
+            let mut rows = program.rows();
+            while let Some((header, row)) = rows.next_row()? {
+                let address = row.address() as u32;
+                if row.end_sequence() {
+                    self.lines.push(LineRow { address, file: None, line: 0 });
+                    continue;
+                }
+                let Some(file) = row.file(header) else { continue };
+                let mut path = match file.directory(header) {
+                    Some(directory) => {
+                        let directory = dwarf.attr_string(&unit, directory)?;
+                        format!("{}/", directory.to_string_lossy())
+                    }
+                    None => String::new(),
+                };
+                path.push_str(&dwarf.attr_string(&unit, file.path_name())?.to_string_lossy());
+
+                let next = self.files.len() as u32;
+                let file = *file_indices.entry(path).or_insert_with_key(|path| {
+                    self.files.push(path.clone());
+                    next
+                });
+                let line = row.line().map_or(0, |line| line.get() as u32);
+                self.lines.push(LineRow { address, file: Some(file), line });
+            }
+        }
+        // A sequence ends at the address the next one may start at, so the end has to come
+        // first.
+        self.lines.sort_by_key(|row| (row.address, row.file.is_some()));
+        Ok(())
+    }
+
+    /// The functions of the program, sorted by address.
+    #[must_use]
+    pub fn functions(&self) -> &[Symbol] {
+        &self.functions
+    }
+
+    /// The loaded sections of the program, sorted by address.
+    #[must_use]
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// The function containing `pc`.
+    ///
+    /// A function without a recorded size is assumed to extend up to the next one.
+    #[must_use]
+    pub fn function(&self, pc: u32) -> Option<&Symbol> {
+        let index = self.functions.partition_point(|symbol| symbol.address <= pc).checked_sub(1)?;
+        let symbol = &self.functions[index];
+        (symbol.size == 0 || pc - symbol.address < symbol.size).then_some(symbol)
+    }
+
+    /// The section containing `addr`.
+    #[must_use]
+    pub fn section(&self, addr: u32) -> Option<&Section> {
+        self.sections.iter().find(|section| section.contains(addr))
+    }
+
+    /// The source file and line of the instruction at `pc`.
+    #[must_use]
+    pub fn location(&self, pc: u32) -> Option<SourceLocation<'_>> {
+        let index = self.lines.partition_point(|row| row.address <= pc).checked_sub(1)?;
+        let row = self.lines[index];
+        Some(SourceLocation { file: &self.files[row.file? as usize], line: row.line })
+    }
+
+    /// Describe the code at `pc` as its function and source location, whichever are known.
+    #[must_use]
+    pub fn describe(&self, pc: u32) -> Option<String> {
+        let function = self.function(pc).map(Symbol::demangled);
+        let location =
+            self.location(pc).map(|location| format!("{}:{}", location.file, location.line));
+        match (function, location) {
+            (Some(function), Some(location)) => Some(format!("{function} at {location}")),
+            (function, location) => function.or(location),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SourceLocation;
+    use crate::{programs::tests::FIBONACCI_IO_ELF, Program};
+
+    /// A program assembled with a line table, see `fixtures/line-info/line_info.s`.
+    const LINE_INFO_ELF: &[u8] = include_bytes!("../fixtures/line-info/line_info.elf");
+
+    #[test]
+    fn resolves_functions_and_sections() {
+        let program = Program::from_with_debug_info(FIBONACCI_IO_ELF).unwrap();
+        let info = program.debug_info.as_deref().unwrap();
+
+        assert_eq!(info.function(program.pc_start).unwrap().name, "_start");
+        assert_eq!(program.describe_pc(program.pc_start).as_deref(), Some("_start"));
+
+        let main = info.functions().iter().find(|symbol| symbol.name == "main").unwrap();
+        assert_eq!(info.function(main.address + main.size - 4), Some(main));
+
+        assert_eq!(info.section(program.pc_start).unwrap().name, ".text");
+        assert!(info.sections().iter().any(|section| section.name == ".rodata"));
+
+        // The example is built without debug info, so there is no line table.
+        assert_eq!(info.location(program.pc_start), None);
+    }
+
+    #[test]
+    fn resolves_source_lines() {
+        let program = Program::from_with_debug_info(LINE_INFO_ELF).unwrap();
+        let info = program.debug_info.as_deref().unwrap();
+        let file = "/fixtures/line-info/line_info.s";
+
+        assert_eq!(info.location(program.pc_start), Some(SourceLocation { file, line: 7 }));
+        assert_eq!(info.location(program.pc_start + 8), Some(SourceLocation { file, line: 9 }));
+        assert_eq!(
+            program.describe_pc(program.pc_start + 16).as_deref(),
+            Some("_start at /fixtures/line-info/line_info.s:11")
+        );
+
+        // The line table ends after the last instruction.
+        assert_eq!(info.location(program.pc_start + 20), None);
+    }
+}
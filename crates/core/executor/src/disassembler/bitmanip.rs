@@ -0,0 +1,141 @@
+//! Decoding of the Zba and Zbb bit-manipulation instructions, which `rrs` doesn't know about.
+
+use crate::{Instruction, Opcode};
+
+/// The major opcode of register-register instructions.
+const OP: u32 = 0b011_0011;
+
+/// The major opcode of register-immediate instructions.
+const OP_IMM: u32 = 0b001_0011;
+
+/// Decode a Zba or Zbb instruction, or return `None` if the word encodes any other instruction.
+///
+/// Register-register instructions are decoded with `op_c` as the second source register, unary
+/// instructions with an immediate `op_c` of zero and `rori` as [`Opcode::ROR`] with the shift
+/// amount as its immediate.
+pub(crate) fn decode_bitmanip(word: u32) -> Option<Instruction> {
+    let opcode = word & 0x7f;
+    let rd = (word >> 7) & 0x1f;
+    let funct3 = (word >> 12) & 0x7;
+    let rs1 = (word >> 15) & 0x1f;
+    let rs2 = (word >> 20) & 0x1f;
+    let funct7 = word >> 25;
+
+    match opcode {
+        OP => {
+            let opcode = match (funct7, funct3) {
+                (0b010_0000, 0b111) => Opcode::ANDN,
+                (0b010_0000, 0b110) => Opcode::ORN,
+                (0b010_0000, 0b100) => Opcode::XNOR,
+                (0b000_0101, 0b110) => Opcode::MAX,
+                (0b000_0101, 0b111) => Opcode::MAXU,
+                (0b000_0101, 0b100) => Opcode::MIN,
+                (0b000_0101, 0b101) => Opcode::MINU,
+                (0b011_0000, 0b001) => Opcode::ROL,
+                (0b011_0000, 0b101) => Opcode::ROR,
+                (0b001_0000, 0b010) => Opcode::SH1ADD,
+                (0b001_0000, 0b100) => Opcode::SH2ADD,
+                (0b001_0000, 0b110) => Opcode::SH3ADD,
+                (0b000_0100, 0b100) if rs2 == 0 => {
+                    return Some(Instruction::new(Opcode::ZEXTH, rd, rs1, 0, false, true));
+                }
+                _ => return None,
+            };
+            Some(Instruction::new(opcode, rd, rs1, rs2, false, false))
+        }
+        OP_IMM => {
+            let imm = word >> 20;
+            let opcode = match (funct3, imm) {
+                (0b001, 0x600) => Opcode::CLZ,
+                (0b001, 0x601) => Opcode::CTZ,
+                (0b001, 0x602) => Opcode::CPOP,
+                (0b001, 0x604) => Opcode::SEXTB,
+                (0b001, 0x605) => Opcode::SEXTH,
+                (0b101, 0x287) => Opcode::ORCB,
+                (0b101, 0x698) => Opcode::REV8,
+                (0b101, _) if funct7 == 0b011_0000 => {
+                    return Some(Instruction::new(Opcode::ROR, rd, rs1, rs2, false, true));
+                }
+                _ => return None,
+            };
+            Some(Instruction::new(opcode, rd, rs1, 0, false, true))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn r_type(funct7: u32, rs2: u32, rs1: u32, funct3: u32, rd: u32) -> u32 {
+        (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | OP
+    }
+
+    const fn i_type(imm: u32, rs1: u32, funct3: u32, rd: u32) -> u32 {
+        (imm << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | OP_IMM
+    }
+
+    /// The opcode, operands and immediate flags of a decoded instruction.
+    fn decode(word: u32) -> Option<(Opcode, u32, u32, u32, bool, bool)> {
+        decode_bitmanip(word).map(|i| (i.opcode, i.op_a, i.op_b, i.op_c, i.imm_b, i.imm_c))
+    }
+
+    #[test]
+    fn decodes_register_instructions() {
+        let cases = [
+            (r_type(0b010_0000, 3, 2, 0b111, 1), Opcode::ANDN),
+            (r_type(0b010_0000, 3, 2, 0b110, 1), Opcode::ORN),
+            (r_type(0b010_0000, 3, 2, 0b100, 1), Opcode::XNOR),
+            (r_type(0b000_0101, 3, 2, 0b110, 1), Opcode::MAX),
+            (r_type(0b000_0101, 3, 2, 0b111, 1), Opcode::MAXU),
+            (r_type(0b000_0101, 3, 2, 0b100, 1), Opcode::MIN),
+            (r_type(0b000_0101, 3, 2, 0b101, 1), Opcode::MINU),
+            (r_type(0b011_0000, 3, 2, 0b001, 1), Opcode::ROL),
+            (r_type(0b011_0000, 3, 2, 0b101, 1), Opcode::ROR),
+            (r_type(0b001_0000, 3, 2, 0b010, 1), Opcode::SH1ADD),
+            (r_type(0b001_0000, 3, 2, 0b100, 1), Opcode::SH2ADD),
+            (r_type(0b001_0000, 3, 2, 0b110, 1), Opcode::SH3ADD),
+        ];
+        for (word, opcode) in cases {
+            assert_eq!(decode(word), Some((opcode, 1, 2, 3, false, false)), "{word:#010x}");
+        }
+    }
+
+    #[test]
+    fn decodes_unary_instructions() {
+        // clz a0, a1
+        assert_eq!(decode(0x6005_9513), Some((Opcode::CLZ, 10, 11, 0, false, true)));
+        let cases = [
+            (i_type(0x601, 2, 0b001, 1), Opcode::CTZ),
+            (i_type(0x602, 2, 0b001, 1), Opcode::CPOP),
+            (i_type(0x604, 2, 0b001, 1), Opcode::SEXTB),
+            (i_type(0x605, 2, 0b001, 1), Opcode::SEXTH),
+            (i_type(0x287, 2, 0b101, 1), Opcode::ORCB),
+            (i_type(0x698, 2, 0b101, 1), Opcode::REV8),
+            (r_type(0b000_0100, 0, 2, 0b100, 1), Opcode::ZEXTH),
+        ];
+        for (word, opcode) in cases {
+            assert_eq!(decode(word), Some((opcode, 1, 2, 0, false, true)), "{word:#010x}");
+        }
+        assert_eq!(
+            decode(i_type(0x600 | 7, 2, 0b101, 1)),
+            Some((Opcode::ROR, 1, 2, 7, false, true))
+        );
+    }
+
+    #[test]
+    fn ignores_base_instructions() {
+        // add, sub, mul, slli, srai and addi.
+        for word in [
+            r_type(0, 3, 2, 0b000, 1),
+            r_type(0b010_0000, 3, 2, 0b000, 1),
+            r_type(0b000_0001, 3, 2, 0b000, 1),
+            i_type(7, 2, 0b001, 1),
+            i_type(0x400 | 7, 2, 0b101, 1),
+            i_type(0x123, 2, 0b000, 1),
+        ] {
+            assert_eq!(decode(word), None, "{word:#010x}");
+        }
+    }
+}
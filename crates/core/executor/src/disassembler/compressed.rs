@@ -0,0 +1,301 @@
+//! Expansion of 16-bit compressed (RVC) instructions into their 32-bit RV32IM equivalents.
+//!
+//! Reference: The RISC-V Instruction Set Manual, Volume I, "C" Standard Extension for Compressed
+//! Instructions.
+
+/// The canonical encoding of `unimp` (`csrrw x0, cycle, x0`), used for illegal and unsupported
+/// compressed instructions.
+pub(crate) const UNIMP_ENCODING: u32 = 0xc000_1073;
+
+const OPCODE_LOAD: u32 = 0b000_0011;
+const OPCODE_OP_IMM: u32 = 0b001_0011;
+const OPCODE_STORE: u32 = 0b010_0011;
+const OPCODE_OP: u32 = 0b011_0011;
+const OPCODE_LUI: u32 = 0b011_0111;
+const OPCODE_BRANCH: u32 = 0b110_0011;
+const OPCODE_JALR: u32 = 0b110_0111;
+const OPCODE_JAL: u32 = 0b110_1111;
+const EBREAK_ENCODING: u32 = 0x0010_0073;
+
+/// The stack pointer and the link register.
+const SP: u32 = 2;
+const RA: u32 = 1;
+
+/// Whether a halfword is the first halfword of a compressed instruction.
+#[must_use]
+pub(crate) const fn is_compressed(halfword: u16) -> bool {
+    halfword & 0b11 != 0b11
+}
+
+/// Split a stream of little-endian code words into instructions encoded as 32 bits, expanding the
+/// compressed ones, along with whether each instruction was compressed.
+///
+/// A trailing halfword that starts a 32-bit instruction is dropped.
+#[must_use]
+pub(crate) fn expand_code(words: &[u32]) -> (Vec<u32>, Vec<bool>) {
+    let halfwords =
+        words.iter().flat_map(|word| [*word as u16, (word >> 16) as u16]).collect::<Vec<_>>();
+    let mut instructions = Vec::new();
+    let mut compressed = Vec::new();
+    let mut i = 0;
+    while i < halfwords.len() {
+        if is_compressed(halfwords[i]) {
+            instructions.push(expand(halfwords[i]).unwrap_or(UNIMP_ENCODING));
+            compressed.push(true);
+            i += 1;
+        } else {
+            let Some(&high) = halfwords.get(i + 1) else { break };
+            instructions.push(u32::from(halfwords[i]) | (u32::from(high) << 16));
+            compressed.push(false);
+            i += 2;
+        }
+    }
+    (instructions, compressed)
+}
+
+/// Expand a compressed RV32C instruction into the 32-bit instruction it stands for.
+///
+/// Returns `None` for illegal encodings, reserved encodings, and the floating-point instructions,
+/// which SP1 doesn't support.
+#[must_use]
+pub(crate) fn expand(halfword: u16) -> Option<u32> {
+    let inst = u32::from(halfword);
+    let funct3 = bits(inst, 15, 13);
+    // The full register fields, and the 3-bit fields of x8 to x15.
+    let rd = bits(inst, 11, 7);
+    let rs2 = bits(inst, 6, 2);
+    let rd_prime = bits(inst, 4, 2) + 8;
+    let rs1_prime = bits(inst, 9, 7) + 8;
+
+    match (inst & 0b11, funct3) {
+        // C.ADDI4SPN: addi rd', x2, nzuimm.
+        (0b00, 0b000) => {
+            let imm = (bits(inst, 12, 11) << 4)
+                | (bits(inst, 10, 7) << 6)
+                | (bits(inst, 6, 6) << 2)
+                | (bits(inst, 5, 5) << 3);
+            (imm != 0).then(|| i_type(OPCODE_OP_IMM, rd_prime, 0b000, SP, imm))
+        }
+        // C.LW: lw rd', uimm(rs1').
+        (0b00, 0b010) => Some(i_type(OPCODE_LOAD, rd_prime, 0b010, rs1_prime, lw_sw_offset(inst))),
+        // C.SW: sw rs2', uimm(rs1').
+        (0b00, 0b110) => Some(s_type(0b010, rs1_prime, rd_prime, lw_sw_offset(inst))),
+        // C.ADDI: addi rd, rd, imm, which is C.NOP when rd is x0.
+        (0b01, 0b000) => Some(i_type(OPCODE_OP_IMM, rd, 0b000, rd, imm6(inst))),
+        // C.JAL: jal x1, offset.
+        (0b01, 0b001) => Some(j_type(RA, jump_offset(inst))),
+        // C.LI: addi rd, x0, imm.
+        (0b01, 0b010) => Some(i_type(OPCODE_OP_IMM, rd, 0b000, 0, imm6(inst))),
+        // C.ADDI16SP: addi x2, x2, nzimm.
+        (0b01, 0b011) if rd == SP => {
+            let imm = sign_extend(
+                (bits(inst, 12, 12) << 9)
+                    | (bits(inst, 6, 6) << 4)
+                    | (bits(inst, 5, 5) << 6)
+                    | (bits(inst, 4, 3) << 7)
+                    | (bits(inst, 2, 2) << 5),
+                10,
+            );
+            (imm != 0).then(|| i_type(OPCODE_OP_IMM, SP, 0b000, SP, imm))
+        }
+        // C.LUI: lui rd, nzimm.
+        (0b01, 0b011) => {
+            let imm = sign_extend((bits(inst, 12, 12) << 17) | (bits(inst, 6, 2) << 12), 18);
+            (imm != 0 && rd != 0).then_some((imm & 0xffff_f000) | (rd << 7) | OPCODE_LUI)
+        }
+        // C.SRLI, C.SRAI, C.ANDI, C.SUB, C.XOR, C.OR and C.AND on rd'.
+        (0b01, 0b100) => {
+            let shamt = bits(inst, 6, 2);
+            match bits(inst, 11, 10) {
+                // Shift amounts of 32 and above are reserved on RV32.
+                0b00 if bits(inst, 12, 12) == 0 => {
+                    Some(i_type(OPCODE_OP_IMM, rs1_prime, 0b101, rs1_prime, shamt))
+                }
+                0b01 if bits(inst, 12, 12) == 0 => {
+                    Some(i_type(OPCODE_OP_IMM, rs1_prime, 0b101, rs1_prime, shamt | 0b0100000 << 5))
+                }
+                0b10 => Some(i_type(OPCODE_OP_IMM, rs1_prime, 0b111, rs1_prime, imm6(inst))),
+                // The word variants with bit 12 set only exist on RV64.
+                0b11 if bits(inst, 12, 12) == 0 => {
+                    let (funct7, funct3) = match bits(inst, 6, 5) {
+                        0b00 => (0b0100000, 0b000),
+                        0b01 => (0, 0b100),
+                        0b10 => (0, 0b110),
+                        _ => (0, 0b111),
+                    };
+                    Some(r_type(funct7, rs1_prime, rs1_prime, funct3, rd_prime))
+                }
+                _ => None,
+            }
+        }
+        // C.J: jal x0, offset.
+        (0b01, 0b101) => Some(j_type(0, jump_offset(inst))),
+        // C.BEQZ and C.BNEZ: beq/bne rs1', x0, offset.
+        (0b01, 0b110 | 0b111) => {
+            let offset = sign_extend(
+                (bits(inst, 12, 12) << 8)
+                    | (bits(inst, 11, 10) << 3)
+                    | (bits(inst, 6, 5) << 6)
+                    | (bits(inst, 4, 3) << 1)
+                    | (bits(inst, 2, 2) << 5),
+                9,
+            );
+            Some(b_type(funct3 & 1, rs1_prime, 0, offset))
+        }
+        // C.SLLI: slli rd, rd, shamt.
+        (0b10, 0b000) if bits(inst, 12, 12) == 0 => Some(i_type(OPCODE_OP_IMM, rd, 0b001, rd, rs2)),
+        // C.LWSP: lw rd, uimm(x2).
+        (0b10, 0b010) if rd != 0 => {
+            let offset =
+                (bits(inst, 12, 12) << 5) | (bits(inst, 6, 4) << 2) | (bits(inst, 3, 2) << 6);
+            Some(i_type(OPCODE_LOAD, rd, 0b010, SP, offset))
+        }
+        (0b10, 0b100) => match (bits(inst, 12, 12), rd, rs2) {
+            // C.JR: jalr x0, 0(rs1).
+            (0, rs1, 0) if rs1 != 0 => Some(i_type(OPCODE_JALR, 0, 0b000, rs1, 0)),
+            // C.MV: add rd, x0, rs2.
+            (0, rd, rs2) if rs2 != 0 => Some(r_type(0, rd, 0, 0b000, rs2)),
+            // C.EBREAK.
+            (1, 0, 0) => Some(EBREAK_ENCODING),
+            // C.JALR: jalr x1, 0(rs1).
+            (1, rs1, 0) => Some(i_type(OPCODE_JALR, RA, 0b000, rs1, 0)),
+            // C.ADD: add rd, rd, rs2.
+            (1, rd, rs2) => Some(r_type(0, rd, rd, 0b000, rs2)),
+            _ => None,
+        },
+        // C.SWSP: sw rs2, uimm(x2).
+        (0b10, 0b110) => {
+            let offset = (bits(inst, 12, 9) << 2) | (bits(inst, 8, 7) << 6);
+            Some(s_type(0b010, SP, rs2, offset))
+        }
+        _ => None,
+    }
+}
+
+/// The bits `high..=low` of `value`, shifted down to bit 0.
+const fn bits(value: u32, high: u32, low: u32) -> u32 {
+    (value >> low) & ((1 << (high - low + 1)) - 1)
+}
+
+/// Sign-extend the lowest `width` bits of `value`.
+const fn sign_extend(value: u32, width: u32) -> u32 {
+    let shift = 32 - width;
+    (((value << shift) as i32) >> shift) as u32
+}
+
+/// The sign-extended 6-bit immediate of C.ADDI, C.LI and C.ANDI.
+const fn imm6(inst: u32) -> u32 {
+    sign_extend((bits(inst, 12, 12) << 5) | bits(inst, 6, 2), 6)
+}
+
+/// The unsigned offset of C.LW and C.SW.
+const fn lw_sw_offset(inst: u32) -> u32 {
+    (bits(inst, 12, 10) << 3) | (bits(inst, 6, 6) << 2) | (bits(inst, 5, 5) << 6)
+}
+
+/// The sign-extended offset of C.J and C.JAL.
+const fn jump_offset(inst: u32) -> u32 {
+    sign_extend(
+        (bits(inst, 12, 12) << 11)
+            | (bits(inst, 11, 11) << 4)
+            | (bits(inst, 10, 9) << 8)
+            | (bits(inst, 8, 8) << 10)
+            | (bits(inst, 7, 7) << 6)
+            | (bits(inst, 6, 6) << 7)
+            | (bits(inst, 5, 3) << 1)
+            | (bits(inst, 2, 2) << 5),
+        12,
+    )
+}
+
+const fn i_type(opcode: u32, rd: u32, funct3: u32, rs1: u32, imm: u32) -> u32 {
+    ((imm & 0xfff) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+const fn s_type(funct3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    (bits(imm, 11, 5) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (bits(imm, 4, 0) << 7)
+        | OPCODE_STORE
+}
+
+const fn r_type(funct7: u32, rd: u32, rs1: u32, funct3: u32, rs2: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | OPCODE_OP
+}
+
+const fn b_type(funct3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    (bits(imm, 12, 12) << 31)
+        | (bits(imm, 10, 5) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (bits(imm, 4, 1) << 8)
+        | (bits(imm, 11, 11) << 7)
+        | OPCODE_BRANCH
+}
+
+const fn j_type(rd: u32, imm: u32) -> u32 {
+    (bits(imm, 20, 20) << 31)
+        | (bits(imm, 10, 1) << 21)
+        | (bits(imm, 11, 11) << 20)
+        | (bits(imm, 19, 12) << 12)
+        | (rd << 7)
+        | OPCODE_JAL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_compressed_instructions() {
+        // c.addi a0, 1 => addi a0, a0, 1
+        assert_eq!(expand(0x0505), Some(0x0015_0513));
+        // c.li a5, -1 => addi a5, x0, -1
+        assert_eq!(expand(0x57fd), Some(0xfff0_0793));
+        // c.mv a0, a1 => add a0, x0, a1
+        assert_eq!(expand(0x852e), Some(0x00b0_0533));
+        // c.add a0, a1 => add a0, a0, a1
+        assert_eq!(expand(0x952e), Some(0x00b5_0533));
+        // c.lw a0, 4(a1) => lw a0, 4(a1)
+        assert_eq!(expand(0x41c8), Some(0x0045_a503));
+        // c.sw a0, 4(a1) => sw a0, 4(a1)
+        assert_eq!(expand(0xc1c8), Some(0x00a5_a223));
+        // c.lwsp ra, 12(sp) => lw ra, 12(sp)
+        assert_eq!(expand(0x40b2), Some(0x00c1_2083));
+        // c.swsp ra, 12(sp) => sw ra, 12(sp)
+        assert_eq!(expand(0xc606), Some(0x0011_2623));
+        // c.addi16sp sp, -32 => addi sp, sp, -32
+        assert_eq!(expand(0x1101), Some(0xfe01_0113));
+        // c.addi4spn a0, sp, 8 => addi a0, sp, 8
+        assert_eq!(expand(0x0028), Some(0x0081_0513));
+        // c.slli a0, 2 => slli a0, a0, 2
+        assert_eq!(expand(0x050a), Some(0x0025_1513));
+        // c.srai a0, 2 => srai a0, a0, 2
+        assert_eq!(expand(0x8509), Some(0x4025_5513));
+        // c.sub a0, a1 => sub a0, a0, a1
+        assert_eq!(expand(0x8d0d), Some(0x40b5_0533));
+        // c.jr ra => jalr x0, 0(ra)
+        assert_eq!(expand(0x8082), Some(0x0000_8067));
+        // c.j -4 => jal x0, -4
+        assert_eq!(expand(0xbff5), Some(0xffdf_f06f));
+        // c.beqz a0, 8 => beq a0, x0, 8
+        assert_eq!(expand(0xc501), Some(0x0005_0463));
+        // c.ebreak
+        assert_eq!(expand(0x9002), Some(EBREAK_ENCODING));
+        // The all-zero halfword is illegal.
+        assert_eq!(expand(0x0000), None);
+    }
+
+    #[test]
+    fn splits_mixed_code() {
+        // c.li a0, 1; addi a1, x0, 2; c.mv a2, a0, with the last halfword padded by c.nop.
+        let words = [0x0593_4505, 0x862a_0020, 0x0000_0001];
+        let (instructions, compressed) = expand_code(&words);
+        assert_eq!(compressed, vec![true, false, true, true, true]);
+        assert_eq!(instructions[1], 0x0020_0593);
+        assert_eq!(instructions[2], 0x00a0_0633);
+    }
+}
@@ -1,13 +1,148 @@
-use std::cmp::min;
+use std::{cmp::min, ops::Range};
 
 use elf::{
-    abi::{EM_RISCV, ET_EXEC, PF_X, PT_LOAD},
+    abi::{EM_RISCV, ET_EXEC, PF_W, PF_X, PT_LOAD},
     endian::LittleEndian,
     file::Class,
-    ElfBytes,
+    segment::ProgramHeader,
+    ElfBytes, ParseError,
 };
 use hashbrown::HashMap;
-use sp1_primitives::consts::{MAXIMUM_MEMORY_SIZE, WORD_SIZE};
+use sp1_primitives::consts::{MAXIMUM_MEMORY_SIZE, STACK_TOP, WORD_SIZE};
+use thiserror::Error;
+
+use super::compressed::expand_code;
+
+/// The ELF header flag set when the code may contain compressed instructions.
+const EF_RISCV_RVC: u32 = 0x0001;
+
+/// An error that makes an ELF unloadable by the zkVM.
+#[derive(Error, Debug)]
+pub enum ElfError {
+    /// The bytes are not a well-formed ELF.
+    #[error("failed to parse the ELF: {0}")]
+    Parse(#[from] ParseError),
+
+    /// The DWARF debug info of the ELF is malformed.
+    #[error("failed to read the debug info: {0}")]
+    Dwarf(#[from] gimli::Error),
+
+    /// The ELF is not a 32-bit ELF.
+    #[error("must be a 32-bit ELF")]
+    Not32Bit,
+
+    /// The ELF is for another machine than RISC-V.
+    #[error("must be a RISC-V ELF, got machine {0:#x}")]
+    NotRiscv(u16),
+
+    /// The ELF is not an executable.
+    #[error("must be an executable ELF, got type {0:#x}")]
+    NotExecutable(u16),
+
+    /// A header value doesn't fit in the 32-bit address space.
+    #[error("{field} {value:#x} does not fit in 32 bits")]
+    ValueOutOfRange {
+        /// The name of the header field.
+        field: &'static str,
+        /// The value of the header field.
+        value: u64,
+    },
+
+    /// The entrypoint is misaligned or not in an executable segment.
+    #[error("invalid entrypoint {0:#010x}")]
+    InvalidEntrypoint(u32),
+
+    /// The ELF has no program headers.
+    #[error("the ELF has no program headers")]
+    MissingSegments,
+
+    /// The ELF has more program headers than the loader accepts.
+    #[error("the ELF has {0} program headers, at most 256 are supported")]
+    TooManySegments(usize),
+
+    /// A segment doesn't start on a word boundary.
+    #[error("segment at {vaddr:#010x} is not word aligned")]
+    UnalignedSegment {
+        /// The address of the segment.
+        vaddr: u32,
+    },
+
+    /// The memory size of a segment is not a whole number of words, or of halfwords for
+    /// compressed code.
+    #[error("segment at {vaddr:#010x} has a memory size of {mem_size:#x}, which is misaligned")]
+    UnalignedMemSize {
+        /// The address of the segment.
+        vaddr: u32,
+        /// The memory size of the segment.
+        mem_size: u32,
+    },
+
+    /// A segment has more bytes in the file than in memory.
+    #[error("segment at {vaddr:#010x} has a file size of {file_size:#x}, larger than its memory size of {mem_size:#x}")]
+    FileSizeExceedsMemSize {
+        /// The address of the segment.
+        vaddr: u32,
+        /// The file size of the segment.
+        file_size: u32,
+        /// The memory size of the segment.
+        mem_size: u32,
+    },
+
+    /// A segment, including its bss, extends past the end of the address space.
+    #[error("segment at {vaddr:#010x} with a memory size of {mem_size:#x} extends past the maximum address {max:#010x}", max = MAXIMUM_MEMORY_SIZE)]
+    SegmentOutOfRange {
+        /// The address of the segment.
+        vaddr: u32,
+        /// The memory size of the segment.
+        mem_size: u32,
+    },
+
+    /// The file bytes of a segment extend past the end of the ELF.
+    #[error("segment at {vaddr:#010x} reads {file_size:#x} bytes at offset {offset:#x}, past the end of the file")]
+    SegmentPastEndOfFile {
+        /// The address of the segment.
+        vaddr: u32,
+        /// The offset of the segment in the file.
+        offset: u32,
+        /// The file size of the segment.
+        file_size: u32,
+    },
+
+    /// Two segments are loaded at overlapping addresses.
+    #[error("segments {first:#010x?} and {second:#010x?} overlap")]
+    OverlappingSegments {
+        /// The addresses of the segment starting first.
+        first: Range<u32>,
+        /// The addresses of the segment starting second.
+        second: Range<u32>,
+    },
+
+    /// A segment is both executable and writable.
+    #[error("executable segment at {vaddr:#010x} is writable")]
+    WritableCode {
+        /// The address of the segment.
+        vaddr: u32,
+    },
+
+    /// A segment is loaded where the stack starts.
+    #[error("segment {segment:#010x?} collides with the stack, which grows down from {stack_top:#010x}", stack_top = STACK_TOP)]
+    StackCollision {
+        /// The addresses of the segment.
+        segment: Range<u32>,
+    },
+
+    /// The image ends below the stack, so the heap would grow into it.
+    #[error("the heap starts at {heap_start:#010x}, below the stack top {stack_top:#010x}, and would grow into the stack", stack_top = STACK_TOP)]
+    HeapCollision {
+        /// The end of the image, where the heap starts.
+        heap_start: u32,
+    },
+}
+
+/// Convert a header value to an address-sized value.
+fn to_u32(field: &'static str, value: u64) -> Result<u32, ElfError> {
+    value.try_into().map_err(|_| ElfError::ValueOutOfRange { field, value })
+}
 
 /// RISC-V 32IM ELF (Executable and Linkable Format) File.
 ///
@@ -16,12 +151,16 @@ use sp1_primitives::consts::{MAXIMUM_MEMORY_SIZE, WORD_SIZE};
 ///
 /// - Base Integer Instruction Set (I)
 /// - Integer Multiplication and Division (M)
+/// - Compressed Instructions (C), when the ELF header sets `EF_RISCV_RVC`
 ///
 /// This format is commonly used in embedded systems and is supported by many compilers.
 #[derive(Debug, Clone)]
 pub(crate) struct Elf {
-    /// The instructions of the program encoded as 32-bits.
+    /// The instructions of the program encoded as 32-bits, with compressed instructions expanded.
     pub(crate) instructions: Vec<u32>,
+    /// Whether each instruction is compressed, empty if the ELF doesn't use compressed
+    /// instructions.
+    pub(crate) compressed: Vec<bool>,
     /// The start address of the program.
     pub(crate) pc_start: u32,
     /// The base address of the program.
@@ -35,11 +174,12 @@ impl Elf {
     #[must_use]
     pub(crate) const fn new(
         instructions: Vec<u32>,
+        compressed: Vec<bool>,
         pc_start: u32,
         pc_base: u32,
         memory_image: HashMap<u32, u32>,
     ) -> Self {
-        Self { instructions, pc_start, pc_base, memory_image }
+        Self { instructions, compressed, pc_start, pc_base, memory_image }
     }
 
     /// Parse the ELF file into a vector of 32-bit encoded instructions and the first memory
@@ -50,7 +190,7 @@ impl Elf {
     /// This function may return an error if the ELF is not valid.
     ///
     /// Reference: [Executable and Linkable Format](https://en.wikipedia.org/wiki/Executable_and_Linkable_Format)
-    pub(crate) fn decode(input: &[u8]) -> eyre::Result<Self> {
+    pub(crate) fn decode(input: &[u8]) -> Result<Self, ElfError> {
         let mut image: HashMap<u32, u32> = HashMap::new();
 
         // Parse the ELF file assuming that it is little-endian..
@@ -58,67 +198,50 @@ impl Elf {
 
         // Some sanity checks to make sure that the ELF file is valid.
         if elf.ehdr.class != Class::ELF32 {
-            eyre::bail!("must be a 32-bit elf");
+            return Err(ElfError::Not32Bit);
         } else if elf.ehdr.e_machine != EM_RISCV {
-            eyre::bail!("must be a riscv machine");
+            return Err(ElfError::NotRiscv(elf.ehdr.e_machine));
         } else if elf.ehdr.e_type != ET_EXEC {
-            eyre::bail!("must be executable");
+            return Err(ElfError::NotExecutable(elf.ehdr.e_type));
         }
 
         // Get the entrypoint of the ELF file as an u32.
-        let entry: u32 = elf.ehdr.e_entry.try_into()?;
+        let entry = to_u32("entrypoint", elf.ehdr.e_entry)?;
+
+        // Compressed instructions are only aligned to halfwords.
+        let rvc = elf.ehdr.e_flags & EF_RISCV_RVC != 0;
+        let alignment = if rvc { 2 } else { WORD_SIZE as u32 };
 
         // Make sure the entrypoint is valid.
-        if entry == MAXIMUM_MEMORY_SIZE || entry % WORD_SIZE as u32 != 0 {
-            eyre::bail!("invalid entrypoint");
+        if entry == MAXIMUM_MEMORY_SIZE || entry % alignment != 0 {
+            return Err(ElfError::InvalidEntrypoint(entry));
         }
 
         // Get the segments of the ELF file.
-        let segments = elf.segments().ok_or_else(|| eyre::eyre!("failed to get segments"))?;
+        let segments = elf.segments().ok_or(ElfError::MissingSegments)?;
         if segments.len() > 256 {
-            eyre::bail!("too many program headers");
+            return Err(ElfError::TooManySegments(segments.len()));
         }
 
         let mut instructions: Vec<u32> = Vec::new();
         let mut base_address = u32::MAX;
+        let mut loaded: Vec<(Range<u32>, u32)> = Vec::new();
 
         // Only read segments that are executable instructions that are also PT_LOAD.
         for segment in segments.iter().filter(|x| x.p_type == PT_LOAD) {
-            // Get the file size of the segment as an u32.
-            let file_size: u32 = segment.p_filesz.try_into()?;
-            if file_size == MAXIMUM_MEMORY_SIZE {
-                eyre::bail!("invalid segment file_size");
-            }
-
-            // Get the memory size of the segment as an u32.
-            let mem_size: u32 = segment.p_memsz.try_into()?;
-            if mem_size == MAXIMUM_MEMORY_SIZE {
-                eyre::bail!("Invalid segment mem_size");
-            }
-
-            // Get the virtual address of the segment as an u32.
-            let vaddr: u32 = segment.p_vaddr.try_into()?;
-            if vaddr % WORD_SIZE as u32 != 0 {
-                eyre::bail!("vaddr {vaddr:08x} is unaligned");
-            }
+            let LoadSegment { vaddr, file_size, mem_size, offset, end, executable } =
+                LoadSegment::parse(&segment, alignment, input.len())?;
 
             // If the virtual address is less than the first memory address, then update the first
             // memory address.
-            if (segment.p_flags & PF_X) != 0 && base_address > vaddr {
+            if executable && base_address > vaddr {
                 base_address = vaddr;
             }
-
-            // Get the offset to the segment.
-            let offset: u32 = segment.p_offset.try_into()?;
+            loaded.push((vaddr..end, segment.p_flags));
 
             // Read the segment and decode each word as an instruction.
             for i in (0..mem_size).step_by(WORD_SIZE) {
-                let addr = vaddr.checked_add(i).ok_or_else(|| eyre::eyre!("vaddr overflow"))?;
-                if addr == MAXIMUM_MEMORY_SIZE {
-                    eyre::bail!(
-                        "address [0x{addr:08x}] exceeds maximum address for guest programs [0x{MAXIMUM_MEMORY_SIZE:08x}]"
-                    );
-                }
+                let addr = vaddr + i;
 
                 // If we are reading past the end of the file, then break.
                 if i >= file_size {
@@ -130,19 +253,286 @@ impl Elf {
                 let mut word = 0;
                 let len = min(file_size - i, WORD_SIZE as u32);
                 for j in 0..len {
-                    let offset = (offset + i + j) as usize;
-                    let byte = input
-                        .get(offset)
-                        .ok_or_else(|| eyre::eyre!("failed to read segment offset"))?;
-                    word |= u32::from(*byte) << (j * 8);
+                    let byte = input[(offset + i + j) as usize];
+                    word |= u32::from(byte) << (j * 8);
                 }
                 image.insert(addr, word);
-                if (segment.p_flags & PF_X) != 0 {
+                if executable {
                     instructions.push(word);
                 }
             }
         }
 
-        Ok(Elf::new(instructions, entry, base_address, image))
+        check_layout(&mut loaded, entry)?;
+
+        // Compressed instructions are 16 bits, so the code has to be split into instructions
+        // halfword by halfword.
+        let (instructions, compressed) =
+            if rvc { expand_code(&instructions) } else { (instructions, Vec::new()) };
+
+        // The entrypoint has to start an instruction, not the second halfword of one.
+        if rvc {
+            let mut pc = base_address;
+            let starts_instruction = compressed.iter().any(|&compressed| {
+                let start = pc;
+                pc += if compressed { 2 } else { WORD_SIZE as u32 };
+                start == entry
+            });
+            if !starts_instruction {
+                return Err(ElfError::InvalidEntrypoint(entry));
+            }
+        }
+
+        Ok(Elf::new(instructions, compressed, entry, base_address, image))
+    }
+}
+
+/// The bounds of a `PT_LOAD` segment, checked to be loadable.
+struct LoadSegment {
+    /// The address the segment is loaded at.
+    vaddr: u32,
+    /// The number of bytes of the segment in the file.
+    file_size: u32,
+    /// The number of bytes of the segment in memory.
+    mem_size: u32,
+    /// The offset of the segment in the file.
+    offset: u32,
+    /// The address after the end of the segment in memory.
+    end: u32,
+    /// Whether the segment holds code.
+    executable: bool,
+}
+
+impl LoadSegment {
+    /// Check that a segment of an ELF of `input_len` bytes, with instructions aligned to
+    /// `alignment`, can be loaded.
+    fn parse(segment: &ProgramHeader, alignment: u32, input_len: usize) -> Result<Self, ElfError> {
+        // Get the file size of the segment as an u32.
+        let file_size = to_u32("segment file size", segment.p_filesz)?;
+
+        // Get the memory size of the segment as an u32.
+        let mem_size = to_u32("segment memory size", segment.p_memsz)?;
+
+        // Get the virtual address of the segment as an u32.
+        let vaddr = to_u32("segment address", segment.p_vaddr)?;
+        if vaddr % WORD_SIZE as u32 != 0 {
+            return Err(ElfError::UnalignedSegment { vaddr });
+        }
+
+        // Compressed code may end on a halfword, everything else has to be whole words.
+        let executable = segment.p_flags & PF_X != 0;
+        let size_alignment = if executable { alignment } else { WORD_SIZE as u32 };
+        if mem_size % size_alignment != 0 {
+            return Err(ElfError::UnalignedMemSize { vaddr, mem_size });
+        }
+        if file_size > mem_size {
+            return Err(ElfError::FileSizeExceedsMemSize { vaddr, file_size, mem_size });
+        }
+
+        // The whole segment, including the zero-filled bss after the file bytes, has to be
+        // addressable.
+        let end = vaddr
+            .checked_add(mem_size)
+            .filter(|end| *end < MAXIMUM_MEMORY_SIZE)
+            .ok_or(ElfError::SegmentOutOfRange { vaddr, mem_size })?;
+
+        // Code has to stay fixed once it's committed to, so it can't be writable.
+        if executable && segment.p_flags & PF_W != 0 {
+            return Err(ElfError::WritableCode { vaddr });
+        }
+
+        // The stack grows down from `STACK_TOP`, so no segment can hold its first word.
+        if vaddr < STACK_TOP && end > STACK_TOP - WORD_SIZE as u32 {
+            return Err(ElfError::StackCollision { segment: vaddr..end });
+        }
+
+        // Get the offset to the segment, and make sure the file bytes are all in the input.
+        let offset = to_u32("segment offset", segment.p_offset)?;
+        if offset.checked_add(file_size).map_or(true, |end| end as usize > input_len) {
+            return Err(ElfError::SegmentPastEndOfFile { vaddr, offset, file_size });
+        }
+
+        Ok(Self { vaddr, file_size, mem_size, offset, end, executable })
+    }
+}
+
+/// Check that the loaded segments, given as their address ranges and flags, don't overlap, that
+/// the entrypoint is in the code, and that the heap starts above the stack.
+fn check_layout(loaded: &mut [(Range<u32>, u32)], entry: u32) -> Result<(), ElfError> {
+    // Segments loaded on top of each other would silently overwrite each other's bytes.
+    loaded.sort_by_key(|(range, _)| range.start);
+    for pair in loaded.windows(2) {
+        let (first, second) = (&pair[0].0, &pair[1].0);
+        if second.start < first.end {
+            return Err(ElfError::OverlappingSegments {
+                first: first.clone(),
+                second: second.clone(),
+            });
+        }
+    }
+
+    // The entrypoint has to be in the code.
+    if !loaded.iter().any(|(range, flags)| flags & PF_X != 0 && range.contains(&entry)) {
+        return Err(ElfError::InvalidEntrypoint(entry));
+    }
+
+    // The heap starts at the end of the image and grows up, so it has to start above the stack.
+    let heap_start = loaded.iter().map(|(range, _)| range.end).max().unwrap_or_default();
+    if heap_start < STACK_TOP {
+        return Err(ElfError::HeapCollision { heap_start });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use elf::abi::PF_R;
+
+    use super::*;
+
+    const TEXT: u32 = 0x0020_0800;
+    const DATA: u32 = 0x0020_1000;
+
+    /// A loadable segment of a test ELF.
+    struct Segment<'a> {
+        vaddr: u32,
+        mem_size: u32,
+        flags: u32,
+        bytes: &'a [u8],
+    }
+
+    /// The text segment of a test ELF, holding two `addi` instructions.
+    const CODE: Segment<'static> = Segment {
+        vaddr: TEXT,
+        mem_size: 8,
+        flags: PF_R | PF_X,
+        bytes: &[0x13, 0x05, 0x10, 0x00, 0x93, 0x05, 0x20, 0x00],
+    };
+
+    /// Encode a little-endian RV32 executable ELF with the given segments.
+    fn encode(entry: u32, segments: &[Segment]) -> Vec<u8> {
+        let phoff = 52u32;
+        let mut offset = phoff + 32 * segments.len() as u32;
+
+        let mut elf = vec![0x7f, b'E', b'L', b'F', 1, 1, 1];
+        elf.resize(16, 0);
+        elf.extend_from_slice(&ET_EXEC.to_le_bytes());
+        elf.extend_from_slice(&EM_RISCV.to_le_bytes());
+        for word in [1, entry, phoff, 0, 0] {
+            elf.extend_from_slice(&word.to_le_bytes());
+        }
+        for half in [52, 32, segments.len() as u16, 40, 0, 0] {
+            elf.extend_from_slice(&half.to_le_bytes());
+        }
+        for segment in segments {
+            let file_size = segment.bytes.len() as u32;
+            for word in [
+                PT_LOAD,
+                offset,
+                segment.vaddr,
+                segment.vaddr,
+                file_size,
+                segment.mem_size,
+                segment.flags,
+                WORD_SIZE as u32,
+            ] {
+                elf.extend_from_slice(&word.to_le_bytes());
+            }
+            offset += file_size;
+        }
+        for segment in segments {
+            elf.extend_from_slice(segment.bytes);
+        }
+        elf
+    }
+
+    #[test]
+    fn decodes_code_and_bss() {
+        let data = Segment { vaddr: DATA, mem_size: 16, flags: PF_R | PF_W, bytes: &[1, 2, 3] };
+        let elf = Elf::decode(&encode(TEXT, &[CODE, data])).unwrap();
+        assert_eq!(elf.instructions, vec![0x0010_0513, 0x0020_0593]);
+        assert_eq!((elf.pc_start, elf.pc_base), (TEXT, TEXT));
+        assert_eq!(elf.memory_image.len(), 6);
+        assert_eq!(elf.memory_image[&DATA], 0x0003_0201);
+        assert_eq!(elf.memory_image[&(DATA + 12)], 0);
+    }
+
+    #[test]
+    fn rejects_overlapping_segments() {
+        let data = Segment { vaddr: TEXT + 4, mem_size: 8, flags: PF_R | PF_W, bytes: &[] };
+        let error = Elf::decode(&encode(TEXT, &[CODE, data])).unwrap_err();
+        assert!(matches!(
+            error,
+            ElfError::OverlappingSegments { first, second }
+                if first == (TEXT..TEXT + 8) && second == (TEXT + 4..TEXT + 12)
+        ));
+    }
+
+    #[test]
+    fn rejects_writable_code() {
+        let code = Segment { flags: PF_R | PF_W | PF_X, ..CODE };
+        let error = Elf::decode(&encode(TEXT, &[code])).unwrap_err();
+        assert!(matches!(error, ElfError::WritableCode { vaddr: TEXT }));
+    }
+
+    #[test]
+    fn rejects_unaligned_mem_size() {
+        let data = Segment { vaddr: DATA, mem_size: 6, flags: PF_R | PF_W, bytes: &[] };
+        let error = Elf::decode(&encode(TEXT, &[CODE, data])).unwrap_err();
+        assert!(matches!(error, ElfError::UnalignedMemSize { vaddr: DATA, mem_size: 6 }));
+    }
+
+    #[test]
+    fn rejects_out_of_range_bss() {
+        let bss = Segment { vaddr: 0xffff_f000, mem_size: 0x2000, flags: PF_R | PF_W, bytes: &[] };
+        let error = Elf::decode(&encode(TEXT, &[CODE, bss])).unwrap_err();
+        assert!(matches!(
+            error,
+            ElfError::SegmentOutOfRange { vaddr: 0xffff_f000, mem_size: 0x2000 }
+        ));
+    }
+
+    #[test]
+    fn rejects_segments_past_end_of_file() {
+        let mut elf = encode(TEXT, &[CODE]);
+        elf.truncate(elf.len() - 1);
+        let error = Elf::decode(&elf).unwrap_err();
+        assert!(matches!(error, ElfError::SegmentPastEndOfFile { vaddr: TEXT, .. }));
+    }
+
+    #[test]
+    fn rejects_stack_and_heap_collisions() {
+        let stack = Segment { vaddr: STACK_TOP - 0x100, mem_size: 0x100, ..CODE };
+        let error = Elf::decode(&encode(STACK_TOP - 0x100, &[stack])).unwrap_err();
+        assert!(matches!(error, ElfError::StackCollision { segment } if segment.end == STACK_TOP));
+
+        let low = Segment { vaddr: 0x0001_0000, ..CODE };
+        let error = Elf::decode(&encode(0x0001_0000, &[low])).unwrap_err();
+        assert!(matches!(error, ElfError::HeapCollision { heap_start: 0x0001_0008 }));
+    }
+
+    #[test]
+    fn rejects_entrypoint_outside_code() {
+        let data = Segment { vaddr: DATA, mem_size: 8, flags: PF_R | PF_W, bytes: &[] };
+        let error = Elf::decode(&encode(DATA, &[CODE, data])).unwrap_err();
+        assert!(matches!(error, ElfError::InvalidEntrypoint(DATA)));
+    }
+
+    #[test]
+    fn rejects_entrypoint_inside_instruction() {
+        // A compressed `c.nop` followed by a 32-bit `addi`, whose second halfword is at TEXT + 4.
+        let code = Segment {
+            mem_size: 8,
+            bytes: &[0x01, 0x00, 0x13, 0x05, 0x10, 0x00, 0x01, 0x00],
+            ..CODE
+        };
+        let mut elf = encode(TEXT + 2, &[code]);
+        elf[36..40].copy_from_slice(&EF_RISCV_RVC.to_le_bytes());
+        assert!(Elf::decode(&elf).is_ok());
+
+        elf[24..28].copy_from_slice(&(TEXT + 4).to_le_bytes());
+        let error = Elf::decode(&elf).unwrap_err();
+        assert!(matches!(error, ElfError::InvalidEntrypoint(entry) if entry == TEXT + 4));
     }
 }
@@ -1,7 +1,10 @@
 //! A disassembler for RISC-V ELFs.
 
+mod bitmanip;
+mod compressed;
 mod elf;
 mod rrs;
 
+pub use elf::ElfError;
 pub(crate) use elf::*;
 pub(crate) use rrs::*;
@@ -3,6 +3,7 @@ use rrs_lib::{
     process_instruction, InstructionProcessor,
 };
 
+use super::bitmanip::decode_bitmanip;
 use crate::{Instruction, Opcode, Register};
 
 impl Instruction {
@@ -391,7 +392,8 @@ pub(crate) fn transpile(instructions_u32: &[u32]) -> Vec<Instruction> {
     let mut instructions = Vec::new();
     let mut transpiler = InstructionTranspiler;
     for instruction_u32 in instructions_u32 {
-        let instruction = process_instruction(&mut transpiler, *instruction_u32).unwrap();
+        let instruction = decode_bitmanip(*instruction_u32)
+            .unwrap_or_else(|| process_instruction(&mut transpiler, *instruction_u32).unwrap());
         instructions.push(instruction);
     }
     instructions
@@ -4,7 +4,7 @@ use std::{
     sync::Arc,
 };
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use sp1_stark::SP1CoreOpts;
 use thiserror::Error;
@@ -17,8 +17,12 @@ use crate::{
         MemoryAccessPosition, MemoryInitializeFinalizeEvent, MemoryLocalEvent, MemoryReadRecord,
         MemoryRecord, MemoryWriteRecord, SyscallEvent,
     },
+    guest_panic::{BacktraceFrame, GuestPanic, HaltReport},
     hook::{HookEnv, HookRegistry},
+    input::BoxedInputProvider,
     memory::{Entry, PagedMemory},
+    output::OutputSink,
+    progress::{ProverObserver, ProvingInterrupted},
     record::{ExecutionRecord, MemoryAccessRecord},
     report::ExecutionReport,
     state::{ExecutionState, ForkState},
@@ -61,6 +65,9 @@ pub struct Executor<'a> {
     /// The mapping between syscall codes and their implementations.
     pub syscall_map: HashMap<SyscallCode, Arc<dyn Syscall>>,
 
+    /// The syscalls registered on the host, which can't be executed while proving.
+    pub host_syscalls: HashSet<SyscallCode>,
+
     /// The options for the runtime.
     pub opts: SP1CoreOpts,
 
@@ -96,12 +103,30 @@ pub struct Executor<'a> {
     /// A buffer for stdout and stderr IO.
     pub io_buf: HashMap<u32, String>,
 
+    /// Where the program's stdout goes, or `None` to print it.
+    pub stdout: Option<OutputSink<'a>>,
+
+    /// Where the program's stderr goes, or `None` to print it.
+    pub stderr: Option<OutputSink<'a>>,
+
+    /// Whether to also keep the program's stdout and stderr in the report.
+    pub capture_output: bool,
+
     /// A buffer for writing trace events to a file.
     pub trace_buf: Option<BufWriter<File>>,
 
     /// The state of the runtime when in unconstrained mode.
     pub unconstrained_state: ForkState,
 
+    /// The addresses of the calls the program is in, outermost first.
+    pub call_stack: Vec<u32>,
+
+    /// The panic the program reported, if it panicked.
+    pub guest_panic: Option<GuestPanic>,
+
+    /// The panic and call stack of the program, if it halted with a non-zero exit code.
+    halt_report: Option<HaltReport>,
+
     /// Report of the program execution.
     pub report: ExecutionReport,
 
@@ -110,6 +135,15 @@ pub struct Executor<'a> {
 
     /// Registry of hooks, to be invoked by writing to certain file descriptors.
     pub hook_registry: HookRegistry<'a>,
+
+    /// The source of input items once the input stream has been read to the end.
+    pub input_provider: Option<BoxedInputProvider<'a>>,
+
+    /// The items pulled from the input provider since the last checkpoint.
+    pub provided_inputs: Vec<Vec<u8>>,
+
+    /// The observer used to stop execution if the request is cancelled or times out.
+    pub observer: ProverObserver<'a>,
 }
 
 /// The different modes the executor can run in.
@@ -127,12 +161,44 @@ pub enum ExecutorMode {
 #[derive(Error, Debug, Serialize, Deserialize)]
 pub enum ExecutionError {
     /// The execution failed with a non-zero exit code.
+    ///
+    /// The panic the program reported and its call stack are in [`Executor::halt_report`].
     #[error("execution failed with exit code {0}")]
     HaltWithNonZeroExitCode(u32),
 
-    /// The execution failed with an invalid memory access.
-    #[error("invalid memory access for opcode {0} and address {1}")]
-    InvalidMemoryAccess(Opcode, u32),
+    /// The execution failed with a non-zero exit code, with the panic the program reported and
+    /// its call stack.
+    ///
+    /// `SP1Prover::execute` returns this instead of [`Self::HaltWithNonZeroExitCode`], with the
+    /// locations of the call stack resolved from the ELF.
+    #[error("execution failed with {0}")]
+    HaltWithReport(Box<HaltReport>),
+
+    /// The execution failed with an invalid memory access, such as a misaligned access or a
+    /// store to the read-only input regions.
+    #[error(
+        "invalid memory access for opcode {opcode} and address {addr} at pc {pc:#x}{}",
+        .location.as_ref().map(|location| format!(" in {location}")).unwrap_or_default()
+    )]
+    InvalidMemoryAccess {
+        /// The opcode of the access.
+        opcode: Opcode,
+        /// The accessed address.
+        addr: u32,
+        /// The address of the instruction.
+        pc: u32,
+        /// The function and source location of the instruction, if the program kept its debug
+        /// info.
+        location: Option<String>,
+    },
+
+    /// The program counter doesn't point to the start of an instruction, for example after a jump
+    /// into the middle of one.
+    #[error("pc {pc:#x} is not the start of an instruction")]
+    InvalidPc {
+        /// The program counter.
+        pc: u32,
+    },
 
     /// The execution failed with an unimplemented syscall.
     #[error("unimplemented syscall {0}")]
@@ -157,6 +223,153 @@ pub enum ExecutionError {
     /// The program ended in unconstrained mode.
     #[error("program ended in unconstrained mode")]
     EndInUnconstrained(),
+
+    /// The execution was cancelled or timed out.
+    #[error("{0}")]
+    Interrupted(ProvingInterrupted),
+
+    /// A syscall read past the end of the input stream.
+    #[error("{syscall} at pc {pc:#x} ran out of input after reading {read} items")]
+    InputExhausted {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+        /// The number of items read before.
+        read: usize,
+    },
+
+    /// A syscall read an input item with a length different from the one it asked for.
+    #[error(
+        "{syscall} at pc {pc:#x} read {requested} bytes, but the next input item has {actual}"
+    )]
+    InputLengthMismatch {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+        /// The length the guest asked for.
+        requested: u32,
+        /// The length of the input item.
+        actual: usize,
+    },
+
+    /// A syscall read input into memory that is already initialized.
+    #[error("{syscall} at pc {pc:#x} wrote input to the initialized address {addr:#x}")]
+    InputOverwritesMemory {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+        /// The initialized address.
+        addr: u32,
+    },
+
+    /// A syscall was passed a pointer that isn't word aligned.
+    #[error("{syscall} at pc {pc:#x} was passed the unaligned pointer {ptr:#x}")]
+    UnalignedSyscallPointer {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+        /// The pointer.
+        ptr: u32,
+    },
+
+    /// A syscall was passed an argument it doesn't accept.
+    #[error("{syscall} at pc {pc:#x} was passed the invalid argument {arg:#x}")]
+    InvalidSyscallArgument {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+        /// The argument.
+        arg: u32,
+    },
+
+    /// A syscall that isn't allowed in unconstrained mode was made in it, or an unconstrained
+    /// block was entered twice.
+    #[error("{syscall} at pc {pc:#x} is not allowed in unconstrained mode")]
+    UnconstrainedSyscall {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+    },
+
+    /// A proof was verified, but no more proofs were written to the executor.
+    #[error(
+        "{syscall} at pc {pc:#x} verified proof {index}, but only {index} proofs were written"
+    )]
+    MissingProof {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+        /// The index of the proof.
+        index: usize,
+    },
+
+    /// A proof written to the executor failed to verify.
+    #[error(
+        "{syscall} at pc {pc:#x} failed to verify proof {index} with digest {digest}: {reason}"
+    )]
+    InvalidProof {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+        /// The index of the proof.
+        index: usize,
+        /// The public values digest the guest verified the proof against, hex encoded.
+        digest: String,
+        /// Why the proof didn't verify.
+        reason: String,
+    },
+
+    /// The guest wrote to the public values channels without a valid channel name.
+    #[error("{syscall} at pc {pc:#x} wrote to a public values channel without a valid name")]
+    InvalidChannelWrite {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+    },
+
+    /// The guest wrote bytes that aren't UTF-8 to stdout or stderr.
+    #[error("{syscall} at pc {pc:#x} wrote bytes to fd {fd} that are not UTF-8")]
+    InvalidUtf8Output {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+        /// The file descriptor.
+        fd: u32,
+    },
+
+    /// A syscall registered on the host was invoked while proving.
+    #[error(
+        "{syscall} at pc {pc:#x} is handled by a syscall registered on the host, which can be \
+         executed but not proven"
+    )]
+    UnprovableSyscall {
+        /// The address of the `ecall`.
+        pc: u32,
+        /// The syscall.
+        syscall: SyscallCode,
+    },
+}
+
+impl ExecutionError {
+    /// The exit code, if the execution failed with a non-zero exit code.
+    #[must_use]
+    pub fn exit_code(&self) -> Option<u32> {
+        match self {
+            Self::HaltWithNonZeroExitCode(exit_code) => Some(*exit_code),
+            Self::HaltWithReport(report) => Some(report.exit_code),
+            _ => None,
+        }
+    }
 }
 
 macro_rules! assert_valid_memory_access {
@@ -177,7 +390,8 @@ impl<'a> Executor<'a> {
     ///
     /// # Panics
     ///
-    /// This function may panic if it fails to create the trace file if `TRACE_FILE` is set.
+    /// This function may panic if it fails to create the trace file if `TRACE_FILE` is set, or if
+    /// the context registers a syscall on the host with a code that isn't reserved for it.
     #[must_use]
     pub fn with_context(program: Program, opts: SP1CoreOpts, context: SP1Context<'a>) -> Self {
         // Create a shared reference to the program.
@@ -194,16 +408,13 @@ impl<'a> Executor<'a> {
             None
         };
 
-        // Determine the maximum number of cycles for any syscall.
+        // Determine the maximum number of cycles for any syscall. Syscalls registered on the
+        // host can't be proven, so they don't count.
         let syscall_map = default_syscall_map();
         let max_syscall_cycles =
             syscall_map.values().map(|syscall| syscall.num_extra_cycles()).max().unwrap_or(0);
 
-        let subproof_verifier =
-            context.subproof_verifier.unwrap_or_else(|| Arc::new(DefaultSubproofVerifier::new()));
-        let hook_registry = context.hook_registry.unwrap_or_default();
-
-        Self {
+        let mut runtime = Self {
             record,
             records: vec![],
             state: ExecutionState::new(program.pc_start),
@@ -213,22 +424,62 @@ impl<'a> Executor<'a> {
             shard_batch_size: opts.shard_batch_size as u32,
             cycle_tracker: HashMap::new(),
             io_buf: HashMap::new(),
+            stdout: None,
+            stderr: None,
+            capture_output: false,
             trace_buf,
             unconstrained: false,
             unconstrained_state: ForkState::default(),
+            call_stack: Vec::new(),
+            guest_panic: None,
+            halt_report: None,
             syscall_map,
+            host_syscalls: HashSet::new(),
             executor_mode: ExecutorMode::Trace,
             max_syscall_cycles,
             report: ExecutionReport::default(),
             print_report: false,
-            subproof_verifier,
-            hook_registry,
+            subproof_verifier: Arc::new(DefaultSubproofVerifier::new()),
+            hook_registry: HookRegistry::default(),
+            input_provider: None,
+            provided_inputs: Vec::new(),
+            observer: ProverObserver::default(),
             opts,
-            max_cycles: context.max_cycles,
+            max_cycles: None,
             memory_checkpoint: PagedMemory::new_preallocated(),
             uninitialized_memory_checkpoint: PagedMemory::new_preallocated(),
             local_memory_access: HashMap::new(),
+        };
+        runtime.set_context(context);
+        runtime
+    }
+
+    /// Replace the context of the runtime, as if it was created with `context`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the context registers a syscall on the host with a code that isn't
+    /// reserved for it.
+    pub fn set_context(&mut self, context: SP1Context<'a>) {
+        assert!(
+            context.syscalls.keys().all(|code| code.is_host()),
+            "only the reserved codes can be registered as syscalls on the host"
+        );
+        for code in self.host_syscalls.drain() {
+            self.syscall_map.remove(&code);
         }
+        self.host_syscalls = context.syscalls.keys().copied().collect();
+        self.syscall_map.extend(context.syscalls);
+
+        self.subproof_verifier =
+            context.subproof_verifier.unwrap_or_else(|| Arc::new(DefaultSubproofVerifier::new()));
+        self.hook_registry = context.hook_registry.unwrap_or_default();
+        self.input_provider = context.input_provider;
+        self.stdout = context.stdout;
+        self.stderr = context.stderr;
+        self.capture_output = context.capture_output;
+        self.observer = context.observer;
+        self.max_cycles = context.max_cycles;
     }
 
     /// Invokes a hook with the given file descriptor `fd` with the data `buf`.
@@ -259,6 +510,30 @@ impl<'a> Executor<'a> {
         runtime
     }
 
+    /// Reset the runtime to the start of its program so it can run again on `stdin`, keeping its
+    /// context and the memory it already allocated.
+    pub fn reset(&mut self, stdin: &[Vec<u8>]) {
+        self.state.reset(self.program.pc_start);
+        self.record = ExecutionRecord::new(self.program.clone());
+        self.records.clear();
+        self.executor_mode = ExecutorMode::Trace;
+        self.unconstrained = false;
+        self.unconstrained_state = ForkState::default();
+        self.memory_checkpoint.clear();
+        self.uninitialized_memory_checkpoint.clear();
+        self.memory_accesses = MemoryAccessRecord::default();
+        self.local_memory_access.clear();
+        self.cycle_tracker.clear();
+        self.io_buf.clear();
+        self.call_stack.clear();
+        self.guest_panic = None;
+        self.halt_report = None;
+        self.provided_inputs.clear();
+        self.report = ExecutionReport::default();
+        self.print_report = false;
+        self.write_vecs(stdin);
+    }
+
     /// Get the current values of the registers.
     #[allow(clippy::single_match_else)]
     #[must_use]
@@ -672,6 +947,9 @@ impl<'a> Executor<'a> {
                 self.record.divrem_events.push(event);
                 emit_divrem_dependencies(self, event);
             }
+            opcode if opcode.is_bitmanip() => {
+                self.record.bitmanip_events.push(event);
+            }
             _ => {}
         }
     }
@@ -775,9 +1053,25 @@ impl<'a> Executor<'a> {
 
     /// Fetch the instruction at the current program counter.
     #[inline]
-    fn fetch(&self) -> Instruction {
-        let idx = ((self.state.pc - self.program.pc_base) / 4) as usize;
-        self.program.instructions[idx]
+    fn fetch(&self) -> Result<Instruction, ExecutionError> {
+        let pc = self.state.pc;
+        let idx = self.program.instruction_index(pc).ok_or(ExecutionError::InvalidPc { pc })?;
+        Ok(self.program.instructions[idx])
+    }
+
+    /// The error for an invalid memory access by the instruction at `pc`.
+    pub(crate) fn invalid_memory_access(
+        &self,
+        opcode: Opcode,
+        addr: u32,
+        pc: u32,
+    ) -> ExecutionError {
+        ExecutionError::InvalidMemoryAccess {
+            opcode,
+            addr,
+            pc,
+            location: self.program.describe_pc(pc),
+        }
     }
 
     /// Execute the given instruction over the current state of the runtime.
@@ -787,7 +1081,7 @@ impl<'a> Executor<'a> {
         let mut clk = self.state.clk;
         let mut exit_code = 0u32;
 
-        let mut next_pc = self.state.pc.wrapping_add(4);
+        let mut next_pc = self.state.pc.wrapping_add(instruction.size());
 
         let rd: Register;
         let (a, b, c): (u32, u32, u32);
@@ -880,7 +1174,7 @@ impl<'a> Executor<'a> {
             Opcode::LH => {
                 (rd, b, c, addr, memory_read_value) = self.load_rr(instruction);
                 if addr % 2 != 0 {
-                    return Err(ExecutionError::InvalidMemoryAccess(Opcode::LH, addr));
+                    return Err(self.invalid_memory_access(Opcode::LH, addr, self.state.pc));
                 }
                 let value = match (addr >> 1) % 2 {
                     0 => memory_read_value & 0x0000_FFFF,
@@ -898,7 +1192,7 @@ impl<'a> Executor<'a> {
             Opcode::LW => {
                 (rd, b, c, addr, memory_read_value) = self.load_rr(instruction);
                 if addr % 4 != 0 {
-                    return Err(ExecutionError::InvalidMemoryAccess(Opcode::LW, addr));
+                    return Err(self.invalid_memory_access(Opcode::LW, addr, self.state.pc));
                 }
                 a = memory_read_value;
                 memory_store_value = Some(memory_read_value);
@@ -918,7 +1212,7 @@ impl<'a> Executor<'a> {
             Opcode::LHU => {
                 (rd, b, c, addr, memory_read_value) = self.load_rr(instruction);
                 if addr % 2 != 0 {
-                    return Err(ExecutionError::InvalidMemoryAccess(Opcode::LHU, addr));
+                    return Err(self.invalid_memory_access(Opcode::LHU, addr, self.state.pc));
                 }
                 let value = match (addr >> 1) % 2 {
                     0 => memory_read_value & 0x0000_FFFF,
@@ -933,6 +1227,9 @@ impl<'a> Executor<'a> {
             // Store instructions.
             Opcode::SB => {
                 (a, b, c, addr, memory_read_value) = self.store_rr(instruction);
+                if self.is_input_region(addr) {
+                    return Err(self.invalid_memory_access(Opcode::SB, addr, self.state.pc));
+                }
                 let value = match addr % 4 {
                     0 => (a & 0x0000_00FF) + (memory_read_value & 0xFFFF_FF00),
                     1 => ((a & 0x0000_00FF) << 8) + (memory_read_value & 0xFFFF_00FF),
@@ -949,8 +1246,8 @@ impl<'a> Executor<'a> {
             }
             Opcode::SH => {
                 (a, b, c, addr, memory_read_value) = self.store_rr(instruction);
-                if addr % 2 != 0 {
-                    return Err(ExecutionError::InvalidMemoryAccess(Opcode::SH, addr));
+                if addr % 2 != 0 || self.is_input_region(addr) {
+                    return Err(self.invalid_memory_access(Opcode::SH, addr, self.state.pc));
                 }
                 let value = match (addr >> 1) % 2 {
                     0 => (a & 0x0000_FFFF) + (memory_read_value & 0xFFFF_0000),
@@ -966,8 +1263,8 @@ impl<'a> Executor<'a> {
             }
             Opcode::SW => {
                 (a, b, c, addr, _) = self.store_rr(instruction);
-                if addr % 4 != 0 {
-                    return Err(ExecutionError::InvalidMemoryAccess(Opcode::SW, addr));
+                if addr % 4 != 0 || self.is_input_region(addr) {
+                    return Err(self.invalid_memory_access(Opcode::SW, addr, self.state.pc));
                 }
                 let value = a;
                 memory_store_value = Some(value);
@@ -1020,16 +1317,24 @@ impl<'a> Executor<'a> {
             Opcode::JAL => {
                 let (rd, imm) = instruction.j_type();
                 (b, c) = (imm, 0);
-                a = self.state.pc + 4;
+                a = self.state.pc + instruction.size();
                 self.rw(rd, a);
                 next_pc = self.state.pc.wrapping_add(imm);
+                if rd == Register::X1 {
+                    self.call_stack.push(self.state.pc);
+                }
             }
             Opcode::JALR => {
                 let (rd, rs1, imm) = instruction.i_type();
                 (b, c) = (self.rr(rs1, MemoryAccessPosition::B), imm);
-                a = self.state.pc + 4;
+                a = self.state.pc + instruction.size();
                 self.rw(rd, a);
                 next_pc = b.wrapping_add(c);
+                if rd == Register::X1 {
+                    self.call_stack.push(self.state.pc);
+                } else if rd == Register::X0 && rs1 == Register::X1 {
+                    self.call_stack.pop();
+                }
             }
 
             // Upper immediate instructions.
@@ -1066,6 +1371,14 @@ impl<'a> Executor<'a> {
                     return Err(ExecutionError::InvalidSyscallUsage(syscall_id as u64));
                 }
 
+                // Syscalls registered on the host have no constraints, so they can only be used
+                // when executing without a proof.
+                if self.executor_mode != ExecutorMode::Simple
+                    && self.host_syscalls.contains(&syscall)
+                {
+                    return Err(ExecutionError::UnprovableSyscall { pc, syscall });
+                }
+
                 let global_clk = self.state.global_clk;
                 // Update the syscall counts.
                 let syscall_for_count = syscall.count_map();
@@ -1099,7 +1412,7 @@ impl<'a> Executor<'a> {
                         // Executing a syscall optionally returns a value to write to the t0
                         // register. If it returns None, we just keep the
                         // syscall_id in t0.
-                        let res = syscall_impl.execute(&mut precompile_rt, syscall, b, c);
+                        let res = syscall_impl.execute(&mut precompile_rt, syscall, b, c)?;
                         if let Some(val) = res {
                             a = val;
                         } else {
@@ -1108,9 +1421,8 @@ impl<'a> Executor<'a> {
 
                         // If the syscall is `HALT` and the exit code is non-zero, return an error.
                         if syscall == SyscallCode::HALT && precompile_rt.exit_code != 0 {
-                            return Err(ExecutionError::HaltWithNonZeroExitCode(
-                                precompile_rt.exit_code,
-                            ));
+                            let exit_code = precompile_rt.exit_code;
+                            return Err(self.halt_error(exit_code));
                         }
 
                         (
@@ -1197,6 +1509,32 @@ impl<'a> Executor<'a> {
                 self.alu_rw(instruction, rd, a, b, c, lookup_id);
             }
 
+            // Bit-manipulation instructions.
+            Opcode::ANDN
+            | Opcode::ORN
+            | Opcode::XNOR
+            | Opcode::CLZ
+            | Opcode::CTZ
+            | Opcode::CPOP
+            | Opcode::MAX
+            | Opcode::MAXU
+            | Opcode::MIN
+            | Opcode::MINU
+            | Opcode::SEXTB
+            | Opcode::SEXTH
+            | Opcode::ZEXTH
+            | Opcode::ROL
+            | Opcode::ROR
+            | Opcode::ORCB
+            | Opcode::REV8
+            | Opcode::SH1ADD
+            | Opcode::SH2ADD
+            | Opcode::SH3ADD => {
+                (rd, b, c) = self.alu_rr(instruction);
+                a = bitmanip(instruction.opcode, b, c);
+                self.alu_rw(instruction, rd, a, b, c, lookup_id);
+            }
+
             // See https://github.com/riscv-non-isa/riscv-asm-manual/blob/master/riscv-asm.md#instruction-aliases
             Opcode::UNIMP => {
                 return Err(ExecutionError::Unimplemented());
@@ -1232,20 +1570,35 @@ impl<'a> Executor<'a> {
 
     /// Executes one cycle of the program, returning whether the program has finished.
     #[inline]
-    fn execute_cycle(&mut self) -> Result<bool, ExecutionError> {
+    pub(crate) fn execute_cycle(&mut self) -> Result<bool, ExecutionError> {
         // Fetch the instruction at the current program counter.
-        let instruction = self.fetch();
+        let instruction = self.fetch()?;
 
         // Log the current state of the runtime.
         #[cfg(debug_assertions)]
         self.log(&instruction);
 
-        // Execute the instruction.
-        self.execute_instruction(&instruction)?;
+        // Execute the instruction, pointing at the faulting code if the program kept its debug
+        // info. Invalid memory accesses carry the location themselves.
+        let pc = self.state.pc;
+        if let Err(error) = self.execute_instruction(&instruction) {
+            if !matches!(error, ExecutionError::InvalidMemoryAccess { .. }) {
+                if let Some(location) = self.program.describe_pc(pc) {
+                    tracing::error!("{error} at pc {pc:#x} in {location}");
+                }
+            }
+            return Err(error);
+        }
 
         // Increment the clock.
         self.state.global_clk += 1;
 
+        self.end_cycle()
+    }
+
+    /// Moves to the next shard and checks the cycle limit after the last executed cycle,
+    /// returning whether the program has finished.
+    pub(crate) fn end_cycle(&mut self) -> Result<bool, ExecutionError> {
         // If there's not enough cycles left for another instruction, move to the next shard.
         // We multiply by 4 because clk is incremented by 4 for each normal instruction.
         if !self.unconstrained && self.max_syscall_cycles + self.state.clk >= self.shard_size {
@@ -1253,6 +1606,9 @@ impl<'a> Executor<'a> {
             self.state.clk = 0;
 
             self.bump_record();
+
+            // Stop at shard boundaries if the request was cancelled or timed out.
+            self.check_interrupted()?;
         }
 
         // If the cycle limit is exceeded, return an error.
@@ -1263,8 +1619,7 @@ impl<'a> Executor<'a> {
         }
 
         let done = self.state.pc == 0
-            || self.state.pc.wrapping_sub(self.program.pc_base)
-                >= (self.program.instructions.len() * 4) as u32;
+            || self.state.pc.wrapping_sub(self.program.pc_base) >= self.program.code_size();
         if done && self.unconstrained {
             log::error!("program ended in unconstrained mode at clk {}", self.state.global_clk);
             return Err(ExecutionError::EndInUnconstrained());
@@ -1272,6 +1627,17 @@ impl<'a> Executor<'a> {
         Ok(done)
     }
 
+    /// Return an error if the request this execution belongs to was cancelled or timed out.
+    fn check_interrupted(&self) -> Result<(), ExecutionError> {
+        self.observer.check().map_err(|mut interrupted| {
+            // The executor knows the exact cycle count, which may be ahead of the last checkpoint.
+            let (ProvingInterrupted::Cancelled(stats) | ProvingInterrupted::TimedOut(stats)) =
+                &mut interrupted;
+            stats.cycles = self.state.global_clk;
+            ExecutionError::Interrupted(interrupted)
+        })
+    }
+
     /// Bump the record.
     pub fn bump_record(&mut self) {
         // Copy all of the existing local memory accesses to the record's local_memory_access vec.
@@ -1317,6 +1683,7 @@ impl<'a> Executor<'a> {
         self.state.uninitialized_memory = uninitialized_memory;
 
         let done = tracing::info_span!("execute").in_scope(|| self.execute())?;
+        checkpoint.provided_inputs = std::mem::take(&mut self.provided_inputs).into();
         // Create a checkpoint using `memory_checkpoint`. Just include all memory if `done` since we
         // need it all for MemoryFinalize.
         tracing::info_span!("create memory checkpoint").in_scope(|| {
@@ -1367,6 +1734,11 @@ impl<'a> Executor<'a> {
 
     /// Executes the program without tracing and without emitting events.
     ///
+    /// The program is run a basic block at a time from its pre-decoded instructions, which gives
+    /// the same results and cycle counts as [`Executor::run`].
+    /// The memory records don't keep the shards and timestamps of their accesses, so the state
+    /// can't be checkpointed or traced from afterwards.
+    ///
     /// # Errors
     ///
     /// This function will return an error if the program execution fails.
@@ -1409,7 +1781,13 @@ impl<'a> Executor<'a> {
         let mut current_shard = self.state.current_shard;
         let mut num_shards_executed = 0;
         loop {
-            if self.execute_cycle()? {
+            // Execution without tracing runs whole basic blocks at a time.
+            let cycle_done = if self.executor_mode == ExecutorMode::Simple {
+                self.execute_blocks()?
+            } else {
+                self.execute_cycle()?
+            };
+            if cycle_done {
                 done = true;
                 break;
             }
@@ -1463,6 +1841,26 @@ impl<'a> Executor<'a> {
         Ok(done)
     }
 
+    /// The error for a halt with a non-zero exit code, keeping the panic the program reported
+    /// and its call stack in the [`HaltReport`].
+    fn halt_error(&mut self, exit_code: u32) -> ExecutionError {
+        let pc = self.state.pc;
+        let backtrace = std::iter::once(pc)
+            .chain(self.call_stack.iter().rev().copied())
+            .map(|pc| BacktraceFrame { pc, location: self.program.describe_pc(pc) })
+            .collect();
+        self.halt_report =
+            Some(HaltReport { exit_code, pc, panic: self.guest_panic.take(), backtrace });
+        ExecutionError::HaltWithNonZeroExitCode(exit_code)
+    }
+
+    /// The panic and call stack of the program, if the last run failed with
+    /// [`ExecutionError::HaltWithNonZeroExitCode`].
+    #[must_use]
+    pub fn halt_report(&self) -> Option<&HaltReport> {
+        self.halt_report.as_ref()
+    }
+
     fn postprocess(&mut self) {
         // Flush remaining stdout/stderr
         for (fd, buf) in &self.io_buf {
@@ -1479,6 +1877,12 @@ impl<'a> Executor<'a> {
             }
         }
 
+        for sink in [&self.stdout, &self.stderr].into_iter().flatten() {
+            if let Err(error) = sink.lock().unwrap().flush() {
+                tracing::warn!("failed to flush the output of the program: {error}");
+            }
+        }
+
         // Flush trace buf
         if let Some(ref mut buf) = self.trace_buf {
             buf.flush().unwrap();
@@ -1575,6 +1979,35 @@ pub const fn align(addr: u32) -> u32 {
     addr - addr % 4
 }
 
+/// Compute the result of a Zba/Zbb bit-manipulation instruction. Unary instructions ignore `c`.
+pub(crate) fn bitmanip(opcode: Opcode, b: u32, c: u32) -> u32 {
+    match opcode {
+        Opcode::ANDN => b & !c,
+        Opcode::ORN => b | !c,
+        Opcode::XNOR => !(b ^ c),
+        Opcode::CLZ => b.leading_zeros(),
+        Opcode::CTZ => b.trailing_zeros(),
+        Opcode::CPOP => b.count_ones(),
+        Opcode::MAX => (b as i32).max(c as i32) as u32,
+        Opcode::MAXU => b.max(c),
+        Opcode::MIN => (b as i32).min(c as i32) as u32,
+        Opcode::MINU => b.min(c),
+        Opcode::SEXTB => b as i8 as i32 as u32,
+        Opcode::SEXTH => b as i16 as i32 as u32,
+        Opcode::ZEXTH => b & 0xffff,
+        Opcode::ROL => b.rotate_left(c & 0x1f),
+        Opcode::ROR => b.rotate_right(c & 0x1f),
+        Opcode::ORCB => {
+            u32::from_le_bytes(b.to_le_bytes().map(|byte| if byte == 0 { 0 } else { 0xff }))
+        }
+        Opcode::REV8 => b.swap_bytes(),
+        Opcode::SH1ADD => (b << 1).wrapping_add(c),
+        Opcode::SH2ADD => (b << 2).wrapping_add(c),
+        Opcode::SH3ADD => (b << 3).wrapping_add(c),
+        _ => unreachable!("{opcode} is not a bit-manipulation instruction"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1582,12 +2015,18 @@ mod tests {
 
     use crate::programs::tests::{
         fibonacci_program, panic_program, simple_memory_program, simple_program,
-        ssz_withdrawals_program,
+        ssz_withdrawals_program, FIBONACCI_IO_ELF,
     };
 
-    use crate::Register;
+    use sp1_primitives::consts::INPUT_REGIONS_PTR;
+    use std::sync::Arc;
 
-    use super::{Executor, Instruction, Opcode, Program};
+    use crate::{
+        syscalls::{Syscall, SyscallCode, SyscallContext},
+        Register, SP1Context, SharedBuffer, FD_PANIC,
+    };
+
+    use super::{ExecutionError, Executor, Instruction, Opcode, Program};
 
     fn _assert_send<T: Send>() {}
 
@@ -1626,6 +2065,68 @@ mod tests {
         runtime.run().unwrap();
     }
 
+    #[test]
+    fn test_panic_report() {
+        // Call a function that reports a panic with the message "boom" and halts with exit code 1.
+        let instructions = vec![
+            Instruction::new(Opcode::JAL, 1, 8, 0, true, true),
+            Instruction::new(Opcode::ADD, 0, 0, 0, false, true),
+            Instruction::new(Opcode::ADD, 29, 0, 7, false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1004, false, true),
+            Instruction::new(Opcode::ADD, 29, 0, u32::from_le_bytes(*b"boom"), false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x100C, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, FD_PANIC, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 16, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HALT as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 1, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let mut runtime = Executor::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        let error = runtime.run_fast().unwrap_err();
+        assert!(matches!(error, ExecutionError::HaltWithNonZeroExitCode(1)));
+        let report = runtime.halt_report().unwrap();
+        assert_eq!(
+            report.to_string(),
+            "exit code 1 at pc 0x3c: panicked at :7:5: boom\n    called from 0x00000000"
+        );
+        let pcs = report.backtrace.iter().map(|frame| frame.pc).collect::<Vec<_>>();
+        assert_eq!(pcs, [60, 0]);
+    }
+
+    #[test]
+    fn test_input_regions() {
+        // "hello" is padded to 8 bytes below the pointer, after a table of 3 words.
+        let table = INPUT_REGIONS_PTR - 20;
+        let region = INPUT_REGIONS_PTR - 8;
+        let instructions = vec![
+            Instruction::new(Opcode::LW, 28, 0, INPUT_REGIONS_PTR, false, true),
+            Instruction::new(Opcode::LW, 29, 0, table, false, true),
+            Instruction::new(Opcode::LW, 30, 0, table + 4, false, true),
+            Instruction::new(Opcode::LW, 31, 0, region, false, true),
+        ];
+        let mut runtime = Executor::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        runtime.write_regions(&[b"hello".to_vec()]);
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X28), table);
+        assert_eq!(runtime.register(Register::X29), 1);
+        assert_eq!(runtime.register(Register::X30), region);
+        assert_eq!(runtime.register(Register::X31), u32::from_le_bytes(*b"hell"));
+
+        // The regions are read-only.
+        let instructions = vec![Instruction::new(Opcode::SB, 29, 0, region + 1, false, true)];
+        let mut runtime = Executor::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        runtime.write_regions(&[b"hello".to_vec()]);
+        assert!(matches!(
+            runtime.run(),
+            Err(ExecutionError::InvalidMemoryAccess { opcode: Opcode::SB, .. })
+        ));
+    }
+
     #[test]
     fn test_add() {
         // main:
@@ -1643,6 +2144,73 @@ mod tests {
         assert_eq!(runtime.register(Register::X31), 42);
     }
 
+    #[test]
+    fn test_compressed() {
+        // main:
+        //     c.li x29, 5
+        //     addi x30, x0, 37
+        //     c.jal 6
+        //     addi x31, x0, 1
+        //     c.add x31, x30, x29
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true).into_compressed(),
+            Instruction::new(Opcode::ADD, 30, 0, 37, false, true),
+            Instruction::new(Opcode::JAL, 1, 6, 0, true, true).into_compressed(),
+            Instruction::new(Opcode::ADD, 31, 0, 1, false, true),
+            Instruction::new(Opcode::ADD, 31, 30, 29, false, false).into_compressed(),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        assert_eq!(program.pcs().collect::<Vec<_>>(), vec![0, 2, 6, 8, 12]);
+        assert_eq!(program.code_size(), 14);
+        assert_eq!(program.instruction_index(4), None);
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X1), 8);
+        assert_eq!(runtime.register(Register::X31), 42);
+    }
+
+    #[test]
+    fn test_jump_into_instruction() {
+        // main:
+        //     c.li x29, 5
+        //     addi x30, x0, 37
+        //     jal x0, -2
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true).into_compressed(),
+            Instruction::new(Opcode::ADD, 30, 0, 37, false, true),
+            Instruction::new(Opcode::JAL, 0, 0u32.wrapping_sub(2), 0, true, true),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Executor::new(program.clone(), SP1CoreOpts::default());
+        assert!(matches!(runtime.run(), Err(ExecutionError::InvalidPc { pc: 4 })));
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        assert!(matches!(runtime.run_fast(), Err(ExecutionError::InvalidPc { pc: 4 })));
+    }
+
+    #[test]
+    fn test_invalid_memory_access() {
+        //     addi x29, x0, 0x1006
+        //     lw x30, 0(x29)
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 0x1006, false, true),
+            Instruction::new(Opcode::LW, 30, 29, 0, false, true),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        for fast in [false, true] {
+            let mut runtime = Executor::new(program.clone(), SP1CoreOpts::default());
+            let result = if fast { runtime.run_fast() } else { runtime.run() };
+            assert!(matches!(
+                result,
+                Err(ExecutionError::InvalidMemoryAccess {
+                    opcode: Opcode::LW,
+                    addr: 0x1006,
+                    pc: 4,
+                    location: None
+                })
+            ));
+        }
+    }
+
     #[test]
     fn test_sub() {
         //     addi x29, x0, 5
@@ -2088,6 +2656,44 @@ mod tests {
         simple_op_code_test(Opcode::REMU, 0, 0, 0);
     }
 
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn bitmanip_tests() {
+        simple_op_code_test(Opcode::ANDN, 0x0000f0f0, 0x0000ffff, 0xffff0f0f);
+        simple_op_code_test(Opcode::ORN, 0xffff0f0f, 0x00000f0f, 0x0000f0f0);
+        simple_op_code_test(Opcode::XNOR, 0xffffff00, 0x12345678, 0x12345687);
+
+        simple_op_code_test(Opcode::CLZ, 32, 0, 0);
+        simple_op_code_test(Opcode::CLZ, 0, neg(1), 0);
+        simple_op_code_test(Opcode::CLZ, 19, 0x1000, 0);
+        simple_op_code_test(Opcode::CTZ, 32, 0, 0);
+        simple_op_code_test(Opcode::CTZ, 12, 0x1000, 0);
+        simple_op_code_test(Opcode::CPOP, 32, neg(1), 0);
+        simple_op_code_test(Opcode::CPOP, 13, 0x12345678, 0);
+
+        simple_op_code_test(Opcode::MAX, 5, neg(3), 5);
+        simple_op_code_test(Opcode::MAXU, neg(3), neg(3), 5);
+        simple_op_code_test(Opcode::MIN, neg(3), neg(3), 5);
+        simple_op_code_test(Opcode::MINU, 5, neg(3), 5);
+
+        simple_op_code_test(Opcode::SEXTB, 0xffffff80, 0x12345680, 0);
+        simple_op_code_test(Opcode::SEXTB, 0x0000007f, 0x1234567f, 0);
+        simple_op_code_test(Opcode::SEXTH, 0xffff8765, 0x12348765, 0);
+        simple_op_code_test(Opcode::ZEXTH, 0x00008765, 0x12348765, 0);
+
+        simple_op_code_test(Opcode::ROL, 0x23456781, 0x12345678, 4);
+        simple_op_code_test(Opcode::ROL, 0x12345678, 0x12345678, 32);
+        simple_op_code_test(Opcode::ROR, 0x81234567, 0x12345678, 4);
+        simple_op_code_test(Opcode::ROR, 0x2468acf0, 0x12345678, 31);
+
+        simple_op_code_test(Opcode::ORCB, 0xff00ffff, 0x12004501, 0);
+        simple_op_code_test(Opcode::REV8, 0x78563412, 0x12345678, 0);
+
+        simple_op_code_test(Opcode::SH1ADD, 7, 3, 1);
+        simple_op_code_test(Opcode::SH2ADD, 13, 3, 1);
+        simple_op_code_test(Opcode::SH3ADD, 0x00000017, 0x80000002, 7);
+    }
+
     #[test]
     #[allow(clippy::unreadable_literal)]
     fn shift_tests() {
@@ -2188,4 +2794,186 @@ mod tests {
         assert_eq!(runtime.register(Register::X12), 0x12346525);
         assert_eq!(runtime.register(Register::X11), 0x65256525);
     }
+
+    #[test]
+    fn test_missing_input_is_an_error() {
+        let program = Program::from(FIBONACCI_IO_ELF).unwrap();
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        let error = runtime.run_fast().unwrap_err();
+        assert!(
+            matches!(
+                error,
+                ExecutionError::InputExhausted { syscall: SyscallCode::HINT_LEN, read: 0, .. }
+            ),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_input_provider() {
+        let program = Program::from(FIBONACCI_IO_ELF).unwrap();
+        let mut inputs = vec![bincode::serialize(&10u32).unwrap()].into_iter();
+        let context = SP1Context::builder().input_provider(move || inputs.next()).build();
+        let mut runtime = Executor::with_context(program, SP1CoreOpts::default(), context);
+        runtime.run_fast().unwrap();
+        assert_eq!(runtime.read_public_values::<u32>(), 10);
+    }
+
+    #[test]
+    fn test_input_provider_checkpoint_replay() {
+        let program = Program::from(FIBONACCI_IO_ELF).unwrap();
+        let mut inputs = vec![bincode::serialize(&10u32).unwrap()].into_iter();
+        let context = SP1Context::builder().input_provider(move || inputs.next()).build();
+        let mut runtime = Executor::with_context(program.clone(), SP1CoreOpts::default(), context);
+        let (checkpoint, done) = runtime.execute_state().unwrap();
+        assert!(done);
+        assert_eq!(checkpoint.provided_inputs.len(), 1);
+
+        // The recovered executor has no provider, so it reads the input recorded in the checkpoint.
+        let mut replay = Executor::recover(program, checkpoint, SP1CoreOpts::default());
+        replay.execute_record().unwrap();
+        assert_eq!(replay.state.public_values_stream, runtime.state.public_values_stream);
+    }
+
+    #[test]
+    fn test_reset() {
+        let program = Program::from(FIBONACCI_IO_ELF).unwrap();
+        let stdin = |n: u32| vec![bincode::serialize(&n).unwrap()];
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.write_vecs(&stdin(10));
+        runtime.run_fast().unwrap();
+        let cycles = runtime.report.total_instruction_count();
+
+        runtime.reset(&stdin(20));
+        runtime.run_fast().unwrap();
+        assert_eq!(runtime.read_public_values::<u32>(), 20);
+        assert!(runtime.report.total_instruction_count() > cycles);
+
+        // The same input runs the same way after a reset.
+        runtime.reset(&stdin(10));
+        runtime.run_fast().unwrap();
+        assert_eq!(runtime.report.total_instruction_count(), cycles);
+    }
+
+    #[test]
+    fn test_non_utf8_stdout_is_an_error() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 0xff, false, true),
+            Instruction::new(Opcode::SB, 29, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 1, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 1, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let mut runtime = Executor::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        let error = runtime.run().unwrap_err();
+        assert!(
+            matches!(
+                error,
+                ExecutionError::InvalidUtf8Output { pc: 24, syscall: SyscallCode::WRITE, fd: 1 }
+            ),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_public_value_channels() {
+        // Write "xy" to the channel "a", then write a frame that is too short for its name.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 1, false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 29, 0, u32::from_le_bytes(*b"axy\0"), false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1004, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 10, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 7, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let mut runtime = Executor::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        let error = runtime.run_fast().unwrap_err();
+        assert!(
+            matches!(
+                error,
+                ExecutionError::InvalidChannelWrite { pc: 44, syscall: SyscallCode::WRITE }
+            ),
+            "{error}"
+        );
+        assert_eq!(runtime.public_values().read_channel::<[u8; 2]>("a"), Some(*b"xy"));
+    }
+
+    #[test]
+    fn test_output_sinks() {
+        // Write "hi" to stdout and "h" to stderr.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, u32::from_le_bytes(*b"hi\0\0"), false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 1, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 2, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 2, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 1, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let stdout = SharedBuffer::new();
+        let context = SP1Context::builder().stdout(stdout.clone()).capture_output().build();
+        let mut runtime = Executor::with_context(
+            Program::new(instructions, 0, 0),
+            SP1CoreOpts::default(),
+            context,
+        );
+        runtime.run_fast().unwrap();
+        assert_eq!(stdout.contents(), b"hi");
+        assert_eq!(runtime.report.stdout, "hi");
+        assert_eq!(runtime.report.stderr, "h");
+    }
+
+    #[test]
+    fn test_host_syscall_is_executed_but_not_proven() {
+        struct AddSyscall;
+
+        impl Syscall for AddSyscall {
+            fn execute(
+                &self,
+                _: &mut SyscallContext,
+                _: SyscallCode,
+                arg1: u32,
+                arg2: u32,
+            ) -> Result<Option<u32>, ExecutionError> {
+                Ok(Some(arg1 + arg2))
+            }
+        }
+
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HOST_0 as u32, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let context =
+            || SP1Context::builder().syscall(SyscallCode::HOST_0, Arc::new(AddSyscall)).build();
+
+        let mut runtime =
+            Executor::with_context(program.clone(), SP1CoreOpts::default(), context());
+        runtime.run_fast().unwrap();
+        assert_eq!(runtime.register(Register::X5), 7);
+
+        let mut runtime = Executor::with_context(program, SP1CoreOpts::default(), context());
+        let error = runtime.run().unwrap_err();
+        assert!(
+            matches!(
+                error,
+                ExecutionError::UnprovableSyscall { pc: 12, syscall: SyscallCode::HOST_0 }
+            ),
+            "{error}"
+        );
+    }
 }
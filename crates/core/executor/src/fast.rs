@@ -0,0 +1,580 @@
+//! A fast interpreter for executing a program without tracing.
+//!
+//! The instructions of the program are decoded once into basic blocks, runs of instructions that
+//! end at a branch, a jump or an instruction the interpreter leaves to the full executor. The
+//! registers are kept in an array while the blocks run, and memory is read and written by value,
+//! without the shards, timestamps and access events that only proving needs.
+//!
+//! The memory records the interpreter touches keep their shard and timestamp, or get shard 0 and
+//! timestamp 0 if they are new, so the state it leaves can't be checkpointed or traced from.
+
+use crate::{
+    events::MemoryRecord,
+    executor::{align, bitmanip},
+    memory::Entry,
+    ExecutionError, Executor, ExecutorMode, Instruction, Opcode,
+};
+
+/// An operand of an ALU instruction.
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    /// The value of a register.
+    Register(usize),
+    /// An immediate value.
+    Immediate(u32),
+}
+
+impl Operand {
+    #[inline]
+    fn value(self, registers: &[u32; 32]) -> u32 {
+        match self {
+            Self::Register(register) => registers[register],
+            Self::Immediate(value) => value,
+        }
+    }
+}
+
+/// The operation of an instruction, with its operands resolved to registers and immediates.
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    /// An ALU instruction writing `rd`.
+    Alu { rd: usize, b: Operand, c: Operand },
+    /// A load into `rd` from `base` plus `offset`.
+    Load { rd: usize, base: usize, offset: u32 },
+    /// A store of `src` to `base` plus `offset`.
+    Store { src: usize, base: usize, offset: u32 },
+    /// A branch by `offset` if registers `a` and `b` compare as the opcode says.
+    Branch { a: usize, b: usize, offset: u32 },
+    /// A jump by `offset`, linking `rd`.
+    Jal { rd: usize, offset: u32 },
+    /// A jump to `base` plus `offset`, linking `rd`.
+    Jalr { rd: usize, base: usize, offset: u32 },
+    /// The program counter plus `offset`, into `rd`.
+    Auipc { rd: usize, offset: u32 },
+    /// A syscall, breakpoint or unimplemented instruction, run by the full executor.
+    Executor,
+}
+
+/// An instruction decoded for the fast interpreter.
+#[derive(Debug, Clone, Copy)]
+struct DecodedInstruction {
+    opcode: Opcode,
+    size: u32,
+    operation: Operation,
+}
+
+impl DecodedInstruction {
+    fn decode(instruction: &Instruction) -> Self {
+        let (a, b, c) = (instruction.op_a as usize, instruction.op_b as usize, instruction.op_c);
+        let operation = match instruction.opcode {
+            _ if instruction.is_alu_instruction() => Operation::Alu {
+                rd: a,
+                b: if instruction.imm_b && instruction.imm_c {
+                    Operand::Immediate(instruction.op_b)
+                } else {
+                    Operand::Register(b)
+                },
+                c: if instruction.imm_c {
+                    Operand::Immediate(c)
+                } else {
+                    Operand::Register(c as usize)
+                },
+            },
+            Opcode::LB | Opcode::LH | Opcode::LW | Opcode::LBU | Opcode::LHU => {
+                Operation::Load { rd: a, base: b, offset: c }
+            }
+            Opcode::SB | Opcode::SH | Opcode::SW => Operation::Store { src: a, base: b, offset: c },
+            _ if instruction.is_branch_instruction() => Operation::Branch { a, b, offset: c },
+            Opcode::JAL => Operation::Jal { rd: a, offset: instruction.op_b },
+            Opcode::JALR => Operation::Jalr { rd: a, base: b, offset: c },
+            Opcode::AUIPC => Operation::Auipc { rd: a, offset: instruction.op_b },
+            _ => Operation::Executor,
+        };
+        Self { opcode: instruction.opcode, size: instruction.size(), operation }
+    }
+
+    /// Whether the instruction is the last of its basic block.
+    const fn ends_block(&self) -> bool {
+        matches!(
+            self.operation,
+            Operation::Branch { .. }
+                | Operation::Jal { .. }
+                | Operation::Jalr { .. }
+                | Operation::Executor
+        )
+    }
+}
+
+/// The instructions of a program decoded into basic blocks.
+#[derive(Debug, Clone)]
+pub(crate) struct BasicBlocks {
+    /// The decoded instructions, in the order of the instructions of the program.
+    instructions: Vec<DecodedInstruction>,
+    /// The index one past the end of the basic block from each instruction on.
+    ends: Vec<usize>,
+}
+
+impl BasicBlocks {
+    /// Decode the instructions of a program into basic blocks.
+    pub(crate) fn decode(instructions: &[Instruction]) -> Self {
+        let instructions: Vec<_> = instructions.iter().map(DecodedInstruction::decode).collect();
+        let mut ends = vec![0; instructions.len()];
+        let mut end = instructions.len();
+        for (i, instruction) in instructions.iter().enumerate().rev() {
+            if instruction.ends_block() {
+                end = i + 1;
+            }
+            ends[i] = end;
+        }
+        Self { instructions, ends }
+    }
+
+    /// The instructions from `index` to the end of its basic block.
+    #[inline]
+    fn block(&self, index: usize) -> &[DecodedInstruction] {
+        &self.instructions[index..self.ends[index]]
+    }
+}
+
+/// Compute the result of an ALU instruction.
+#[inline]
+fn alu(opcode: Opcode, b: u32, c: u32) -> u32 {
+    match opcode {
+        Opcode::ADD => b.wrapping_add(c),
+        Opcode::SUB => b.wrapping_sub(c),
+        Opcode::XOR => b ^ c,
+        Opcode::OR => b | c,
+        Opcode::AND => b & c,
+        Opcode::SLL => b.wrapping_shl(c),
+        Opcode::SRL => b.wrapping_shr(c),
+        Opcode::SRA => (b as i32).wrapping_shr(c) as u32,
+        Opcode::SLT => u32::from((b as i32) < (c as i32)),
+        Opcode::SLTU => u32::from(b < c),
+        Opcode::MUL => b.wrapping_mul(c),
+        Opcode::MULH => (((b as i32) as i64).wrapping_mul((c as i32) as i64) >> 32) as u32,
+        Opcode::MULHU => ((b as u64).wrapping_mul(c as u64) >> 32) as u32,
+        Opcode::MULHSU => (((b as i32) as i64).wrapping_mul(c as i64) >> 32) as u32,
+        Opcode::DIV | Opcode::DIVU if c == 0 => u32::MAX,
+        Opcode::DIV => (b as i32).wrapping_div(c as i32) as u32,
+        Opcode::DIVU => b.wrapping_div(c),
+        Opcode::REM | Opcode::REMU if c == 0 => b,
+        Opcode::REM => (b as i32).wrapping_rem(c as i32) as u32,
+        Opcode::REMU => b.wrapping_rem(c),
+        opcode => bitmanip(opcode, b, c),
+    }
+}
+
+/// Write to a register. Like the full executor, writing to `%x0` always writes 0.
+#[inline]
+fn write_register(registers: &mut [u32; 32], rd: usize, value: u32) {
+    registers[rd] = if rd == 0 { 0 } else { value };
+}
+
+impl Executor<'_> {
+    /// Executes basic blocks of the program from the current program counter, returning whether
+    /// the program has finished.
+    ///
+    /// Execution stops before an instruction the full executor has to run, like a syscall, and
+    /// at the last cycle of a shard or before the cycle limit, so that the full executor moves to
+    /// the next shard and checks the limit. In unconstrained mode, and when tracing the program
+    /// counter to a file, every cycle is left to the full executor.
+    pub(crate) fn execute_blocks(&mut self) -> Result<bool, ExecutionError> {
+        debug_assert_eq!(self.executor_mode, ExecutorMode::Simple);
+        if self.unconstrained || self.trace_buf.is_some() || !self.starts_block() {
+            return self.execute_cycle();
+        }
+
+        let program = self.program.clone();
+        let blocks = program.basic_blocks();
+        let budget = self.cycles_until_check();
+        let initial_registers = self.fast_registers();
+        let mut registers = initial_registers;
+        let mut pc = self.state.pc;
+        let mut cycles = 0;
+        let mut error = None;
+
+        'blocks: while cycles < budget {
+            // Leave a jump into the middle of an instruction to the full executor, which reports it.
+            let Some(index) = program.instruction_index(pc) else { break };
+            for instruction in blocks.block(index) {
+                if cycles == budget || matches!(instruction.operation, Operation::Executor) {
+                    break 'blocks;
+                }
+                if self.print_report {
+                    self.report.opcode_counts[instruction.opcode] += 1;
+                }
+                match self.execute_decoded(instruction, pc, &mut registers) {
+                    Ok(next_pc) => pc = next_pc,
+                    Err(err) => {
+                        error = Some(err);
+                        break 'blocks;
+                    }
+                }
+                cycles += 1;
+            }
+            if pc == 0 || pc.wrapping_sub(program.pc_base) >= program.code_size() {
+                break;
+            }
+        }
+
+        self.state.pc = pc;
+        self.state.global_clk += cycles;
+        self.state.clk += 4 * cycles as u32;
+        for (addr, (&initial, &value)) in initial_registers.iter().zip(registers.iter()).enumerate()
+        {
+            if initial != value {
+                self.fast_write(addr as u32, value);
+            }
+        }
+
+        if let Some(error) = error {
+            if let Some(location) = self.program.describe_pc(pc) {
+                tracing::error!("{error} at pc {pc:#x} in {location}");
+            }
+            return Err(error);
+        }
+        if cycles == 0 {
+            // The next instruction is one for the full executor.
+            return self.execute_cycle();
+        }
+        self.end_cycle()
+    }
+
+    /// Whether the instruction at the program counter is one the interpreter runs, so that a
+    /// syscall doesn't pay for setting up the registers of a block.
+    fn starts_block(&self) -> bool {
+        self.program.instruction_index(self.state.pc).is_some_and(|index| {
+            !matches!(self.program.basic_blocks().block(index)[0].operation, Operation::Executor)
+        })
+    }
+
+    /// The number of cycles until the last cycle of the shard or the cycle limit.
+    fn cycles_until_check(&self) -> u64 {
+        let shard_cycles_left =
+            self.shard_size.saturating_sub(self.max_syscall_cycles + self.state.clk).div_ceil(4);
+        let mut cycles = u64::from(shard_cycles_left.max(1));
+        if let Some(max_cycles) = self.max_cycles {
+            cycles = cycles.min(max_cycles.saturating_sub(self.state.global_clk).max(1));
+        }
+        cycles
+    }
+
+    /// Execute a decoded instruction at `pc`, returning the next program counter.
+    #[inline]
+    #[allow(clippy::too_many_lines)]
+    fn execute_decoded(
+        &mut self,
+        instruction: &DecodedInstruction,
+        pc: u32,
+        registers: &mut [u32; 32],
+    ) -> Result<u32, ExecutionError> {
+        let opcode = instruction.opcode;
+        let next_pc = pc.wrapping_add(instruction.size);
+        match instruction.operation {
+            Operation::Alu { rd, b, c } => {
+                let a = alu(opcode, b.value(registers), c.value(registers));
+                write_register(registers, rd, a);
+            }
+            Operation::Load { rd, base, offset } => {
+                let addr = registers[base].wrapping_add(offset);
+                let word = self.fast_read(registers, align(addr));
+                let a = match opcode {
+                    Opcode::LB => ((word.to_le_bytes()[(addr % 4) as usize] as i8) as i32) as u32,
+                    Opcode::LBU => word.to_le_bytes()[(addr % 4) as usize] as u32,
+                    Opcode::LH | Opcode::LHU => {
+                        if addr % 2 != 0 {
+                            return Err(self.invalid_memory_access(opcode, addr, pc));
+                        }
+                        let half = (word >> ((addr & 2) * 8)) as u16;
+                        if opcode == Opcode::LH {
+                            ((half as i16) as i32) as u32
+                        } else {
+                            half as u32
+                        }
+                    }
+                    _ => {
+                        if addr % 4 != 0 {
+                            return Err(self.invalid_memory_access(opcode, addr, pc));
+                        }
+                        word
+                    }
+                };
+                write_register(registers, rd, a);
+            }
+            Operation::Store { src, base, offset } => {
+                let a = registers[src];
+                let addr = registers[base].wrapping_add(offset);
+                if self.is_input_region(addr) {
+                    return Err(self.invalid_memory_access(opcode, addr, pc));
+                }
+                // The bytes that are not stored keep the value of the word, or 0 if it was never
+                // accessed.
+                let word = if addr < 32 {
+                    registers[align(addr) as usize]
+                } else {
+                    self.state.memory.get(align(addr)).map_or(0, |record| record.value)
+                };
+                let value = match opcode {
+                    Opcode::SB => {
+                        let shift = (addr % 4) * 8;
+                        ((a & 0xFF) << shift) | (word & !(0xFF << shift))
+                    }
+                    Opcode::SH => {
+                        if addr % 2 != 0 {
+                            return Err(self.invalid_memory_access(opcode, addr, pc));
+                        }
+                        let shift = (addr & 2) * 8;
+                        ((a & 0xFFFF) << shift) | (word & !(0xFFFF << shift))
+                    }
+                    _ => {
+                        if addr % 4 != 0 {
+                            return Err(self.invalid_memory_access(opcode, addr, pc));
+                        }
+                        a
+                    }
+                };
+                if addr < 32 {
+                    registers[align(addr) as usize] = value;
+                } else {
+                    self.fast_write(align(addr), value);
+                }
+            }
+            Operation::Branch { a, b, offset } => {
+                let (a, b) = (registers[a], registers[b]);
+                let taken = match opcode {
+                    Opcode::BEQ => a == b,
+                    Opcode::BNE => a != b,
+                    Opcode::BLT => (a as i32) < (b as i32),
+                    Opcode::BGE => (a as i32) >= (b as i32),
+                    Opcode::BLTU => a < b,
+                    _ => a >= b,
+                };
+                if taken {
+                    return Ok(pc.wrapping_add(offset));
+                }
+            }
+            Operation::Jal { rd, offset } => {
+                write_register(registers, rd, next_pc);
+                if rd == 1 {
+                    self.call_stack.push(pc);
+                }
+                return Ok(pc.wrapping_add(offset));
+            }
+            Operation::Jalr { rd, base, offset } => {
+                let target = registers[base].wrapping_add(offset);
+                write_register(registers, rd, next_pc);
+                if rd == 1 {
+                    self.call_stack.push(pc);
+                } else if rd == 0 && base == 1 {
+                    self.call_stack.pop();
+                }
+                return Ok(target);
+            }
+            Operation::Auipc { rd, offset } => {
+                write_register(registers, rd, pc.wrapping_add(offset));
+            }
+            Operation::Executor => unreachable!("{opcode} is executed by the full executor"),
+        }
+        Ok(next_pc)
+    }
+
+    /// The values of the registers.
+    fn fast_registers(&self) -> [u32; 32] {
+        std::array::from_fn(|addr| {
+            let addr = addr as u32;
+            match self.state.memory.get(addr) {
+                Some(record) => record.value,
+                None => self.state.uninitialized_memory.get(addr).copied().unwrap_or(0),
+            }
+        })
+    }
+
+    /// Read an aligned word, which is a register below address 32.
+    ///
+    /// Unlike [`Executor::mr`], the read doesn't update the shard and timestamp of the word.
+    #[inline]
+    fn fast_read(&mut self, registers: &[u32; 32], addr: u32) -> u32 {
+        if addr < 32 {
+            return registers[addr as usize];
+        }
+        match self.state.memory.entry(addr) {
+            Entry::Occupied(entry) => entry.get().value,
+            Entry::Vacant(entry) => {
+                // If addr has a specific value to be initialized with, use that, otherwise 0.
+                let value = self.state.uninitialized_memory.get(addr).copied().unwrap_or(0);
+                entry.insert(MemoryRecord { value, shard: 0, timestamp: 0 }).value
+            }
+        }
+    }
+
+    /// Write an aligned word to memory.
+    ///
+    /// Unlike [`Executor::mw`], the write doesn't update the shard and timestamp of the word.
+    #[inline]
+    fn fast_write(&mut self, addr: u32, value: u32) {
+        match self.state.memory.entry(addr) {
+            Entry::Occupied(mut entry) => entry.get_mut().value = value,
+            Entry::Vacant(entry) => {
+                entry.insert(MemoryRecord { value, shard: 0, timestamp: 0 });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_primitives::consts::INPUT_REGIONS_PTR;
+    use sp1_stark::SP1CoreOpts;
+
+    use crate::{
+        programs::tests::{
+            fibonacci_program, panic_program, simple_memory_program, ssz_withdrawals_program,
+            CYCLE_TRACKER_ELF, FIBONACCI_IO_ELF, SHA2_ELF,
+        },
+        Executor, Instruction, Opcode, Program,
+    };
+
+    /// Run the program with the fast interpreter and with the full executor, and assert that they
+    /// end in the same state after the same number of cycles.
+    fn assert_same_execution(program: Program, opts: SP1CoreOpts, setup: impl Fn(&mut Executor)) {
+        let mut fast = Executor::new(program.clone(), opts);
+        setup(&mut fast);
+        let fast_result = fast.run_fast().map_err(|error| error.to_string());
+
+        let mut full = Executor::new(program, opts);
+        setup(&mut full);
+        let full_result = full.run().map_err(|error| error.to_string());
+
+        assert_eq!(fast_result, full_result);
+        assert_eq!(fast.state.global_clk, full.state.global_clk);
+        assert_eq!(fast.state.current_shard, full.state.current_shard);
+        assert_eq!(fast.state.clk, full.state.clk);
+        assert_eq!(fast.state.pc, full.state.pc);
+        assert_eq!(fast.registers(), full.registers());
+        assert_eq!(fast.state.public_values_stream, full.state.public_values_stream);
+        assert_eq!(fast.call_stack, full.call_stack);
+
+        // Only tracing counts the touched memory addresses.
+        fast.report.touched_memory_addresses = full.report.touched_memory_addresses;
+        assert_eq!(fast.report, full.report);
+
+        let addrs: Vec<_> = fast.state.memory.keys().chain(full.state.memory.keys()).collect();
+        for addr in addrs {
+            assert_eq!(fast.word(addr), full.word(addr), "word at {addr:#x}");
+        }
+    }
+
+    #[test]
+    fn test_programs() {
+        let opts = SP1CoreOpts::default();
+        assert_same_execution(fibonacci_program(), opts, |_| {});
+        assert_same_execution(ssz_withdrawals_program(), opts, |_| {});
+        assert_same_execution(simple_memory_program(), opts, |_| {});
+        assert_same_execution(panic_program(), opts, |_| {});
+        assert_same_execution(Program::from(SHA2_ELF).unwrap(), opts, |_| {});
+        assert_same_execution(Program::from(CYCLE_TRACKER_ELF).unwrap(), opts, |_| {});
+        assert_same_execution(Program::from(FIBONACCI_IO_ELF).unwrap(), opts, |runtime| {
+            runtime.write_stdin(&10u32);
+        });
+    }
+
+    #[test]
+    fn test_shards_and_cycle_limit() {
+        let program = Program::from(FIBONACCI_IO_ELF).unwrap();
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 1 << 8;
+        assert_same_execution(program.clone(), opts, |runtime| runtime.write_stdin(&10u32));
+        assert_same_execution(program, SP1CoreOpts::default(), |runtime| {
+            runtime.write_stdin(&10u32);
+            runtime.max_cycles = Some(1000);
+        });
+    }
+
+    #[test]
+    fn test_memory_instructions() {
+        fn setup(runtime: &mut Executor) {
+            runtime.state.uninitialized_memory.insert(0x2000, 0x1234_5678);
+            runtime.state.uninitialized_memory.insert(0x2004, 0x9abc_def0);
+            runtime.write_regions(&[b"hello, world".to_vec()]);
+        }
+
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 0x8765_8787, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, 0x1000, false, true),
+            Instruction::new(Opcode::SB, 29, 30, 1, false, true),
+            Instruction::new(Opcode::SH, 29, 30, 6, false, true),
+            Instruction::new(Opcode::LB, 1, 30, 1, false, true),
+            Instruction::new(Opcode::LBU, 3, 30, 1, false, true),
+            Instruction::new(Opcode::LH, 4, 30, 6, false, true),
+            Instruction::new(Opcode::LHU, 5, 30, 6, false, true),
+            Instruction::new(Opcode::LW, 6, 30, 4, false, true),
+            // Store to the word that holds %x8.
+            Instruction::new(Opcode::SW, 29, 0, 8, false, true),
+            Instruction::new(Opcode::ADD, 7, 8, 0, false, true),
+            // Read and write words that start with a value.
+            Instruction::new(Opcode::LW, 9, 0, 0x2000, false, true),
+            Instruction::new(Opcode::SB, 29, 0, 0x2001, false, true),
+            Instruction::new(Opcode::SB, 29, 0, 0x2005, false, true),
+            // Read an input region.
+            Instruction::new(Opcode::LW, 11, 0, INPUT_REGIONS_PTR - 12, false, true),
+            // Load from a misaligned address.
+            Instruction::new(Opcode::LW, 10, 30, 2, false, true),
+        ];
+        assert_same_execution(Program::new(instructions, 0, 0), SP1CoreOpts::default(), setup);
+
+        // Stores to the input regions fail.
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 0x8765_8787, false, true),
+            Instruction::new(Opcode::SH, 29, 0, INPUT_REGIONS_PTR - 10, false, true),
+        ];
+        assert_same_execution(Program::new(instructions, 0, 0), SP1CoreOpts::default(), setup);
+    }
+
+    #[test]
+    fn test_alu_instructions() {
+        const OPCODES: [Opcode; 23] = [
+            Opcode::ADD,
+            Opcode::SUB,
+            Opcode::XOR,
+            Opcode::OR,
+            Opcode::AND,
+            Opcode::SLL,
+            Opcode::SRL,
+            Opcode::SRA,
+            Opcode::SLT,
+            Opcode::SLTU,
+            Opcode::MUL,
+            Opcode::MULH,
+            Opcode::MULHU,
+            Opcode::MULHSU,
+            Opcode::DIV,
+            Opcode::DIVU,
+            Opcode::REM,
+            Opcode::REMU,
+            Opcode::ANDN,
+            Opcode::CLZ,
+            Opcode::MAX,
+            Opcode::ROR,
+            Opcode::SH2ADD,
+        ];
+
+        // A pseudorandom sequence of ALU instructions, after setting every register.
+        let mut seed = 0x2545_f491_u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            seed
+        };
+        let mut instructions: Vec<_> =
+            (1..32).map(|rd| Instruction::new(Opcode::ADD, rd, 0, next(), false, true)).collect();
+        for _ in 0..1000 {
+            let opcode = OPCODES[next() as usize % OPCODES.len()];
+            let (rd, rs1) = (next() % 32, next() % 32);
+            instructions.push(if next() % 2 == 0 {
+                Instruction::new(opcode, rd, rs1, next() % 32, false, false)
+            } else {
+                Instruction::new(opcode, rd, rs1, next(), false, true)
+            });
+        }
+        assert_same_execution(Program::new(instructions, 0, 0), SP1CoreOpts::default(), |_| {});
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+
+use crate::DebugInfo;
+
+/// The file descriptor the panic hook of the guest reports a panic to.
+pub const FD_PANIC: u32 = 11;
+
+/// A panic of the guest program, as reported by its panic hook.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestPanic {
+    /// The panic message.
+    pub message: String,
+    /// The source file the panic happened in, empty if unknown.
+    pub file: String,
+    /// The line in the source file, zero if unknown.
+    pub line: u32,
+    /// The column in the source file, zero if unknown.
+    pub column: u32,
+}
+
+impl GuestPanic {
+    /// Decode a report written to [`FD_PANIC`]: the little-endian `u32` line, column and length
+    /// of the file path, followed by the path and the message.
+    #[must_use]
+    pub fn decode(report: &[u8]) -> Option<Self> {
+        let (header, rest) = report.split_first_chunk::<12>()?;
+        let word = |i: usize| u32::from_le_bytes(header[i * 4..(i + 1) * 4].try_into().unwrap());
+        let file_len = word(2) as usize;
+        if file_len > rest.len() {
+            return None;
+        }
+        let (file, message) = rest.split_at(file_len);
+        Some(Self {
+            message: String::from_utf8_lossy(message).into_owned(),
+            file: String::from_utf8_lossy(file).into_owned(),
+            line: word(0),
+            column: word(1),
+        })
+    }
+}
+
+impl Display for GuestPanic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "panicked at {}:{}:{}: {}", self.file, self.line, self.column, self.message)
+    }
+}
+
+/// A frame of the call stack of the guest program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BacktraceFrame {
+    /// The address of the instruction, the call for every frame but the innermost.
+    pub pc: u32,
+    /// The function and source location of the instruction, if the program kept its debug info.
+    pub location: Option<String>,
+}
+
+impl Display for BacktraceFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{:#010x}", self.pc)?;
+        if let Some(location) = &self.location {
+            write!(f, " in {location}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The state of a program that halted with a non-zero exit code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HaltReport {
+    /// The exit code.
+    pub exit_code: u32,
+    /// The address of the `ecall` that halted.
+    pub pc: u32,
+    /// The panic the program reported before halting, if it panicked.
+    pub panic: Option<GuestPanic>,
+    /// The call stack at the halt, innermost frame first.
+    pub backtrace: Vec<BacktraceFrame>,
+}
+
+impl HaltReport {
+    /// Fill in the locations of the backtrace, for a program that was loaded without its debug
+    /// info.
+    pub fn symbolize(&mut self, debug_info: &DebugInfo) {
+        for frame in self.backtrace.iter_mut().filter(|frame| frame.location.is_none()) {
+            frame.location = debug_info.describe(frame.pc);
+        }
+    }
+}
+
+impl Display for HaltReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "exit code {} at pc {:#x}", self.exit_code, self.pc)?;
+        if let Some(location) = self.backtrace.first().and_then(|frame| frame.location.as_ref()) {
+            write!(f, " in {location}")?;
+        }
+        if let Some(panic) = &self.panic {
+            write!(f, ": {panic}")?;
+        }
+        for frame in self.backtrace.iter().skip(1) {
+            write!(f, "\n    called from {frame}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_report() {
+        let mut report = [7u32, 5, 7].map(u32::to_le_bytes).concat();
+        report.extend_from_slice(b"main.rsoh no");
+        let panic = GuestPanic::decode(&report).unwrap();
+        assert_eq!(panic.to_string(), "panicked at main.rs:7:5: oh no");
+
+        assert_eq!(GuestPanic::decode(&report[..12 + 6]), None);
+        assert_eq!(GuestPanic::decode(&report[..8]), None);
+    }
+}
@@ -1,8 +1,15 @@
 use core::fmt::Debug;
 
-use std::sync::{Arc, RwLock, RwLockWriteGuard};
+use std::{
+    future::Future,
+    sync::{Arc, RwLock, RwLockWriteGuard},
+};
 
+use curve25519_dalek::{edwards::CompressedEdwardsY, EdwardsPoint, Scalar};
 use hashbrown::HashMap;
+use num::{BigUint, One, Zero};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha512};
 use sp1_curves::k256::{Invert, RecoveryId, Signature, VerifyingKey};
 
 use crate::Executor;
@@ -13,6 +20,18 @@ pub type BoxedHook<'a> = Arc<RwLock<dyn Hook + Send + Sync + 'a>>;
 /// The file descriptor through which to access `hook_ecrecover`.
 pub const FD_ECRECOVER_HOOK: u32 = 5;
 
+/// The file descriptor through which to access `hook_ed25519_verify`.
+pub const FD_ED25519_VERIFY_HOOK: u32 = 6;
+
+/// The file descriptor through which to access `hook_mod_inverse`.
+pub const FD_MOD_INVERSE_HOOK: u32 = 7;
+
+/// The file descriptor through which to access `hook_mod_sqrt`.
+pub const FD_MOD_SQRT_HOOK: u32 = 8;
+
+/// The file descriptor through which to access `hook_sort_permutation`.
+pub const FD_SORT_PERMUTATION_HOOK: u32 = 9;
+
 /// A runtime hook. May be called during execution by writing to a specified file descriptor,
 /// accepting and returning arbitrary data.
 pub trait Hook {
@@ -37,6 +56,40 @@ pub fn hookify<'a>(
     Arc::new(RwLock::new(f))
 }
 
+/// Wrap a function that computes the result of a hook asynchronously, like a query to a
+/// database, in a smart pointer so it may be placed in a `HookRegistry`.
+///
+/// The function reads what it needs from the environment and the data, and returns a future that
+/// owns everything it uses. The executor is synchronous, so the future is polled to completion on
+/// the executor's thread with [`futures::executor::block_on`]. A future that needs a tokio
+/// runtime should be spawned on that runtime, awaiting its `JoinHandle` instead.
+pub fn hookify_async<'a, Fut: Future<Output = Vec<Vec<u8>>> + 'a>(
+    mut f: impl FnMut(HookEnv, &[u8]) -> Fut + Send + Sync + 'a,
+) -> BoxedHook<'a> {
+    hookify(move |env, buf| futures::executor::block_on(f(env, buf)))
+}
+
+/// Turn a function from a deserializable request to a serializable response into a hook.
+///
+/// The data written by the program is deserialized with bincode, and the response is returned as
+/// a single serialized item, as expected by `sp1_zkvm::io::hook`.
+///
+/// If the data written by the program is not a valid `Req`, the response is empty, so the program
+/// fails when it reads the response instead of the host panicking.
+pub fn typed_hook<'a, Req: DeserializeOwned + 'a, Resp: Serialize + 'a>(
+    mut f: impl FnMut(HookEnv, Req) -> Resp + Send + Sync + 'a,
+) -> impl FnMut(HookEnv, &[u8]) -> Vec<Vec<u8>> + Send + Sync + 'a {
+    move |env, buf| match bincode::deserialize(buf) {
+        Ok(request) => {
+            vec![bincode::serialize(&f(env, request)).expect("failed to serialize hook response")]
+        }
+        Err(error) => {
+            tracing::warn!("ignoring an invalid hook request: {error}");
+            vec![]
+        }
+    }
+}
+
 /// A registry of hooks to call, indexed by the file descriptors through which they are accessed.
 #[derive(Clone)]
 pub struct HookRegistry<'a> {
@@ -76,6 +129,10 @@ impl<'a> Default for HookRegistry<'a> {
             // Note: To ensure any `fd` value is synced with `zkvm/precompiles/src/io.rs`,
             // add an assertion to the test `hook_fds_match` below.
             (FD_ECRECOVER_HOOK, hookify(hook_ecrecover)),
+            (FD_ED25519_VERIFY_HOOK, hookify(typed_hook(hook_ed25519_verify))),
+            (FD_MOD_INVERSE_HOOK, hookify(typed_hook(hook_mod_inverse))),
+            (FD_MOD_SQRT_HOOK, hookify(typed_hook(hook_mod_sqrt))),
+            (FD_SORT_PERMUTATION_HOOK, hookify(typed_hook(hook_sort_permutation))),
         ]);
 
         Self { table }
@@ -101,6 +158,45 @@ pub struct HookEnv<'a, 'b: 'a> {
     pub runtime: &'a Executor<'b>,
 }
 
+impl<'a, 'b: 'a> HookEnv<'a, 'b> {
+    /// The number of cycles executed so far.
+    #[must_use]
+    pub fn cycle(&self) -> u64 {
+        self.runtime.state.global_clk
+    }
+
+    /// The address of the instruction that invoked the hook.
+    #[must_use]
+    pub fn pc(&self) -> u32 {
+        self.runtime.state.pc
+    }
+
+    /// Read the word of guest memory at the word-aligned address `addr`.
+    ///
+    /// Unlike reads by the program, this isn't recorded as a memory access.
+    #[must_use]
+    pub fn word(&self, addr: u32) -> u32 {
+        let state = &self.runtime.state;
+        match state.memory.get(addr) {
+            Some(record) => record.value,
+            None => state.uninitialized_memory.get(addr).copied().unwrap_or(0),
+        }
+    }
+
+    /// Read `len` bytes of guest memory starting at `addr`.
+    ///
+    /// Unlike reads by the program, this isn't recorded as a memory access.
+    #[must_use]
+    pub fn bytes(&self, addr: u32, len: usize) -> Vec<u8> {
+        (0..len as u32)
+            .map(|i| {
+                let addr = addr.wrapping_add(i);
+                self.word(addr & !3).to_le_bytes()[(addr & 3) as usize]
+            })
+            .collect()
+    }
+}
+
 /// Recovers the public key from the signature and message hash using the k256 crate.
 ///
 /// # Arguments
@@ -141,14 +237,179 @@ pub fn hook_ecrecover(_: HookEnv, buf: &[u8]) -> Vec<Vec<u8>> {
     vec![bytes.to_vec(), s_inverse.to_bytes().to_vec()]
 }
 
+/// Checks which of a batch of Ed25519 signatures are valid.
+///
+/// The request is a list of public keys, signatures and messages, and the response says whether
+/// each signature is valid. Keys and signatures of the wrong length are invalid.
+///
+/// WARNING: The program can use this to skip verifying signatures it would reject anyway, but
+/// must still verify the ones it accepts, since the response is not constrained.
+#[must_use]
+#[allow(clippy::needless_pass_by_value)]
+pub fn hook_ed25519_verify(_: HookEnv, batch: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>) -> Vec<bool> {
+    batch
+        .iter()
+        .map(|(public_key, signature, message)| ed25519_verify(public_key, signature, message))
+        .collect()
+}
+
+/// Whether `signature` is a valid Ed25519 signature of `message` by `public_key`.
+fn ed25519_verify(public_key: &[u8], signature: &[u8], message: &[u8]) -> bool {
+    let (Ok(public_key), Ok(signature)) =
+        (<[u8; 32]>::try_from(public_key), <[u8; 64]>::try_from(signature))
+    else {
+        return false;
+    };
+    let Some(a) = CompressedEdwardsY(public_key).decompress() else {
+        return false;
+    };
+    let r = CompressedEdwardsY(signature[..32].try_into().unwrap());
+    let Some(s) =
+        Option::<Scalar>::from(Scalar::from_canonical_bytes(signature[32..].try_into().unwrap()))
+    else {
+        return false;
+    };
+
+    let hash =
+        Sha512::new().chain_update(r.as_bytes()).chain_update(public_key).chain_update(message);
+    let mut wide = [0u8; 64];
+    wide.copy_from_slice(&hash.finalize());
+    let k = Scalar::from_bytes_mod_order_wide(&wide);
+
+    // The signature is valid if `R = [s]B - [k]A`.
+    EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-a, &s).compress() == r
+}
+
+/// Computes the inverse of a number modulo another.
+///
+/// The request is the number and the modulus as little-endian bytes, and the response is the
+/// inverse as little-endian bytes, or `None` if the number has no inverse.
+///
+/// WARNING: The response is not constrained, so the program must check that the product of the
+/// number and its inverse is one.
+#[must_use]
+#[allow(clippy::needless_pass_by_value)]
+pub fn hook_mod_inverse(_: HookEnv, (value, modulus): (Vec<u8>, Vec<u8>)) -> Option<Vec<u8>> {
+    let modulus = BigUint::from_bytes_le(&modulus);
+    if modulus.is_zero() {
+        return None;
+    }
+    BigUint::from_bytes_le(&value).modinv(&modulus).map(|inverse| inverse.to_bytes_le())
+}
+
+/// Computes a square root of a number modulo a prime.
+///
+/// The request is the number and the prime as little-endian bytes, and the response is the
+/// smaller of the two square roots as little-endian bytes, or `None` if the number is not a
+/// square or the modulus is not an odd prime.
+///
+/// WARNING: The response is not constrained, so the program must check that the square of the
+/// root is the number.
+#[must_use]
+#[allow(clippy::needless_pass_by_value)]
+pub fn hook_mod_sqrt(_: HookEnv, (value, prime): (Vec<u8>, Vec<u8>)) -> Option<Vec<u8>> {
+    let prime = BigUint::from_bytes_le(&prime);
+    let value = BigUint::from_bytes_le(&value);
+    mod_sqrt(&value, &prime).map(|root| root.to_bytes_le())
+}
+
+/// The number of candidates tried when looking for a non-square modulo the prime.
+///
+/// Half of the numbers are non-squares modulo an odd prime, so the search only runs out of
+/// candidates with probability `2^-256`, unless the modulus is not prime.
+const MAX_NON_SQUARE_CANDIDATES: u32 = 256;
+
+/// A square root of `value` modulo `prime`, found with the Tonelli-Shanks algorithm.
+///
+/// The variables are named as in the usual statement of the algorithm (`q`, `s`, `z`, `m`, `c`,
+/// `t`, `b`), so that it can be checked against it.
+#[allow(clippy::many_single_char_names)]
+fn mod_sqrt(value: &BigUint, prime: &BigUint) -> Option<BigUint> {
+    let one = BigUint::one();
+    if prime <= &BigUint::from(2u32) || !prime.bit(0) {
+        return None;
+    }
+    let value = value % prime;
+    if value.is_zero() {
+        return Some(value);
+    }
+
+    // A number is a square if and only if `value^((p - 1) / 2) = 1` (Euler's criterion).
+    let p_minus_one = prime - 1u32;
+    let half = &p_minus_one >> 1;
+    if value.modpow(&half, prime) != one {
+        return None;
+    }
+
+    // Write `p - 1 = q * 2^s` with `q` odd, and find a non-square `z`. A composite modulus may
+    // have none, so the search is bounded.
+    let s = p_minus_one.trailing_zeros().unwrap();
+    let q = &p_minus_one >> s;
+    let z = (2..2 + MAX_NON_SQUARE_CANDIDATES)
+        .map(BigUint::from)
+        .take_while(|z| z < prime)
+        .find(|z| z.modpow(&half, prime) == p_minus_one)?;
+
+    let mut m = s;
+    let mut c = z.modpow(&q, prime);
+    let mut t = value.modpow(&q, prime);
+    let mut root = value.modpow(&((&q + 1u32) >> 1), prime);
+    while t != one {
+        // Find the least `i` with `t^(2^i) = 1`.
+        let mut i = 0;
+        let mut t_pow = t.clone();
+        while t_pow != one {
+            t_pow = &t_pow * &t_pow % prime;
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+        let b = c.modpow(&(BigUint::one() << (m - i - 1)), prime);
+        m = i;
+        c = &b * &b % prime;
+        t = t * &c % prime;
+        root = root * b % prime;
+    }
+
+    // The modulus may not be prime, so the root is checked.
+    if &root * &root % prime != value {
+        return None;
+    }
+    let other = prime - &root;
+    Some(root.min(other))
+}
+
+/// Computes the permutation that sorts a list.
+///
+/// The request is a list of byte strings, and the response is the list of their indices in the
+/// order that sorts them lexicographically. Equal strings keep their order.
+///
+/// WARNING: The response is not constrained, so the program must check that it is a permutation
+/// and that the permuted list is sorted.
+#[must_use]
+#[allow(clippy::needless_pass_by_value)]
+pub fn hook_sort_permutation(_: HookEnv, list: Vec<Vec<u8>>) -> Vec<u32> {
+    let mut permutation = (0..list.len() as u32).collect::<Vec<_>>();
+    permutation.sort_by(|&i, &j| list[i as usize].cmp(&list[j as usize]));
+    permutation
+}
+
 #[cfg(test)]
 pub mod tests {
+    use sp1_stark::SP1CoreOpts;
+
     use super::*;
+    use crate::{events::MemoryRecord, programs::tests::simple_program};
 
     #[test]
     pub fn hook_fds_match() {
         use sp1_zkvm::lib::io;
         assert_eq!(FD_ECRECOVER_HOOK, io::FD_ECRECOVER_HOOK);
+        assert_eq!(FD_ED25519_VERIFY_HOOK, io::FD_ED25519_VERIFY_HOOK);
+        assert_eq!(FD_MOD_INVERSE_HOOK, io::FD_MOD_INVERSE_HOOK);
+        assert_eq!(FD_MOD_SQRT_HOOK, io::FD_MOD_SQRT_HOOK);
+        assert_eq!(FD_SORT_PERMUTATION_HOOK, io::FD_SORT_PERMUTATION_HOOK);
     }
 
     #[test]
@@ -161,4 +422,86 @@ pub mod tests {
     pub fn registry_empty_is_empty() {
         assert_eq!(HookRegistry::empty().table.len(), 0);
     }
+
+    fn executor() -> Executor<'static> {
+        Executor::new(simple_program(), SP1CoreOpts::default())
+    }
+
+    #[test]
+    pub fn env_reads_memory() {
+        let mut executor = executor();
+        executor
+            .state
+            .memory
+            .insert(0x100, MemoryRecord { shard: 0, timestamp: 0, value: 0x04030201 });
+        executor.state.uninitialized_memory.insert(0x104, 0x08070605);
+
+        let env = executor.hook_env();
+        assert_eq!(env.word(0x100), 0x04030201);
+        assert_eq!(env.bytes(0x102, 4), vec![3, 4, 5, 6]);
+        assert_eq!(env.word(0x108), 0);
+        assert_eq!(env.cycle(), 0);
+    }
+
+    #[test]
+    pub fn typed_and_async_hooks() {
+        let executor = executor();
+        let mut hook = typed_hook(|_, (a, b): (u32, u32)| a + b);
+        let response = hook(executor.hook_env(), &bincode::serialize(&(2u32, 3u32)).unwrap());
+        assert_eq!(response, vec![bincode::serialize(&5u32).unwrap()]);
+        assert!(hook(executor.hook_env(), &[1, 2, 3]).is_empty());
+
+        let hook = hookify_async(|_, buf| {
+            let len = buf.len() as u8;
+            async move { vec![vec![len]] }
+        });
+        let response = hook.write().unwrap().invoke_hook(executor.hook_env(), &[0; 3]);
+        assert_eq!(response, vec![vec![3]]);
+    }
+
+    #[test]
+    pub fn ed25519_verify_hook() {
+        // The first test vector of RFC 8032, which signs an empty message.
+        let public_key =
+            hex::decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")
+                .unwrap();
+        let signature = hex::decode(
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        )
+        .unwrap();
+        let mut forged = signature.clone();
+        forged[0] ^= 1;
+
+        let batch = vec![
+            (public_key.clone(), signature, vec![]),
+            (public_key.clone(), forged, vec![]),
+            (public_key, vec![0; 63], vec![]),
+        ];
+        assert_eq!(hook_ed25519_verify(executor().hook_env(), batch), vec![true, false, false]);
+    }
+
+    #[test]
+    pub fn mod_inverse_and_sqrt_hooks() {
+        let executor = executor();
+        assert_eq!(hook_mod_inverse(executor.hook_env(), (vec![3], vec![7])), Some(vec![5]));
+        assert_eq!(hook_mod_inverse(executor.hook_env(), (vec![2], vec![4])), None);
+        assert_eq!(hook_mod_inverse(executor.hook_env(), (vec![2], vec![])), None);
+
+        // Since 17 - 1 = 2^4, unlike 7 - 1 = 2 * 3, the root of 2 modulo 17 takes several steps.
+        assert_eq!(hook_mod_sqrt(executor.hook_env(), (vec![2], vec![7])), Some(vec![3]));
+        assert_eq!(hook_mod_sqrt(executor.hook_env(), (vec![2], vec![17])), Some(vec![6]));
+        assert_eq!(hook_mod_sqrt(executor.hook_env(), (vec![3], vec![17])), None);
+        assert_eq!(hook_mod_sqrt(executor.hook_env(), (vec![4], vec![8])), None);
+
+        // 1 passes Euler's criterion for any modulus, but no small number is a non-square modulo
+        // 9 * (2^61 - 1), which an unbounded search would take about 2^64 steps to find out.
+        let composite = BigUint::from(9u32) * ((BigUint::one() << 61u32) - 1u32);
+        assert_eq!(hook_mod_sqrt(executor.hook_env(), (vec![1], composite.to_bytes_le())), None);
+    }
+
+    #[test]
+    pub fn sort_permutation_hook() {
+        let list = vec![vec![3], vec![1, 2], vec![1], vec![3]];
+        assert_eq!(hook_sort_permutation(executor().hook_env(), list), vec![2, 1, 0, 3]);
+    }
 }
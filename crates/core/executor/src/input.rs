@@ -0,0 +1,21 @@
+use std::sync::{Arc, Mutex};
+
+/// An input provider, wrapped in a smart pointer.
+pub type BoxedInputProvider<'a> = Arc<Mutex<dyn InputProvider + 'a>>;
+
+/// A source of input items that the guest reads with `HINT_LEN` and `HINT_READ`, pulled lazily
+/// once the items written to the input stream have all been read.
+///
+/// Unlike the buffers of `SP1Stdin`, the input doesn't have to be built before execution, so it
+/// can be streamed from disk or computed from what the guest has output so far.
+pub trait InputProvider: Send {
+    /// Returns the next input item, or `None` if there are no more.
+    fn next_input(&mut self) -> Option<Vec<u8>>;
+}
+
+impl<F: FnMut() -> Option<Vec<u8>> + Send> InputProvider for F {
+    /// Calls the function `self` for the next input item.
+    fn next_input(&mut self) -> Option<Vec<u8>> {
+        self()
+    }
+}
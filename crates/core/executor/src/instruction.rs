@@ -24,6 +24,8 @@ pub struct Instruction {
     pub imm_b: bool,
     /// Whether the third operand is an immediate value.
     pub imm_c: bool,
+    /// Whether the instruction was decoded from a 16-bit compressed instruction.
+    pub compressed: bool,
 }
 
 impl Instruction {
@@ -37,33 +39,51 @@ impl Instruction {
         imm_b: bool,
         imm_c: bool,
     ) -> Self {
-        Self { opcode, op_a, op_b, op_c, imm_b, imm_c }
+        Self { opcode, op_a, op_b, op_c, imm_b, imm_c, compressed: false }
+    }
+
+    /// Mark the [`Instruction`] as decoded from a 16-bit compressed instruction.
+    #[must_use]
+    pub const fn into_compressed(self) -> Self {
+        Self { compressed: true, ..self }
+    }
+
+    /// The size of the encoded [`Instruction`] in bytes, which the program counter advances by.
+    #[inline]
+    #[must_use]
+    pub const fn size(&self) -> u32 {
+        if self.compressed {
+            2
+        } else {
+            4
+        }
     }
 
     /// Returns if the instruction is an ALU instruction.
     #[must_use]
     pub const fn is_alu_instruction(&self) -> bool {
-        matches!(
-            self.opcode,
-            Opcode::ADD
-                | Opcode::SUB
-                | Opcode::XOR
-                | Opcode::OR
-                | Opcode::AND
-                | Opcode::SLL
-                | Opcode::SRL
-                | Opcode::SRA
-                | Opcode::SLT
-                | Opcode::SLTU
-                | Opcode::MUL
-                | Opcode::MULH
-                | Opcode::MULHU
-                | Opcode::MULHSU
-                | Opcode::DIV
-                | Opcode::DIVU
-                | Opcode::REM
-                | Opcode::REMU
-        )
+        self.opcode.is_bitmanip()
+            || matches!(
+                self.opcode,
+                Opcode::ADD
+                    | Opcode::SUB
+                    | Opcode::XOR
+                    | Opcode::OR
+                    | Opcode::AND
+                    | Opcode::SLL
+                    | Opcode::SRL
+                    | Opcode::SRA
+                    | Opcode::SLT
+                    | Opcode::SLTU
+                    | Opcode::MUL
+                    | Opcode::MULH
+                    | Opcode::MULHU
+                    | Opcode::MULHSU
+                    | Opcode::DIV
+                    | Opcode::DIVU
+                    | Opcode::REM
+                    | Opcode::REMU
+            )
     }
 
     /// Returns if the instruction is a ecall instruction.
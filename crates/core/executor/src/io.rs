@@ -1,10 +1,14 @@
 use std::io::Read;
 
 use serde::{de::DeserializeOwned, Serialize};
+use sp1_primitives::{
+    consts::{input_regions_layout, INPUT_REGIONS_PTR, MAX_MEMORY, WORD_SIZE},
+    io::SP1PublicValues,
+};
 use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkVerifyingKey};
 
 use super::Executor;
-use crate::SP1ReduceProof;
+use crate::{ExecutorMode, SP1ReduceProof};
 
 impl<'a> Read for Executor<'a> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
@@ -33,6 +37,90 @@ impl<'a> Executor<'a> {
         }
     }
 
+    /// Pull the next item into the input stream if all of its items have been read.
+    ///
+    /// The item comes from the inputs recorded in the state when replaying a checkpoint, and from
+    /// the [`crate::InputProvider`] otherwise. Items pulled while creating a checkpoint are
+    /// recorded, so that the checkpoint reads the same input when it's replayed.
+    pub(crate) fn pull_input(&mut self) {
+        if self.state.input_stream_ptr < self.state.input_stream.len() {
+            return;
+        }
+        let input = match self.state.provided_inputs.pop_front() {
+            Some(input) => Some(input),
+            None => self.input_provider.as_ref().and_then(|provider| {
+                // Calling `.unwrap()` panics on a poisoned lock. Should never happen normally.
+                provider.lock().unwrap().next_input()
+            }),
+        };
+        if let Some(input) = input {
+            if self.executor_mode == ExecutorMode::Checkpoint {
+                self.provided_inputs.push(input.clone());
+            }
+            self.state.input_stream.push(input);
+        }
+    }
+
+    /// Map read-only input regions into the top of memory, after a table of their addresses and
+    /// lengths whose address is at [`INPUT_REGIONS_PTR`].
+    ///
+    /// The words are placed in uninitialized memory, so only the words the program reads are
+    /// initialized in the proof. They are not constrained otherwise: `sp1_zkvm::io::input_region`
+    /// commits to the digest of each region it hands out, once, in a channel of public values.
+    /// Stores to the regions fail with an
+    /// [`crate::ExecutionError::InvalidMemoryAccess`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regions don't fit in memory or overlap the program.
+    pub fn write_regions(&mut self, regions: &[Vec<u8>]) {
+        if regions.is_empty() {
+            return;
+        }
+        let lens = regions.iter().map(Vec::len).collect::<Vec<_>>();
+        let (table_addr, addresses) =
+            input_regions_layout(&lens).expect("the input regions don't fit in memory");
+        assert!(
+            self.program.memory_image.keys().all(|addr| *addr < table_addr),
+            "the input regions overlap the program"
+        );
+        self.state.input_regions_start = table_addr;
+
+        let mut table = vec![regions.len() as u32];
+        for (region, addr) in regions.iter().zip(addresses) {
+            table.extend([addr, region.len() as u32]);
+            for (i, chunk) in region.chunks(WORD_SIZE).enumerate() {
+                let mut word = [0; WORD_SIZE];
+                word[..chunk.len()].copy_from_slice(chunk);
+                self.write_uninitialized_word(
+                    addr + (i * WORD_SIZE) as u32,
+                    u32::from_le_bytes(word),
+                );
+            }
+        }
+        for (i, word) in table.into_iter().enumerate() {
+            self.write_uninitialized_word(table_addr + (i * WORD_SIZE) as u32, word);
+        }
+        self.write_uninitialized_word(INPUT_REGIONS_PTR, table_addr);
+    }
+
+    fn write_uninitialized_word(&mut self, addr: u32, word: u32) {
+        // Uninitialized memory reads as zero anyway.
+        if word != 0 {
+            self.state.uninitialized_memory.insert(addr, word);
+        }
+    }
+
+    /// Whether `addr` is in the read-only input regions, their table or the word pointing to it.
+    #[inline]
+    pub(crate) fn is_input_region(&self, addr: u32) -> bool {
+        let start = match self.state.input_regions_start {
+            0 => INPUT_REGIONS_PTR,
+            start => start,
+        };
+        (start..MAX_MEMORY).contains(&addr)
+    }
+
     /// Write a proof and verifying key to the proof stream.
     pub fn write_proof(
         &mut self,
@@ -57,4 +145,13 @@ impl<'a> Executor<'a> {
         buf.copy_from_slice(&self.state.public_values_stream[start..end]);
         self.state.public_values_stream_ptr = end;
     }
+
+    /// The public values committed by the program, with its named channels.
+    #[must_use]
+    pub fn public_values(&self) -> SP1PublicValues {
+        SP1PublicValues::from_channels(
+            &self.state.public_values_stream,
+            self.state.public_value_channels.clone(),
+        )
+    }
 }
@@ -19,19 +19,27 @@
 #![allow(clippy::explicit_iter_loop)]
 #![warn(missing_docs)]
 
+pub mod conformance;
 mod context;
+mod debug_info;
 mod dependencies;
 mod disassembler;
 pub mod events;
 mod executor;
+mod fast;
+mod guest_panic;
 mod hook;
+mod input;
 mod instruction;
 mod io;
 mod memory;
 mod opcode;
+mod output;
+mod pool;
 mod program;
 #[cfg(any(test, feature = "programs"))]
 pub mod programs;
+mod progress;
 mod record;
 mod reduce;
 mod register;
@@ -41,13 +49,21 @@ mod state;
 pub mod subproof;
 pub mod syscalls;
 mod utils;
+mod validation;
 
 pub use context::*;
+pub use debug_info::*;
+pub use disassembler::ElfError;
 pub use executor::*;
+pub use guest_panic::*;
 pub use hook::*;
+pub use input::*;
 pub use instruction::*;
 pub use opcode::*;
+pub use output::*;
+pub use pool::*;
 pub use program::*;
+pub use progress::*;
 pub use record::*;
 pub use reduce::*;
 pub use register::*;
@@ -55,3 +71,4 @@ pub use report::*;
 pub use shape::*;
 pub use state::*;
 pub use utils::*;
+pub use validation::*;
@@ -101,6 +101,46 @@ pub enum Opcode {
     REMU = 37,
     /// Unimplemented instruction.
     UNIMP = 39,
+    /// rd ← rs1 & ~rs2, pc ← pc + 4
+    ANDN = 40,
+    /// rd ← rs1 | ~rs2, pc ← pc + 4
+    ORN = 41,
+    /// rd ← ~(rs1 ^ rs2), pc ← pc + 4
+    XNOR = 42,
+    /// rd ← number of leading zero bits of rs1, pc ← pc + 4
+    CLZ = 43,
+    /// rd ← number of trailing zero bits of rs1, pc ← pc + 4
+    CTZ = 44,
+    /// rd ← number of set bits of rs1, pc ← pc + 4
+    CPOP = 45,
+    /// rd ← max(rs1, rs2) (signed), pc ← pc + 4
+    MAX = 46,
+    /// rd ← max(rs1, rs2) (unsigned), pc ← pc + 4
+    MAXU = 47,
+    /// rd ← min(rs1, rs2) (signed), pc ← pc + 4
+    MIN = 48,
+    /// rd ← min(rs1, rs2) (unsigned), pc ← pc + 4
+    MINU = 49,
+    /// rd ← sx(rs1[7:0]), pc ← pc + 4
+    SEXTB = 50,
+    /// rd ← sx(rs1[15:0]), pc ← pc + 4
+    SEXTH = 51,
+    /// rd ← zx(rs1[15:0]), pc ← pc + 4
+    ZEXTH = 52,
+    /// rd ← rs1 rotated left by rs2[4:0], pc ← pc + 4
+    ROL = 53,
+    /// rd ← rs1 rotated right by rs2[4:0], pc ← pc + 4
+    ROR = 54,
+    /// rd ← each byte of rs1 set to 0xff if non-zero, pc ← pc + 4
+    ORCB = 55,
+    /// rd ← rs1 with its bytes reversed, pc ← pc + 4
+    REV8 = 56,
+    /// rd ← (rs1 << 1) + rs2, pc ← pc + 4
+    SH1ADD = 57,
+    /// rd ← (rs1 << 2) + rs2, pc ← pc + 4
+    SH2ADD = 58,
+    /// rd ← (rs1 << 3) + rs2, pc ← pc + 4
+    SH3ADD = 59,
 }
 
 /// Byte Opcode.
@@ -174,9 +214,57 @@ impl Opcode {
             Opcode::REM => "rem",
             Opcode::REMU => "remu",
             Opcode::UNIMP => "unimp",
+            Opcode::ANDN => "andn",
+            Opcode::ORN => "orn",
+            Opcode::XNOR => "xnor",
+            Opcode::CLZ => "clz",
+            Opcode::CTZ => "ctz",
+            Opcode::CPOP => "cpop",
+            Opcode::MAX => "max",
+            Opcode::MAXU => "maxu",
+            Opcode::MIN => "min",
+            Opcode::MINU => "minu",
+            Opcode::SEXTB => "sext.b",
+            Opcode::SEXTH => "sext.h",
+            Opcode::ZEXTH => "zext.h",
+            Opcode::ROL => "rol",
+            Opcode::ROR => "ror",
+            Opcode::ORCB => "orc.b",
+            Opcode::REV8 => "rev8",
+            Opcode::SH1ADD => "sh1add",
+            Opcode::SH2ADD => "sh2add",
+            Opcode::SH3ADD => "sh3add",
         }
     }
 
+    /// Whether the opcode is one of the Zba/Zbb bit-manipulation instructions.
+    #[must_use]
+    pub const fn is_bitmanip(&self) -> bool {
+        matches!(
+            self,
+            Opcode::ANDN
+                | Opcode::ORN
+                | Opcode::XNOR
+                | Opcode::CLZ
+                | Opcode::CTZ
+                | Opcode::CPOP
+                | Opcode::MAX
+                | Opcode::MAXU
+                | Opcode::MIN
+                | Opcode::MINU
+                | Opcode::SEXTB
+                | Opcode::SEXTH
+                | Opcode::ZEXTH
+                | Opcode::ROL
+                | Opcode::ROR
+                | Opcode::ORCB
+                | Opcode::REV8
+                | Opcode::SH1ADD
+                | Opcode::SH2ADD
+                | Opcode::SH3ADD
+        )
+    }
+
     /// Convert the opcode to a field element.
     #[must_use]
     pub fn as_field<F: Field>(self) -> F {
@@ -0,0 +1,36 @@
+use std::{
+    io::{Result, Write},
+    sync::{Arc, Mutex},
+};
+
+/// A sink for what a program writes to stdout or stderr, wrapped in a smart pointer.
+pub type OutputSink<'a> = Arc<Mutex<dyn Write + Send + 'a>>;
+
+/// A buffer that can be set as an output sink and read once execution is done.
+#[derive(Debug, Clone, Default)]
+pub struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    /// Create an empty [`SharedBuffer`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The bytes written to the buffer so far.
+    #[must_use]
+    pub fn contents(&self) -> Vec<u8> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Mutex,
+};
+
+use sp1_stark::SP1CoreOpts;
+
+use crate::{Executor, Program, SP1Context};
+
+/// A pool of runtimes for a program, reset and reused across runs so that each run doesn't pay
+/// for building the syscall map and allocating memory again.
+///
+/// Each run gets its own context, so input providers and output sinks are not shared between
+/// runs.
+pub struct ExecutorPool<'a> {
+    program: Program,
+    opts: SP1CoreOpts,
+    executors: Mutex<Vec<Executor<'a>>>,
+}
+
+impl<'a> ExecutorPool<'a> {
+    /// Create an empty [`ExecutorPool`] for a program and options.
+    #[must_use]
+    pub fn new(program: Program, opts: SP1CoreOpts) -> Self {
+        Self { program, opts, executors: Mutex::new(Vec::new()) }
+    }
+
+    /// Take a runtime ready to run the program on `stdin` with `context` from the pool, or create
+    /// one if the pool is empty.
+    ///
+    /// The runtime goes back to the pool when the returned guard is dropped.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the context registers a syscall on the host with a code that isn't
+    /// reserved for it.
+    pub fn get(&self, context: SP1Context<'a>, stdin: &[Vec<u8>]) -> PooledExecutor<'_, 'a> {
+        let pooled = self.executors.lock().unwrap().pop();
+        let executor = if let Some(mut executor) = pooled {
+            executor.reset(stdin);
+            executor.set_context(context);
+            executor
+        } else {
+            let mut executor = Executor::with_context(self.program.clone(), self.opts, context);
+            executor.write_vecs(stdin);
+            executor
+        };
+        PooledExecutor { pool: self, executor: Some(executor) }
+    }
+
+    /// The number of idle runtimes in the pool.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.executors.lock().unwrap().len()
+    }
+
+    /// Whether the pool has no idle runtimes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A runtime taken from an [`ExecutorPool`], returned to it on drop.
+pub struct PooledExecutor<'p, 'a> {
+    pool: &'p ExecutorPool<'a>,
+    executor: Option<Executor<'a>>,
+}
+
+impl<'a> Deref for PooledExecutor<'_, 'a> {
+    type Target = Executor<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.executor.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledExecutor<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.executor.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledExecutor<'_, '_> {
+    fn drop(&mut self) {
+        if let Some(executor) = self.executor.take() {
+            self.pool.executors.lock().unwrap().push(executor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_stark::SP1CoreOpts;
+
+    use super::ExecutorPool;
+    use crate::{programs::tests::FIBONACCI_IO_ELF, Program, SP1Context};
+
+    #[test]
+    fn test_pool_reuses_executors() {
+        let program = Program::from(FIBONACCI_IO_ELF).unwrap();
+        let pool = ExecutorPool::new(program, SP1CoreOpts::default());
+        for n in [10u32, 20, 10] {
+            let stdin = [bincode::serialize(&n).unwrap()];
+            let mut runtime = pool.get(SP1Context::default(), &stdin);
+            runtime.run_fast().unwrap();
+            assert_eq!(runtime.read_public_values::<u32>(), n);
+        }
+        assert_eq!(pool.len(), 1);
+
+        // A reused runtime takes the context of its run.
+        let runtime = pool.get(SP1Context::builder().max_cycles(10).build(), &[]);
+        assert_eq!(runtime.max_cycles, Some(10));
+        drop(runtime);
+        let runtime = pool.get(SP1Context::default(), &[]);
+        assert_eq!(runtime.max_cycles, None);
+        drop(runtime);
+
+        let first = pool.get(SP1Context::default(), &[]);
+        let second = pool.get(SP1Context::default(), &[]);
+        drop((first, second));
+        assert_eq!(pool.len(), 2);
+    }
+}
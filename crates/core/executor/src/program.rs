@@ -1,6 +1,10 @@
 //! Programs that can be executed by the SP1 zkVM.
 
-use std::{fs::File, io::Read};
+use std::{
+    fs::File,
+    io::Read,
+    sync::{Arc, OnceLock},
+};
 
 use hashbrown::HashMap;
 use p3_field::Field;
@@ -8,15 +12,19 @@ use serde::{Deserialize, Serialize};
 use sp1_stark::air::{MachineAir, MachineProgram};
 
 use crate::{
-    disassembler::{transpile, Elf},
+    disassembler::{transpile, Elf, ElfError},
+    fast::BasicBlocks,
     instruction::Instruction,
-    CoreShape,
+    CoreShape, DebugInfo,
 };
 
 /// A program that can be executed by the SP1 zkVM.
 ///
 /// Contains a series of instructions along with the initial memory image. It also contains the
 /// start address and base address of the program.
+///
+/// The instructions are laid out back to back from the base address, so that an instruction is 2
+/// bytes after a compressed instruction and 4 bytes after any other.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Program {
     /// The instructions of the program.
@@ -29,6 +37,26 @@ pub struct Program {
     pub memory_image: HashMap<u32, u32>,
     /// The shape for the preprocessed tables.
     pub preprocessed_shape: Option<CoreShape>,
+    /// The symbols, sections and line table of the ELF, if they were kept when loading it.
+    #[serde(skip)]
+    pub debug_info: Option<Arc<DebugInfo>>,
+    /// The layout of the instructions, computed on first use.
+    #[serde(skip)]
+    layout: OnceLock<CodeLayout>,
+    /// The instructions decoded into basic blocks for the fast interpreter, on first use.
+    #[serde(skip)]
+    basic_blocks: OnceLock<BasicBlocks>,
+}
+
+/// Where the instructions of a [`Program`] are, relative to its base address.
+#[derive(Debug, Clone)]
+struct CodeLayout {
+    /// The size of the code in bytes.
+    size: u32,
+    /// The index of the instruction starting at each halfword, or `u32::MAX` for halfwords in
+    /// the middle of an instruction. `None` if no instruction is compressed, in which case the
+    /// instruction at offset `4 * i` is the `i`-th one.
+    halfword_index: Option<Vec<u32>>,
 }
 
 impl Program {
@@ -41,6 +69,9 @@ impl Program {
             pc_base,
             memory_image: HashMap::new(),
             preprocessed_shape: None,
+            debug_info: None,
+            layout: OnceLock::new(),
+            basic_blocks: OnceLock::new(),
         }
     }
 
@@ -49,12 +80,17 @@ impl Program {
     /// # Errors
     ///
     /// This function may return an error if the ELF is not valid.
-    pub fn from(input: &[u8]) -> eyre::Result<Self> {
+    pub fn from(input: &[u8]) -> Result<Self, ElfError> {
         // Decode the bytes as an ELF.
         let elf = Elf::decode(input)?;
 
-        // Transpile the RV32IM instructions.
-        let instructions = transpile(&elf.instructions);
+        // Transpile the RV32IM instructions, and mark the ones expanded from RVC instructions.
+        let mut instructions = transpile(&elf.instructions);
+        for (instruction, &compressed) in instructions.iter_mut().zip(&elf.compressed) {
+            if compressed {
+                *instruction = instruction.into_compressed();
+            }
+        }
 
         // Return the program.
         Ok(Program {
@@ -63,6 +99,9 @@ impl Program {
             pc_base: elf.pc_base,
             memory_image: elf.memory_image,
             preprocessed_shape: None,
+            debug_info: None,
+            layout: OnceLock::new(),
+            basic_blocks: OnceLock::new(),
         })
     }
 
@@ -74,7 +113,94 @@ impl Program {
     pub fn from_elf(path: &str) -> eyre::Result<Self> {
         let mut elf_code = Vec::new();
         File::open(path)?.read_to_end(&mut elf_code)?;
-        Program::from(&elf_code)
+        Ok(Program::from(&elf_code)?)
+    }
+
+    /// Disassemble a RV32IM ELF like [`Program::from`], and keep its symbols, sections and line
+    /// table to map addresses back to functions and source code.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if the ELF or its debug info is not valid.
+    pub fn from_with_debug_info(input: &[u8]) -> Result<Self, ElfError> {
+        let mut program = Program::from(input)?;
+        program.debug_info = Some(Arc::new(DebugInfo::decode(input)?));
+        Ok(program)
+    }
+
+    /// Disassemble a RV32IM ELF from a file path like [`Program::from_with_debug_info`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be opened or read.
+    pub fn from_elf_with_debug_info(path: &str) -> eyre::Result<Self> {
+        let mut elf_code = Vec::new();
+        File::open(path)?.read_to_end(&mut elf_code)?;
+        Ok(Program::from_with_debug_info(&elf_code)?)
+    }
+
+    /// Describe the code at `pc` as its function and source location, if the program kept its
+    /// debug info and they are known.
+    #[must_use]
+    pub fn describe_pc(&self, pc: u32) -> Option<String> {
+        self.debug_info.as_ref()?.describe(pc)
+    }
+
+    /// The address of each instruction, in order.
+    pub fn pcs(&self) -> impl Iterator<Item = u32> + '_ {
+        self.instructions.iter().scan(self.pc_base, |pc, instruction| {
+            let current = *pc;
+            *pc = pc.wrapping_add(instruction.size());
+            Some(current)
+        })
+    }
+
+    /// The size of the code of the program in bytes.
+    #[must_use]
+    pub fn code_size(&self) -> u32 {
+        self.layout().size
+    }
+
+    /// The index of the instruction starting at `pc`, if there is one.
+    #[must_use]
+    pub fn instruction_index(&self, pc: u32) -> Option<usize> {
+        let offset = pc.wrapping_sub(self.pc_base);
+        match &self.layout().halfword_index {
+            None => (offset % 4 == 0 && offset < self.code_size()).then_some(offset as usize / 4),
+            Some(halfword_index) => {
+                let index = *halfword_index.get(offset as usize / 2)?;
+                (offset % 2 == 0 && index != u32::MAX).then_some(index as usize)
+            }
+        }
+    }
+
+    /// The instruction starting at `pc`, if there is one.
+    #[must_use]
+    pub fn fetch(&self, pc: u32) -> Option<&Instruction> {
+        self.instruction_index(pc).map(|index| &self.instructions[index])
+    }
+
+    /// The layout of the instructions, computed once since the executor looks up every
+    /// instruction it executes.
+    fn layout(&self) -> &CodeLayout {
+        self.layout.get_or_init(|| {
+            let size = self.instructions.iter().map(Instruction::size).sum();
+            let halfword_index =
+                self.instructions.iter().any(|instruction| instruction.compressed).then(|| {
+                    let mut halfword_index = vec![u32::MAX; size as usize / 2];
+                    for (i, pc) in self.pcs().enumerate() {
+                        halfword_index[(pc - self.pc_base) as usize / 2] = i as u32;
+                    }
+                    halfword_index
+                });
+            CodeLayout { size, halfword_index }
+        })
+    }
+
+    /// The instructions decoded into basic blocks, computed once and reused by every run of the
+    /// program with [`crate::Executor::run_fast`].
+    pub(crate) fn basic_blocks(&self) -> &BasicBlocks {
+        self.basic_blocks.get_or_init(|| BasicBlocks::decode(&self.instructions))
     }
 
     /// Custom logic for padding the trace to a power of two according to the proof shape.
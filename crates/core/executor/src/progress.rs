@@ -0,0 +1,312 @@
+use std::{
+    fmt::{Debug, Display},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A token used to cooperatively cancel a proving request.
+///
+/// Cloning the token is cheap and all clones observe the same cancellation flag, so a caller can
+/// keep one clone and hand the other to the prover.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a new [`CancellationToken`] that has not been cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation. Workers observing this token stop at their next check.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether cancellation has been requested.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// An event emitted by the prover while a proof is being generated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProverEvent {
+    /// The executor produced a checkpoint.
+    CheckpointProduced {
+        /// The index of the checkpoint.
+        index: usize,
+        /// Whether this is the final checkpoint of the execution.
+        done: bool,
+        /// The number of cycles executed so far.
+        cycles: u64,
+    },
+    /// A core shard was proven.
+    ShardProven {
+        /// The shard number, as committed to in the shard's public values.
+        shard: u32,
+        /// The time spent committing and opening the shard.
+        elapsed: Duration,
+    },
+    /// All core shards were proven.
+    CoreProofFinished {
+        /// The number of shard proofs.
+        num_shards: usize,
+        /// The number of cycles executed.
+        cycles: u64,
+    },
+    /// A layer of the recursion tree was proven.
+    CompressLayerFinished {
+        /// The height of the layer in the recursion tree, starting at zero for the first layer.
+        layer: usize,
+        /// The number of proofs in the layer.
+        num_proofs: usize,
+    },
+    /// The shrink proof is being generated.
+    ShrinkStarted,
+    /// The wrap proof over the SNARK-friendly field is being generated.
+    WrapStarted,
+}
+
+/// A callback invoked with every [`ProverEvent`].
+pub type ProgressListener<'a> = Arc<dyn Fn(ProverEvent) + Send + Sync + 'a>;
+
+/// Statistics about the work a proving request did before it was interrupted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvingStats {
+    /// The number of cycles executed.
+    pub cycles: u64,
+    /// The number of checkpoints produced by the executor.
+    pub checkpoints: usize,
+    /// The number of core shards proven.
+    pub shards_proven: usize,
+    /// The number of recursion layers proven.
+    pub compress_layers: usize,
+    /// The time elapsed since proving started.
+    pub elapsed: Duration,
+}
+
+impl Display for ProvingStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cycles={}, checkpoints={}, shards_proven={}, compress_layers={}, elapsed={:?}",
+            self.cycles, self.checkpoints, self.shards_proven, self.compress_layers, self.elapsed
+        )
+    }
+}
+
+/// The reason a proving request stopped before completing.
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProvingInterrupted {
+    /// The request's [`CancellationToken`] was cancelled.
+    #[error("proving was cancelled ({0})")]
+    Cancelled(ProvingStats),
+
+    /// The request's deadline passed.
+    #[error("proving timed out ({0})")]
+    TimedOut(ProvingStats),
+}
+
+impl ProvingInterrupted {
+    /// The work done before the request was interrupted.
+    #[must_use]
+    pub fn stats(&self) -> &ProvingStats {
+        match self {
+            Self::Cancelled(stats) | Self::TimedOut(stats) => stats,
+        }
+    }
+}
+
+/// Reports progress to a listener and exposes the cancellation state of a proving request.
+///
+/// Clones of an observer share the statistics accumulated from the emitted events.
+#[derive(Clone)]
+pub struct ProverObserver<'a> {
+    /// The listener that receives progress events.
+    pub listener: Option<ProgressListener<'a>>,
+
+    /// The token used to cancel the request.
+    pub cancellation_token: Option<CancellationToken>,
+
+    /// The instant after which the request times out.
+    pub deadline: Option<Instant>,
+
+    start: Arc<OnceLock<Instant>>,
+    stats: Arc<Mutex<ProvingStats>>,
+}
+
+impl<'a> ProverObserver<'a> {
+    /// Create a new [`ProverObserver`].
+    #[must_use]
+    pub fn new(
+        listener: Option<ProgressListener<'a>>,
+        cancellation_token: Option<CancellationToken>,
+        deadline: Option<Instant>,
+    ) -> Self {
+        Self {
+            listener,
+            cancellation_token,
+            deadline,
+            start: Arc::new(OnceLock::new()),
+            stats: Arc::new(Mutex::new(ProvingStats::default())),
+        }
+    }
+
+    /// Start the clock measuring [`ProvingStats::elapsed`].
+    ///
+    /// Only the first call on any clone of the observer has an effect, so each proving stage can
+    /// call this without resetting the clock started by an earlier stage.
+    pub fn start(&self) {
+        self.start.get_or_init(Instant::now);
+    }
+
+    /// Forward an event to the listener, if there is one.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the statistics lock is poisoned.
+    pub fn emit(&self, event: ProverEvent) {
+        {
+            let mut stats = self.stats.lock().unwrap();
+            match event {
+                ProverEvent::CheckpointProduced { cycles, .. } => {
+                    stats.checkpoints += 1;
+                    stats.cycles = stats.cycles.max(cycles);
+                }
+                ProverEvent::ShardProven { .. } => stats.shards_proven += 1,
+                ProverEvent::CompressLayerFinished { .. } => stats.compress_layers += 1,
+                ProverEvent::CoreProofFinished { cycles, .. } => stats.cycles = cycles,
+                ProverEvent::ShrinkStarted | ProverEvent::WrapStarted => {}
+            }
+        }
+        if let Some(listener) = &self.listener {
+            listener(event);
+        }
+    }
+
+    /// Whether cancellation has been requested.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Whether the deadline has passed.
+    #[must_use]
+    pub fn is_timed_out(&self) -> bool {
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Whether the request was cancelled or timed out, meaning workers should stop.
+    #[must_use]
+    pub fn is_interrupted(&self) -> bool {
+        self.is_cancelled() || self.is_timed_out()
+    }
+
+    /// A snapshot of the work done so far.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the statistics lock is poisoned.
+    #[must_use]
+    pub fn stats(&self) -> ProvingStats {
+        let elapsed = self.start.get().map_or(Duration::ZERO, Instant::elapsed);
+        ProvingStats { elapsed, ..*self.stats.lock().unwrap() }
+    }
+
+    /// Return an error describing the work done so far if the request was interrupted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProvingInterrupted`] if the request was cancelled or its deadline passed.
+    pub fn check(&self) -> Result<(), ProvingInterrupted> {
+        if self.is_cancelled() {
+            Err(ProvingInterrupted::Cancelled(self.stats()))
+        } else if self.is_timed_out() {
+            Err(ProvingInterrupted::TimedOut(self.stats()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<'a> Default for ProverObserver<'a> {
+    fn default() -> Self {
+        Self::new(None, None, None)
+    }
+}
+
+impl<'a> Debug for ProverObserver<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProverObserver")
+            .field("listener", &self.listener.is_some())
+            .field("cancellation_token", &self.cancellation_token)
+            .field("deadline", &self.deadline)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn cancellation_is_shared_between_clones() {
+        let token = CancellationToken::new();
+        let observer = ProverObserver::new(None, Some(token.clone()), None);
+        assert!(!observer.is_cancelled());
+        token.cancel();
+        assert!(observer.is_cancelled());
+        assert!(matches!(observer.check(), Err(ProvingInterrupted::Cancelled(_))));
+    }
+
+    #[test]
+    fn deadline_times_out() {
+        let observer = ProverObserver::new(None, None, Some(Instant::now()));
+        observer.emit(ProverEvent::CheckpointProduced { index: 0, done: false, cycles: 42 });
+        let Err(ProvingInterrupted::TimedOut(stats)) = observer.clone().check() else {
+            panic!("expected the request to time out");
+        };
+        assert_eq!(stats.cycles, 42);
+        assert_eq!(stats.checkpoints, 1);
+    }
+
+    #[test]
+    fn clock_starts_when_proving_starts() {
+        let observer = ProverObserver::default();
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(observer.stats().elapsed, Duration::ZERO);
+
+        observer.clone().start();
+        let elapsed = observer.stats().elapsed;
+        observer.start();
+        assert!(observer.stats().elapsed >= elapsed);
+        assert!(elapsed < Duration::from_millis(10));
+    }
+
+    #[test]
+    fn events_are_forwarded() {
+        let events = Mutex::new(Vec::new());
+        let observer =
+            ProverObserver::new(Some(Arc::new(|e| events.lock().unwrap().push(e))), None, None);
+        observer.emit(ProverEvent::ShrinkStarted);
+        observer.emit(ProverEvent::WrapStarted);
+        drop(observer);
+        assert_eq!(
+            events.into_inner().unwrap(),
+            vec![ProverEvent::ShrinkStarted, ProverEvent::WrapStarted]
+        );
+    }
+}
@@ -45,6 +45,8 @@ pub struct ExecutionRecord {
     pub divrem_events: Vec<AluEvent>,
     /// A trace of the SLT, SLTI, SLTU, and SLTIU events.
     pub lt_events: Vec<AluEvent>,
+    /// A trace of the Zba/Zbb bit-manipulation events.
+    pub bitmanip_events: Vec<AluEvent>,
     /// A trace of the byte lookups that are needed.
     pub byte_lookups: HashMap<u32, HashMap<ByteLookupEvent, usize>>,
     /// A trace of the precompile events.
@@ -107,6 +109,9 @@ impl ExecutionRecord {
                 Opcode::SLT | Opcode::SLTU => {
                     self.lt_events.append(value);
                 }
+                opcode if opcode.is_bitmanip() => {
+                    self.bitmanip_events.append(value);
+                }
                 _ => {
                     panic!("Invalid opcode: {opcode:?}");
                 }
@@ -284,6 +289,7 @@ impl MachineRecord for ExecutionRecord {
         stats.insert("shift_right_events".to_string(), self.shift_right_events.len());
         stats.insert("divrem_events".to_string(), self.divrem_events.len());
         stats.insert("lt_events".to_string(), self.lt_events.len());
+        stats.insert("bitmanip_events".to_string(), self.bitmanip_events.len());
 
         for (syscall_code, events) in self.precompile_events.iter() {
             stats.insert(format!("syscall {syscall_code:?}"), events.len());
@@ -320,6 +326,7 @@ impl MachineRecord for ExecutionRecord {
         self.shift_right_events.append(&mut other.shift_right_events);
         self.divrem_events.append(&mut other.divrem_events);
         self.lt_events.append(&mut other.lt_events);
+        self.bitmanip_events.append(&mut other.bitmanip_events);
         self.syscall_events.append(&mut other.syscall_events);
 
         self.precompile_events.append(&mut other.precompile_events);
@@ -367,6 +374,10 @@ impl MachineRecord for ExecutionRecord {
         self.lt_events.iter().enumerate().for_each(|(i, event)| {
             self.nonce_lookup.insert(event.lookup_id, i as u32);
         });
+
+        self.bitmanip_events.iter().enumerate().for_each(|(i, event)| {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        });
     }
 
     /// Retrieves the public values.  This method is needed for the `MachineRecord` trait, since
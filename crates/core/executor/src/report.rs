@@ -19,6 +19,10 @@ pub struct ExecutionReport {
     pub cycle_tracker: HashMap<String, u64>,
     /// The unique memory address counts.
     pub touched_memory_addresses: u64,
+    /// What the program wrote to stdout, if the output was captured.
+    pub stdout: String,
+    /// What the program wrote to stderr, if the output was captured.
+    pub stderr: String,
 }
 
 impl ExecutionReport {
@@ -51,6 +55,8 @@ impl AddAssign for ExecutionReport {
         counts_add_assign(&mut self.opcode_counts, *rhs.opcode_counts);
         counts_add_assign(&mut self.syscall_counts, *rhs.syscall_counts);
         self.touched_memory_addresses += rhs.touched_memory_addresses;
+        self.stdout.push_str(&rhs.stdout);
+        self.stderr.push_str(&rhs.stderr);
     }
 }
 
@@ -1,4 +1,5 @@
 use std::{
+    collections::{BTreeMap, VecDeque},
     fs::File,
     io::{Seek, Write},
 };
@@ -46,6 +47,10 @@ pub struct ExecutionState {
     /// A ptr to the current position in the input stream incremented by `HINT_READ` opcode.
     pub input_stream_ptr: usize,
 
+    /// The items pulled from the [`crate::InputProvider`] while executing from this state, in
+    /// order, so that a checkpoint can be replayed without the provider.
+    pub provided_inputs: VecDeque<Vec<u8>>,
+
     /// A stream of proofs (reduce vk, proof, verifying key) inputted to the program.
     pub proof_stream:
         Vec<(SP1ReduceProof<BabyBearPoseidon2>, StarkVerifyingKey<BabyBearPoseidon2>)>,
@@ -60,8 +65,14 @@ pub struct ExecutionState {
     /// `public_values_stream`.
     pub public_values_stream_ptr: usize,
 
+    /// The named channels of public values from the program, by name.
+    pub public_value_channels: BTreeMap<String, Vec<u8>>,
+
     /// Keeps track of how many times a certain syscall has been called.
     pub syscall_counts: HashMap<SyscallCode, u64>,
+
+    /// The address of the table of the read-only input regions, or zero if there are none.
+    pub input_regions_start: u32,
 }
 
 impl ExecutionState {
@@ -78,13 +89,56 @@ impl ExecutionState {
             uninitialized_memory: PagedMemory::default(),
             input_stream: Vec::new(),
             input_stream_ptr: 0,
+            provided_inputs: VecDeque::new(),
             public_values_stream: Vec::new(),
             public_values_stream_ptr: 0,
+            public_value_channels: BTreeMap::new(),
             proof_stream: Vec::new(),
             proof_stream_ptr: 0,
             syscall_counts: HashMap::new(),
+            input_regions_start: 0,
         }
     }
+
+    /// Reset the state to the start of a program at `pc_start`, keeping the memory allocated for
+    /// it.
+    pub fn reset(&mut self, pc_start: u32) {
+        // Exhaustive match is done to ensure we reset new fields.
+        let Self {
+            global_clk,
+            current_shard,
+            clk,
+            pc,
+            memory,
+            uninitialized_memory,
+            input_stream,
+            input_stream_ptr,
+            provided_inputs,
+            proof_stream,
+            proof_stream_ptr,
+            public_values_stream,
+            public_values_stream_ptr,
+            public_value_channels,
+            syscall_counts,
+            input_regions_start,
+        } = self;
+        *global_clk = 0;
+        *current_shard = 1;
+        *clk = 0;
+        *pc = pc_start;
+        memory.clear();
+        uninitialized_memory.clear();
+        input_stream.clear();
+        *input_stream_ptr = 0;
+        provided_inputs.clear();
+        proof_stream.clear();
+        *proof_stream_ptr = 0;
+        public_values_stream.clear();
+        *public_values_stream_ptr = 0;
+        public_value_channels.clear();
+        syscall_counts.clear();
+        *input_regions_start = 0;
+    }
 }
 
 /// Holds data to track changes made to the runtime since a fork point.
@@ -105,6 +159,8 @@ pub struct ForkState {
     pub record: ExecutionRecord,
     /// Whether `emit_events` was enabled at the fork point.
     pub executor_mode: ExecutorMode,
+    /// The depth of the call stack at the fork point.
+    pub call_depth: usize,
 }
 
 impl ExecutionState {
@@ -137,6 +137,15 @@ pub enum SyscallCode {
     BN254_SCALAR_MUL = 0x00_01_01_80,
     /// Execute the `BN254_SCALAR_MAC` precompile.
     BN254_SCALAR_MAC = 0x00_01_01_81,
+
+    /// Reserved for a syscall registered on the host, which can be executed but not proven.
+    HOST_0 = 0x00_00_00_E0,
+    /// Reserved for a syscall registered on the host, which can be executed but not proven.
+    HOST_1 = 0x00_00_00_E1,
+    /// Reserved for a syscall registered on the host, which can be executed but not proven.
+    HOST_2 = 0x00_00_00_E2,
+    /// Reserved for a syscall registered on the host, which can be executed but not proven.
+    HOST_3 = 0x00_00_00_E3,
 }
 
 impl SyscallCode {
@@ -183,6 +192,10 @@ impl SyscallCode {
             0x00_01_01_91 => SyscallCode::MEMCPY_64,
             0x00_01_01_80 => SyscallCode::BN254_SCALAR_MUL,
             0x00_01_01_81 => SyscallCode::BN254_SCALAR_MAC,
+            0x00_00_00_E0 => SyscallCode::HOST_0,
+            0x00_00_00_E1 => SyscallCode::HOST_1,
+            0x00_00_00_E2 => SyscallCode::HOST_2,
+            0x00_00_00_E3 => SyscallCode::HOST_3,
             _ => panic!("invalid syscall number: {value}"),
         }
     }
@@ -205,6 +218,16 @@ impl SyscallCode {
         (self as u32).to_le_bytes()[2].into()
     }
 
+    /// Get whether the code is reserved for a syscall registered on the host, from
+    /// [`SyscallCode::HOST_0`] to [`SyscallCode::HOST_3`].
+    #[must_use]
+    pub fn is_host(self) -> bool {
+        matches!(
+            self,
+            SyscallCode::HOST_0 | SyscallCode::HOST_1 | SyscallCode::HOST_2 | SyscallCode::HOST_3
+        )
+    }
+
     /// Map a syscall to another one in order to coalesce their counts.
     #[must_use]
     #[allow(clippy::match_same_arms)]
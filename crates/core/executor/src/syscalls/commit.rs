@@ -1,3 +1,5 @@
+use crate::ExecutionError;
+
 use super::{Syscall, SyscallCode, SyscallContext};
 
 pub(crate) struct CommitSyscall;
@@ -7,15 +9,24 @@ impl Syscall for CommitSyscall {
     fn execute(
         &self,
         ctx: &mut SyscallContext,
-        _: SyscallCode,
+        syscall_code: SyscallCode,
         word_idx: u32,
         public_values_digest_word: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
+        let pc = ctx.pc();
         let rt = &mut ctx.rt;
 
-        rt.record.public_values.committed_value_digest[word_idx as usize] =
-            public_values_digest_word;
+        let Some(digest_word) =
+            rt.record.public_values.committed_value_digest.get_mut(word_idx as usize)
+        else {
+            return Err(ExecutionError::InvalidSyscallArgument {
+                pc,
+                syscall: syscall_code,
+                arg: word_idx,
+            });
+        };
+        *digest_word = public_values_digest_word;
 
-        None
+        Ok(None)
     }
 }
@@ -47,6 +47,12 @@ impl<'a, 'b> SyscallContext<'a, 'b> {
         &mut self.rt.record
     }
 
+    /// Get the address of the `ecall` instruction that made the syscall.
+    #[must_use]
+    pub fn pc(&self) -> u32 {
+        self.rt.state.pc
+    }
+
     /// Get the current shard.
     #[must_use]
     pub fn current_shard(&self) -> u32 {
@@ -1,3 +1,5 @@
+use crate::ExecutionError;
+
 use super::{Syscall, SyscallCode, SyscallContext};
 
 pub(crate) struct CommitDeferredSyscall;
@@ -7,14 +9,24 @@ impl Syscall for CommitDeferredSyscall {
     fn execute(
         &self,
         ctx: &mut SyscallContext,
-        _: SyscallCode,
+        syscall_code: SyscallCode,
         word_idx: u32,
         word: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
+        let pc = ctx.pc();
         let rt = &mut ctx.rt;
 
-        rt.record.public_values.deferred_proofs_digest[word_idx as usize] = word;
+        let Some(digest_word) =
+            rt.record.public_values.deferred_proofs_digest.get_mut(word_idx as usize)
+        else {
+            return Err(ExecutionError::InvalidSyscallArgument {
+                pc,
+                syscall: syscall_code,
+                arg: word_idx,
+            });
+        };
+        *digest_word = word;
 
-        None
+        Ok(None)
     }
 }
@@ -1,3 +1,5 @@
+use crate::ExecutionError;
+
 use super::{context::SyscallContext, Syscall, SyscallCode};
 
 pub(crate) struct HaltSyscall;
@@ -9,9 +11,9 @@ impl Syscall for HaltSyscall {
         _: SyscallCode,
         exit_code: u32,
         _: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         ctx.set_next_pc(0);
         ctx.set_exit_code(exit_code);
-        None
+        Ok(None)
     }
 }
@@ -1,3 +1,5 @@
+use crate::ExecutionError;
+
 use super::{Syscall, SyscallCode, SyscallContext};
 
 pub(crate) struct HintLenSyscall;
@@ -6,37 +8,61 @@ impl Syscall for HintLenSyscall {
     fn execute(
         &self,
         ctx: &mut SyscallContext,
-        _: SyscallCode,
+        syscall_code: SyscallCode,
         _arg1: u32,
         _arg2: u32,
-    ) -> Option<u32> {
-        if ctx.rt.state.input_stream_ptr >= ctx.rt.state.input_stream.len() {
-            panic!(
-                "failed reading stdin due to insufficient input data: input_stream_ptr={}, input_stream_len={}",
-                ctx.rt.state.input_stream_ptr,
-                ctx.rt.state.input_stream.len()
-            );
-        }
-        Some(ctx.rt.state.input_stream[ctx.rt.state.input_stream_ptr].len() as u32)
+    ) -> Result<Option<u32>, ExecutionError> {
+        ctx.rt.pull_input();
+        let ptr = ctx.rt.state.input_stream_ptr;
+        let Some(input) = ctx.rt.state.input_stream.get(ptr) else {
+            return Err(ExecutionError::InputExhausted {
+                pc: ctx.pc(),
+                syscall: syscall_code,
+                read: ptr,
+            });
+        };
+        Ok(Some(input.len() as u32))
     }
 }
 
 pub(crate) struct HintReadSyscall;
 
 impl Syscall for HintReadSyscall {
-    fn execute(&self, ctx: &mut SyscallContext, _: SyscallCode, ptr: u32, len: u32) -> Option<u32> {
-        if ctx.rt.state.input_stream_ptr >= ctx.rt.state.input_stream.len() {
-            panic!(
-                "failed reading stdin due to insufficient input data: input_stream_ptr={}, input_stream_len={}",
-                ctx.rt.state.input_stream_ptr,
-                ctx.rt.state.input_stream.len()
-            );
+    fn execute(
+        &self,
+        ctx: &mut SyscallContext,
+        syscall_code: SyscallCode,
+        ptr: u32,
+        len: u32,
+    ) -> Result<Option<u32>, ExecutionError> {
+        let pc = ctx.pc();
+        if ctx.rt.unconstrained {
+            return Err(ExecutionError::UnconstrainedSyscall { pc, syscall: syscall_code });
+        }
+        ctx.rt.pull_input();
+        let input_ptr = ctx.rt.state.input_stream_ptr;
+        let Some(vec) = ctx.rt.state.input_stream.get(input_ptr) else {
+            return Err(ExecutionError::InputExhausted {
+                pc,
+                syscall: syscall_code,
+                read: input_ptr,
+            });
+        };
+        if vec.len() as u32 != len {
+            return Err(ExecutionError::InputLengthMismatch {
+                pc,
+                syscall: syscall_code,
+                requested: len,
+                actual: vec.len(),
+            });
         }
-        let vec = &ctx.rt.state.input_stream[ctx.rt.state.input_stream_ptr];
+        if ptr % 4 != 0 {
+            return Err(ExecutionError::UnalignedSyscallPointer { pc, syscall: syscall_code, ptr });
+        }
+        // The item is never read again, so take it out of the stream to free its memory.
+        let vec = std::mem::take(&mut ctx.rt.state.input_stream[input_ptr]);
         ctx.rt.state.input_stream_ptr += 1;
-        assert!(!ctx.rt.unconstrained, "hint read should not be used in a unconstrained block");
-        assert_eq!(vec.len() as u32, len, "hint input stream read length mismatch");
-        assert_eq!(ptr % 4, 0, "hint read address not aligned to 4 bytes");
+
         // Iterate through the vec in 4-byte chunks
         for i in (0..len).step_by(4) {
             // Get each byte in the chunk
@@ -50,14 +76,17 @@ impl Syscall for HintReadSyscall {
 
             // Save the data into runtime state so the runtime will use the desired data instead of
             // 0 when first reading/writing from this address.
-            ctx.rt.uninitialized_memory_checkpoint.entry(ptr + i).or_insert_with(|| false);
-            ctx.rt
-                .state
-                .uninitialized_memory
-                .entry(ptr + i)
-                .and_modify(|_| panic!("hint read address is initialized already"))
-                .or_insert(word);
+            let addr = ptr + i;
+            if ctx.rt.state.uninitialized_memory.get(addr).is_some() {
+                return Err(ExecutionError::InputOverwritesMemory {
+                    pc,
+                    syscall: syscall_code,
+                    addr,
+                });
+            }
+            ctx.rt.uninitialized_memory_checkpoint.entry(addr).or_insert_with(|| false);
+            ctx.rt.state.uninitialized_memory.insert(addr, word);
         }
-        None
+        Ok(None)
     }
 }
@@ -47,7 +47,7 @@ use unconstrained::{EnterUnconstrainedSyscall, ExitUnconstrainedSyscall};
 use verify::VerifySyscall;
 use write::WriteSyscall;
 
-use crate::events::FieldOperation;
+use crate::{events::FieldOperation, ExecutionError};
 
 /// A system call in the SP1 RISC-V zkVM.
 ///
@@ -59,13 +59,16 @@ pub trait Syscall: Send + Sync {
     /// X10 and X11, respectively. While not a hard requirement, the convention is that the return
     /// value is only for system calls such as `HALT`. Most precompiles use `arg1` and `arg2` to
     /// denote the addresses of the input data, and write the result to the memory at `arg1`.
+    ///
+    /// Returns an error if the guest misuses the syscall, for example with a misaligned pointer
+    /// or by reading more input than was written, which ends the execution.
     fn execute(
         &self,
         ctx: &mut SyscallContext,
         syscall_code: SyscallCode,
         arg1: u32,
         arg2: u32,
-    ) -> Option<u32>;
+    ) -> Result<Option<u32>, ExecutionError>;
 
     /// The number of extra cycles that the syscall takes to execute.
     ///
@@ -1,6 +1,7 @@
 use crate::{
     events::{create_bn254_scalar_arith_event, Bn254FieldOperation, PrecompileEvent},
     syscalls::{Syscall, SyscallCode, SyscallContext},
+    ExecutionError,
 };
 
 pub(crate) struct Bn254ScalarMacSyscall;
@@ -12,7 +13,7 @@ impl Syscall for Bn254ScalarMacSyscall {
         syscall_code: SyscallCode,
         arg1: u32,
         arg2: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let start_clk = rt.clk;
         let event = create_bn254_scalar_arith_event(rt, arg1, arg2, Bn254FieldOperation::Mac);
         let syscall_event =
@@ -24,7 +25,7 @@ impl Syscall for Bn254ScalarMacSyscall {
             PrecompileEvent::Bn254ScalarMac(event),
         );
 
-        None
+        Ok(None)
     }
 
     fn num_extra_cycles(&self) -> u32 {
@@ -40,7 +41,7 @@ impl Syscall for Bn254ScalarMulSyscall {
         syscall_code: SyscallCode,
         arg1: u32,
         arg2: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let start_clk = rt.clk;
         let event = create_bn254_scalar_arith_event(rt, arg1, arg2, Bn254FieldOperation::Mul);
         let syscall_event =
@@ -51,7 +52,7 @@ impl Syscall for Bn254ScalarMulSyscall {
             PrecompileEvent::Bn254ScalarMul(event),
         );
 
-        None
+        Ok(None)
     }
 
     fn num_extra_cycles(&self) -> u32 {
@@ -5,6 +5,7 @@ use sp1_curves::{edwards::EdwardsParameters, EllipticCurve};
 use crate::{
     events::{create_ec_add_event, PrecompileEvent},
     syscalls::{Syscall, SyscallCode, SyscallContext},
+    ExecutionError,
 };
 
 pub(crate) struct EdwardsAddAssignSyscall<E: EllipticCurve + EdwardsParameters> {
@@ -29,7 +30,7 @@ impl<E: EllipticCurve + EdwardsParameters> Syscall for EdwardsAddAssignSyscall<E
         syscall_code: SyscallCode,
         arg1: u32,
         arg2: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let event = create_ec_add_event::<E>(rt, arg1, arg2);
         let syscall_event =
             rt.rt.syscall_event(event.clk, syscall_code.syscall_id(), arg1, arg2, event.lookup_id);
@@ -38,6 +39,6 @@ impl<E: EllipticCurve + EdwardsParameters> Syscall for EdwardsAddAssignSyscall<E
             syscall_event,
             PrecompileEvent::EdAdd(event),
         );
-        None
+        Ok(None)
     }
 }
@@ -10,6 +10,7 @@ use sp1_primitives::consts::{bytes_to_words_le, words_to_bytes_le};
 use crate::{
     events::{EdDecompressEvent, MemoryReadRecord, MemoryWriteRecord, PrecompileEvent},
     syscalls::{Syscall, SyscallCode, SyscallContext},
+    ExecutionError,
 };
 
 pub(crate) struct EdwardsDecompressSyscall<E: EdwardsParameters> {
@@ -30,11 +31,23 @@ impl<E: EdwardsParameters> Syscall for EdwardsDecompressSyscall<E> {
         syscall_code: SyscallCode,
         arg1: u32,
         sign: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let start_clk = rt.clk;
         let slice_ptr = arg1;
-        assert!(slice_ptr % 4 == 0, "Pointer must be 4-byte aligned.");
-        assert!(sign <= 1, "Sign bit must be 0 or 1.");
+        if slice_ptr % 4 != 0 {
+            return Err(ExecutionError::UnalignedSyscallPointer {
+                pc: rt.pc(),
+                syscall: syscall_code,
+                ptr: slice_ptr,
+            });
+        }
+        if sign > 1 {
+            return Err(ExecutionError::InvalidSyscallArgument {
+                pc: rt.pc(),
+                syscall: syscall_code,
+                arg: sign,
+            });
+        }
 
         let (y_memory_records_vec, y_vec) =
             rt.mr_slice(slice_ptr + (COMPRESSED_POINT_BYTES as u32), WORDS_FIELD_ELEMENT);
@@ -85,7 +98,7 @@ impl<E: EdwardsParameters> Syscall for EdwardsDecompressSyscall<E> {
             syscall_event,
             PrecompileEvent::EdDecompress(event),
         );
-        None
+        Ok(None)
     }
 
     fn num_extra_cycles(&self) -> u32 {
@@ -10,6 +10,7 @@ use typenum::Unsigned;
 use crate::{
     events::{Fp2MulEvent, PrecompileEvent},
     syscalls::{Syscall, SyscallCode, SyscallContext},
+    ExecutionError,
 };
 
 pub struct Fp2MulSyscall<P> {
@@ -29,15 +30,18 @@ impl<P: FpOpField> Syscall for Fp2MulSyscall<P> {
         syscall_code: SyscallCode,
         arg1: u32,
         arg2: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let clk = rt.clk;
         let x_ptr = arg1;
-        if x_ptr % 4 != 0 {
-            panic!();
-        }
         let y_ptr = arg2;
-        if y_ptr % 4 != 0 {
-            panic!();
+        for ptr in [x_ptr, y_ptr] {
+            if ptr % 4 != 0 {
+                return Err(ExecutionError::UnalignedSyscallPointer {
+                    pc: rt.pc(),
+                    syscall: syscall_code,
+                    ptr,
+                });
+            }
         }
 
         let num_words = <P as NumWords>::WordsCurvePoint::USIZE;
@@ -96,7 +100,7 @@ impl<P: FpOpField> Syscall for Fp2MulSyscall<P> {
                 PrecompileEvent::Bls12381Fp2Mul(event),
             ),
         };
-        None
+        Ok(None)
     }
 
     fn num_extra_cycles(&self) -> u32 {
@@ -1,6 +1,7 @@
 use crate::{
     events::{KeccakPermuteEvent, PrecompileEvent},
     syscalls::{Syscall, SyscallCode, SyscallContext},
+    ExecutionError,
 };
 
 use tiny_keccak::keccakf;
@@ -23,11 +24,15 @@ impl Syscall for Keccak256PermuteSyscall {
         syscall_code: SyscallCode,
         arg1: u32,
         arg2: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let start_clk = rt.clk;
         let state_ptr = arg1;
         if arg2 != 0 {
-            panic!("Expected arg2 to be 0, got {arg2}");
+            return Err(ExecutionError::InvalidSyscallArgument {
+                pc: rt.pc(),
+                syscall: syscall_code,
+                arg: arg2,
+            });
         }
 
         let mut state_read_records = Vec::new();
@@ -80,6 +85,6 @@ impl Syscall for Keccak256PermuteSyscall {
             rt.rt.syscall_event(start_clk, syscall_code.syscall_id(), arg1, arg2, lookup_id);
         rt.record_mut().add_precompile_event(syscall_code, syscall_event, event);
 
-        None
+        Ok(None)
     }
 }
@@ -5,6 +5,7 @@ use generic_array::ArrayLength;
 use crate::{
     events::{MemCopyEvent, PrecompileEvent},
     syscalls::{Syscall, SyscallCode, SyscallContext},
+    ExecutionError,
 };
 
 pub struct MemCopySyscall<NumWords: ArrayLength, NumBytes: ArrayLength> {
@@ -26,7 +27,7 @@ impl<NumWords: ArrayLength + Send + Sync, NumBytes: ArrayLength + Send + Sync> S
         syscall_code: SyscallCode,
         src: u32,
         dst: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let start_clk = rt.clk;
         let (read, read_bytes) = rt.mr_slice(src, NumWords::USIZE);
 
@@ -60,7 +61,7 @@ impl<NumWords: ArrayLength + Send + Sync, NumBytes: ArrayLength + Send + Sync> S
 
         rt.record_mut().add_precompile_event(syscall_code, syscall_event, precompile_event);
 
-        None
+        Ok(None)
     }
 
     fn num_extra_cycles(&self) -> u32 {
@@ -1,6 +1,7 @@
 use crate::{
     events::{PrecompileEvent, ShaExtendEvent},
     syscalls::{Syscall, SyscallCode, SyscallContext},
+    ExecutionError,
 };
 
 pub(crate) struct Sha256ExtendSyscall;
@@ -16,10 +17,16 @@ impl Syscall for Sha256ExtendSyscall {
         syscall_code: SyscallCode,
         arg1: u32,
         arg2: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let clk_init = rt.clk;
         let w_ptr = arg1;
-        assert!(arg2 == 0, "arg2 must be 0");
+        if arg2 != 0 {
+            return Err(ExecutionError::InvalidSyscallArgument {
+                pc: rt.pc(),
+                syscall: syscall_code,
+                arg: arg2,
+            });
+        }
 
         let w_ptr_init = w_ptr;
         let mut w_i_minus_15_reads = Vec::new();
@@ -79,6 +86,6 @@ impl Syscall for Sha256ExtendSyscall {
             rt.rt.syscall_event(clk_init, syscall_code.syscall_id(), arg1, arg2, lookup_id);
         rt.record_mut().add_precompile_event(syscall_code, syscall_event, event);
 
-        None
+        Ok(None)
     }
 }
@@ -6,6 +6,7 @@ use sp1_primitives::consts::{bytes_to_words_le, words_to_bytes_le_vec, WORD_SIZE
 use crate::{
     events::{PrecompileEvent, Uint256MulEvent},
     syscalls::{Syscall, SyscallCode, SyscallContext},
+    ExecutionError,
 };
 
 pub(crate) struct Uint256MulSyscall;
@@ -17,16 +18,19 @@ impl Syscall for Uint256MulSyscall {
         syscall_code: SyscallCode,
         arg1: u32,
         arg2: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let clk = rt.clk;
 
         let x_ptr = arg1;
-        if x_ptr % 4 != 0 {
-            panic!();
-        }
         let y_ptr = arg2;
-        if y_ptr % 4 != 0 {
-            panic!();
+        for ptr in [x_ptr, y_ptr] {
+            if ptr % 4 != 0 {
+                return Err(ExecutionError::UnalignedSyscallPointer {
+                    pc: rt.pc(),
+                    syscall: syscall_code,
+                    ptr,
+                });
+            }
         }
 
         // First read the words for the x value. We can read a slice_unsafe here because we write
@@ -84,7 +88,7 @@ impl Syscall for Uint256MulSyscall {
             rt.rt.syscall_event(clk, syscall_code.syscall_id(), arg1, arg2, lookup_id);
         rt.record_mut().add_precompile_event(syscall_code, sycall_event, event);
 
-        None
+        Ok(None)
     }
 
     fn num_extra_cycles(&self) -> u32 {
@@ -5,6 +5,7 @@ use sp1_curves::{CurveType, EllipticCurve};
 use crate::{
     events::{create_ec_add_event, PrecompileEvent},
     syscalls::{Syscall, SyscallCode, SyscallContext},
+    ExecutionError,
 };
 
 pub(crate) struct WeierstrassAddAssignSyscall<E: EllipticCurve> {
@@ -25,7 +26,7 @@ impl<E: EllipticCurve> Syscall for WeierstrassAddAssignSyscall<E> {
         syscall_code: SyscallCode,
         arg1: u32,
         arg2: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let event = create_ec_add_event::<E>(rt, arg1, arg2);
         let syscall_event =
             rt.rt.syscall_event(event.clk, syscall_code.syscall_id(), arg1, arg2, event.lookup_id);
@@ -49,7 +50,7 @@ impl<E: EllipticCurve> Syscall for WeierstrassAddAssignSyscall<E> {
             ),
             _ => panic!("Unsupported curve"),
         }
-        None
+        Ok(None)
     }
 
     fn num_extra_cycles(&self) -> u32 {
@@ -1,15 +1,24 @@
 use hashbrown::HashMap;
 
-use crate::{state::ForkState, ExecutorMode};
+use crate::{state::ForkState, ExecutionError, ExecutorMode};
 
 use super::{Syscall, SyscallCode, SyscallContext};
 
 pub(crate) struct EnterUnconstrainedSyscall;
 
 impl Syscall for EnterUnconstrainedSyscall {
-    fn execute(&self, ctx: &mut SyscallContext, _: SyscallCode, _: u32, _: u32) -> Option<u32> {
+    fn execute(
+        &self,
+        ctx: &mut SyscallContext,
+        syscall_code: SyscallCode,
+        _: u32,
+        _: u32,
+    ) -> Result<Option<u32>, ExecutionError> {
         if ctx.rt.unconstrained {
-            panic!("Unconstrained block is already active.");
+            return Err(ExecutionError::UnconstrainedSyscall {
+                pc: ctx.pc(),
+                syscall: syscall_code,
+            });
         }
         ctx.rt.unconstrained = true;
         ctx.rt.unconstrained_state = ForkState {
@@ -20,16 +29,23 @@ impl Syscall for EnterUnconstrainedSyscall {
             record: std::mem::take(&mut ctx.rt.record),
             op_record: std::mem::take(&mut ctx.rt.memory_accesses),
             executor_mode: ctx.rt.executor_mode,
+            call_depth: ctx.rt.call_stack.len(),
         };
         ctx.rt.executor_mode = ExecutorMode::Simple;
-        Some(1)
+        Ok(Some(1))
     }
 }
 
 pub(crate) struct ExitUnconstrainedSyscall;
 
 impl Syscall for ExitUnconstrainedSyscall {
-    fn execute(&self, ctx: &mut SyscallContext, _: SyscallCode, _: u32, _: u32) -> Option<u32> {
+    fn execute(
+        &self,
+        ctx: &mut SyscallContext,
+        _: SyscallCode,
+        _: u32,
+        _: u32,
+    ) -> Result<Option<u32>, ExecutionError> {
         // Reset the state of the runtime.
         if ctx.rt.unconstrained {
             ctx.rt.state.global_clk = ctx.rt.unconstrained_state.global_clk;
@@ -49,9 +65,10 @@ impl Syscall for ExitUnconstrainedSyscall {
             ctx.rt.record = std::mem::take(&mut ctx.rt.unconstrained_state.record);
             ctx.rt.memory_accesses = std::mem::take(&mut ctx.rt.unconstrained_state.op_record);
             ctx.rt.executor_mode = ctx.rt.unconstrained_state.executor_mode;
+            ctx.rt.call_stack.truncate(ctx.rt.unconstrained_state.call_depth);
             ctx.rt.unconstrained = false;
         }
         ctx.rt.unconstrained_state = ForkState::default();
-        Some(0)
+        Ok(Some(0))
     }
 }
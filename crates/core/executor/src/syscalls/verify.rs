@@ -1,3 +1,5 @@
+use crate::ExecutionError;
+
 use super::{Syscall, SyscallCode, SyscallContext};
 
 pub(crate) struct VerifySyscall;
@@ -7,16 +9,24 @@ impl Syscall for VerifySyscall {
     fn execute(
         &self,
         ctx: &mut SyscallContext,
-        _: SyscallCode,
+        syscall_code: SyscallCode,
         vkey_ptr: u32,
         pv_digest_ptr: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
+        let pc = ctx.pc();
         let rt = &mut ctx.rt;
 
-        // vkey_ptr is a pointer to [u32; 8] which contains the verification key.
-        assert_eq!(vkey_ptr % 4, 0, "vkey_ptr must be word-aligned");
+        // vkey_ptr is a pointer to [u32; 8] which contains the verification key, and
         // pv_digest_ptr is a pointer to [u32; 8] which contains the public values digest.
-        assert_eq!(pv_digest_ptr % 4, 0, "pv_digest_ptr must be word-aligned");
+        for ptr in [vkey_ptr, pv_digest_ptr] {
+            if ptr % 4 != 0 {
+                return Err(ExecutionError::UnalignedSyscallPointer {
+                    pc,
+                    syscall: syscall_code,
+                    ptr,
+                });
+            }
+        }
 
         let vkey = (0..8).map(|i| rt.word(vkey_ptr + i * 4)).collect::<Vec<u32>>();
 
@@ -24,7 +34,11 @@ impl Syscall for VerifySyscall {
 
         let proof_index = rt.state.proof_stream_ptr;
         if proof_index >= rt.state.proof_stream.len() {
-            panic!("Not enough proofs were written to the runtime.");
+            return Err(ExecutionError::MissingProof {
+                pc,
+                syscall: syscall_code,
+                index: proof_index,
+            });
         }
         let (proof, proof_vk) = &rt.state.proof_stream[proof_index].clone();
         rt.state.proof_stream_ptr += 1;
@@ -35,14 +49,14 @@ impl Syscall for VerifySyscall {
         ctx.rt
             .subproof_verifier
             .verify_deferred_proof(proof, proof_vk, vkey_bytes, pv_digest_bytes)
-            .unwrap_or_else(|e| {
-                panic!(
-                    "Failed to verify proof {proof_index} with digest {}: {}",
-                    hex::encode(bytemuck::cast_slice(&pv_digest_bytes)),
-                    e
-                )
-            });
-
-        None
+            .map_err(|error| ExecutionError::InvalidProof {
+                pc,
+                syscall: syscall_code,
+                index: proof_index,
+                digest: hex::encode(bytemuck::cast_slice(&pv_digest_bytes)),
+                reason: error.to_string(),
+            })?;
+
+        Ok(None)
     }
 }
@@ -1,6 +1,8 @@
-use sp1_primitives::consts::num_to_comma_separated;
+use sp1_primitives::{
+    channels::decode_channel_write, consts::num_to_comma_separated, io::FD_PUBLIC_VALUE_CHANNELS,
+};
 
-use crate::{Executor, Register};
+use crate::{ExecutionError, Executor, GuestPanic, Register, FD_PANIC};
 
 use super::{Syscall, SyscallCode, SyscallContext};
 
@@ -12,10 +14,10 @@ impl Syscall for WriteSyscall {
     /// If stdout (fd = 1):
     /// - If the stream is a cycle tracker, either log the cycle tracker or accumulate it in the
     ///   report.
-    /// - Else, print the stream to stdout.
+    /// - Else, write the stream to the stdout sink, or print it if there is none.
     ///
     /// If stderr (fd = 2):
-    /// - Print the stream to stderr.
+    /// - Write the stream to the stderr sink, or print it if there is none.
     ///
     /// If fd = 3:
     /// - Update the public value stream.
@@ -23,6 +25,12 @@ impl Syscall for WriteSyscall {
     /// If fd = 4:
     /// - Update the input stream.
     ///
+    /// If fd = 10:
+    /// - Append to the named public values channel.
+    ///
+    /// If fd = 11:
+    /// - Record the panic the program reported.
+    ///
     /// If the fd matches a hook in the hook registry, invoke the hook.
     ///
     /// Else, log a warning.
@@ -30,10 +38,10 @@ impl Syscall for WriteSyscall {
     fn execute(
         &self,
         ctx: &mut SyscallContext,
-        _: SyscallCode,
+        syscall_code: SyscallCode,
         arg1: u32,
         arg2: u32,
-    ) -> Option<u32> {
+    ) -> Result<Option<u32>, ExecutionError> {
         let a2 = Register::X12;
         let rt = &mut ctx.rt;
         let fd = arg1;
@@ -42,28 +50,41 @@ impl Syscall for WriteSyscall {
         // Read nbytes from memory starting at write_buf.
         let bytes = (0..nbytes).map(|i| rt.byte(write_buf + i)).collect::<Vec<u8>>();
         let slice = bytes.as_slice();
+        let pc = rt.state.pc;
+        let utf8 = || {
+            core::str::from_utf8(slice).map_err(|_| ExecutionError::InvalidUtf8Output {
+                pc,
+                syscall: syscall_code,
+                fd,
+            })
+        };
         if fd == 1 {
-            let s = core::str::from_utf8(slice).unwrap();
+            let s = utf8()?;
             match parse_cycle_tracker_command(s) {
                 Some(command) => handle_cycle_tracker_command(rt, command),
-                None => {
-                    // If the string does not match any known command, print it to stdout.
-                    let flush_s = update_io_buf(ctx, fd, s);
-                    if !flush_s.is_empty() {
-                        flush_s.into_iter().for_each(|line| println!("stdout: {}", line));
-                    }
-                }
+                // If the string does not match any known command, write it to stdout.
+                None => write_output(ctx, fd, s),
             }
         } else if fd == 2 {
-            let s = core::str::from_utf8(slice).unwrap();
-            let flush_s = update_io_buf(ctx, fd, s);
-            if !flush_s.is_empty() {
-                flush_s.into_iter().for_each(|line| println!("stderr: {}", line));
-            }
+            let s = utf8()?;
+            write_output(ctx, fd, s);
         } else if fd == 3 {
             rt.state.public_values_stream.extend_from_slice(slice);
         } else if fd == 4 {
             rt.state.input_stream.push(slice.to_vec());
+        } else if fd == FD_PUBLIC_VALUE_CHANNELS {
+            let (name, data) = decode_channel_write(slice)
+                .ok_or(ExecutionError::InvalidChannelWrite { pc, syscall: syscall_code })?;
+            rt.state
+                .public_value_channels
+                .entry(name.to_string())
+                .or_default()
+                .extend_from_slice(data);
+        } else if fd == FD_PANIC {
+            rt.guest_panic = GuestPanic::decode(slice);
+            if rt.guest_panic.is_none() {
+                tracing::warn!("the program reported a panic that could not be decoded");
+            }
         } else if let Some(mut hook) = rt.hook_registry.get(fd) {
             let res = hook.invoke_hook(rt.hook_env(), slice);
             // Add result vectors to the beginning of the stream.
@@ -72,7 +93,7 @@ impl Syscall for WriteSyscall {
         } else {
             tracing::warn!("tried to write to unknown file descriptor {fd}");
         }
-        None
+        Ok(None)
     }
 }
 
@@ -143,6 +164,28 @@ fn end_cycle_tracker(rt: &mut Executor, name: &str) -> Option<u64> {
     None
 }
 
+/// Write the program's output to stdout (fd = 1) or stderr (fd = 2).
+///
+/// The output goes to the sink set in the context, or is printed a line at a time with a prefix
+/// if there is none.
+fn write_output(ctx: &mut SyscallContext, fd: u32, s: &str) {
+    let rt = &mut *ctx.rt;
+    if rt.capture_output {
+        let captured = if fd == 1 { &mut rt.report.stdout } else { &mut rt.report.stderr };
+        captured.push_str(s);
+    }
+    let (sink, prefix) = if fd == 1 { (&rt.stdout, "stdout") } else { (&rt.stderr, "stderr") };
+    if let Some(sink) = sink {
+        if let Err(error) = sink.lock().unwrap().write_all(s.as_bytes()) {
+            tracing::warn!("failed to write the {prefix} of the program: {error}");
+        }
+        return;
+    }
+    for line in update_io_buf(ctx, fd, s) {
+        println!("{prefix}: {line}");
+    }
+}
+
 /// Update the io buffer for the given file descriptor with the given string.
 #[allow(clippy::mut_mut)]
 fn update_io_buf(ctx: &mut SyscallContext, fd: u32, s: &str) -> Vec<String> {
@@ -0,0 +1,153 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use sp1_primitives::consts::{STACK_TOP, WORD_SIZE};
+use thiserror::Error;
+
+use crate::Program;
+
+/// A problem that keeps a [`Program`] from running in the zkVM.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProgramIssue {
+    /// The program has no instructions.
+    #[error("the program has no instructions")]
+    NoInstructions,
+
+    /// The start address is not the address of an instruction.
+    #[error("the start address {0:#010x} is not the address of an instruction")]
+    EntryNotInstruction(u32),
+
+    /// A word of the memory image is not at a word-aligned address.
+    #[error("the memory image has a word at the unaligned address {0:#010x}")]
+    UnalignedImageAddress(u32),
+
+    /// The memory image holds the first word of the stack.
+    #[error("the memory image holds the word at {0:#010x}, where the stack starts")]
+    StackCollision(u32),
+
+    /// The memory image ends below the stack, so the heap would grow into it.
+    #[error("the heap starts at {heap_start:#010x}, below the stack top {stack_top:#010x}, and would grow into the stack", stack_top = STACK_TOP)]
+    HeapCollision {
+        /// The end of the memory image, where the heap starts.
+        heap_start: u32,
+    },
+}
+
+/// A summary of a [`Program`] and the problems that would make it fail in the zkVM, meant to be
+/// checked before paying for a proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// The number of instructions.
+    pub instructions: usize,
+    /// The size of the code in bytes.
+    pub code_size: u32,
+    /// The size of the initial memory image in bytes.
+    pub memory_image_size: usize,
+    /// The address the heap starts at, or `None` if the memory image is empty.
+    pub heap_start: Option<u32>,
+    /// The problems found, empty if the program is valid.
+    pub issues: Vec<ProgramIssue>,
+}
+
+impl ValidationReport {
+    /// Whether no problems were found.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        writeln!(f, "instructions: {} ({} bytes)", self.instructions, self.code_size)?;
+        writeln!(f, "memory image: {} bytes", self.memory_image_size)?;
+        match self.heap_start {
+            Some(heap_start) => writeln!(f, "heap start: {heap_start:#010x}")?,
+            None => writeln!(f, "heap start: unknown")?,
+        }
+        for issue in &self.issues {
+            writeln!(f, "error: {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Program {
+    /// Check that the program can run in the zkVM.
+    ///
+    /// Programs decoded from an ELF are already checked by the loader, but programs built or
+    /// deserialized by hand aren't.
+    #[must_use]
+    pub fn validate(&self) -> ValidationReport {
+        let mut issues = Vec::new();
+
+        if self.instructions.is_empty() {
+            issues.push(ProgramIssue::NoInstructions);
+        } else if self.fetch(self.pc_start).is_none() {
+            issues.push(ProgramIssue::EntryNotInstruction(self.pc_start));
+        }
+
+        let mut addresses = self.memory_image.keys().copied().collect::<Vec<_>>();
+        addresses.sort_unstable();
+        issues.extend(
+            addresses
+                .iter()
+                .filter(|addr| *addr % WORD_SIZE as u32 != 0)
+                .map(|addr| ProgramIssue::UnalignedImageAddress(*addr)),
+        );
+
+        let stack_start = STACK_TOP - WORD_SIZE as u32;
+        if self.memory_image.contains_key(&stack_start) {
+            issues.push(ProgramIssue::StackCollision(stack_start));
+        }
+
+        // The heap starts right after the highest word of the image.
+        let heap_start = addresses.last().map(|addr| addr.saturating_add(WORD_SIZE as u32));
+        if let Some(heap_start) = heap_start.filter(|heap_start| *heap_start < STACK_TOP) {
+            issues.push(ProgramIssue::HeapCollision { heap_start });
+        }
+
+        ValidationReport {
+            instructions: self.instructions.len(),
+            code_size: self.code_size(),
+            memory_image_size: self.memory_image.len() * WORD_SIZE,
+            heap_start,
+            issues,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::programs::tests::simple_program;
+
+    #[test]
+    fn simple_program_is_valid() {
+        let report = simple_program().validate();
+        assert!(report.is_valid(), "{report}");
+        assert_eq!(report.heap_start, None);
+    }
+
+    #[test]
+    fn reports_entry_and_image_issues() {
+        let mut program = simple_program();
+        program.pc_start = program.pc_base + 2;
+        program.memory_image.insert(STACK_TOP - 4, 0);
+        program.memory_image.insert(0x1001, 0);
+
+        let report = program.validate();
+        assert_eq!(
+            report.issues,
+            vec![
+                ProgramIssue::EntryNotInstruction(program.pc_base + 2),
+                ProgramIssue::UnalignedImageAddress(0x1001),
+                ProgramIssue::StackCollision(STACK_TOP - 4),
+            ]
+        );
+
+        let mut program = simple_program();
+        program.memory_image.insert(0x1000, 0);
+        let report = program.validate();
+        assert_eq!(report.issues, vec![ProgramIssue::HeapCollision { heap_start: 0x1004 }]);
+    }
+}
@@ -15,7 +15,7 @@ use sp1_core_executor::{
 };
 use sp1_derive::AlignedBorrow;
 use sp1_stark::{
-    air::{MachineAir, SP1AirBuilder},
+    air::{Column, ColumnDescriptor, ColumnLayout, MachineAir, SP1AirBuilder},
     Word,
 };
 
@@ -69,6 +69,10 @@ impl<F: PrimeField> MachineAir<F> for AddSubChip {
         "AddSub".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(AddSubCols::<Column>::layout())
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -233,13 +237,20 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::BorrowMut;
+
     use p3_baby_bear::BabyBear;
+    use p3_challenger::FieldChallenger;
+    use p3_field::AbstractField;
     use p3_matrix::dense::RowMajorMatrix;
     use rand::{thread_rng, Rng};
     use sp1_core_executor::{events::AluEvent, ExecutionRecord, Opcode};
-    use sp1_stark::{air::MachineAir, baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig};
+    use sp1_stark::{
+        air::MachineAir, baby_bear_poseidon2::BabyBearPoseidon2, debug_constraints, Chip,
+        StarkGenericConfig,
+    };
 
-    use super::AddSubChip;
+    use super::{AddSubChip, AddSubCols};
     use crate::utils::{uni_stark_prove as prove, uni_stark_verify as verify};
 
     #[test]
@@ -293,4 +304,42 @@ mod tests {
         let mut challenger = config.challenger();
         verify(&config, &chip, &mut challenger, &proof).unwrap();
     }
+
+    #[test]
+    fn debug_constraints_names_columns() {
+        let mut shard = ExecutionRecord::default();
+        shard.add_events = vec![
+            AluEvent::new(0, 0, Opcode::ADD, 14, 8, 6),
+            AluEvent::new(0, 0, Opcode::SUB, 2, 8, 6),
+        ];
+        let chip = Chip::new(AddSubChip::default());
+        let mut trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&shard, &mut ExecutionRecord::default());
+
+        // Corrupt the low byte of the sum on the first row.
+        let cols: &mut AddSubCols<BabyBear> = trace.row_mut(0).borrow_mut();
+        cols.add_operation.value[0] += BabyBear::one();
+
+        let config = BabyBearPoseidon2::new();
+        let mut challenger = config.challenger();
+        let challenges: Vec<<BabyBearPoseidon2 as StarkGenericConfig>::Challenge> =
+            (0..4).map(|_| challenger.sample_ext_element()).collect();
+        let (perm, global_sum, local_sum) =
+            chip.generate_permutation_trace(None, &trace, &challenges);
+
+        let violations = debug_constraints::<BabyBearPoseidon2, _>(
+            &chip,
+            None,
+            &trace,
+            &perm,
+            &challenges,
+            &[],
+            &[global_sum, local_sum],
+        );
+        assert!(!violations.is_empty());
+        assert!(violations.iter().all(|v| v.chip == "AddSub" && v.row == 0));
+        assert!(violations
+            .iter()
+            .any(|v| v.columns.iter().any(|column| column == "add_operation.value[0]")));
+    }
 }
@@ -0,0 +1,609 @@
+use core::{
+    borrow::{Borrow, BorrowMut},
+    mem::size_of,
+};
+
+use hashbrown::HashMap;
+use itertools::Itertools;
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::{dense::RowMajorMatrix, Matrix};
+use p3_maybe_rayon::prelude::*;
+use sp1_core_executor::{
+    events::{AluEvent, ByteLookupEvent, ByteRecord},
+    ExecutionRecord, Opcode, Program,
+};
+use sp1_derive::AlignedBorrow;
+use sp1_stark::{
+    air::{Column, ColumnDescriptor, ColumnLayout, MachineAir, SP1AirBuilder},
+    Word,
+};
+
+use crate::{air::WordAirBuilder, operations::AddOperation, utils::pad_rows_fixed};
+
+/// The number of main trace columns for `BitManipChip`.
+pub const NUM_BITMANIP_COLS: usize = size_of::<BitManipCols<u8>>();
+
+/// The number of bits in a word.
+const WORD_BITS: usize = 32;
+
+/// The number of stages of the barrel rotator, one per bit of the rotation amount.
+const ROTATION_STAGES: usize = 5;
+
+/// A chip that implements the Zba and Zbb bit-manipulation instructions.
+///
+/// Both operands are decomposed into bits, from which every result is computed: the logical
+/// operations bit by bit, the counts from running products of the bits, the comparisons of MIN and
+/// MAX from a running product of bit equalities, the rotations with a barrel rotator and the
+/// shifted additions with an [`AddOperation`].
+#[derive(Default)]
+pub struct BitManipChip;
+
+/// The column layout for the chip.
+#[derive(AlignedBorrow, Default, Clone, Copy)]
+#[repr(C)]
+pub struct BitManipCols<T> {
+    /// The shard number, used for byte lookup table.
+    pub shard: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The output operand.
+    pub a: Word<T>,
+
+    /// The first input operand.
+    pub b: Word<T>,
+
+    /// The second input operand.
+    pub c: Word<T>,
+
+    /// The bits of `b`, least significant first.
+    pub b_bits: [T; WORD_BITS],
+
+    /// The bits of `c`, least significant first.
+    pub c_bits: [T; WORD_BITS],
+
+    /// Whether the `i + 1` most significant bits of `b` are all zero.
+    pub leading_zeros: [T; WORD_BITS],
+
+    /// Whether the `i + 1` least significant bits of `b` are all zero.
+    pub trailing_zeros: [T; WORD_BITS],
+
+    /// Whether the `i + 1` most significant bits of `b` and `c` are equal.
+    pub prefix_eq: [T; WORD_BITS],
+
+    /// Whether `b < c`, signed for MIN and MAX and unsigned otherwise.
+    pub lt: T,
+
+    /// Whether each byte of `b` is non-zero.
+    pub byte_nonzero: [T; 4],
+
+    /// The inverse of each byte of `b`, or zero for a zero byte.
+    pub byte_inverse: [T; 4],
+
+    /// The bits after each stage of the barrel rotator, which rotates by `2^i` in stage `i` if
+    /// bit `i` of `c` is set.
+    pub rotation: [[T; WORD_BITS]; ROTATION_STAGES],
+
+    /// The value of `b` shifted left for SH1ADD, SH2ADD and SH3ADD.
+    pub shifted: Word<T>,
+
+    /// The addition of `shifted` and `c` for SH1ADD, SH2ADD and SH3ADD.
+    pub add_operation: AddOperation<T>,
+
+    /// If the opcode is ANDN.
+    pub is_andn: T,
+    /// If the opcode is ORN.
+    pub is_orn: T,
+    /// If the opcode is XNOR.
+    pub is_xnor: T,
+    /// If the opcode is CLZ.
+    pub is_clz: T,
+    /// If the opcode is CTZ.
+    pub is_ctz: T,
+    /// If the opcode is CPOP.
+    pub is_cpop: T,
+    /// If the opcode is MAX.
+    pub is_max: T,
+    /// If the opcode is MAXU.
+    pub is_maxu: T,
+    /// If the opcode is MIN.
+    pub is_min: T,
+    /// If the opcode is MINU.
+    pub is_minu: T,
+    /// If the opcode is SEXT.B.
+    pub is_sextb: T,
+    /// If the opcode is SEXT.H.
+    pub is_sexth: T,
+    /// If the opcode is ZEXT.H.
+    pub is_zexth: T,
+    /// If the opcode is ROL.
+    pub is_rol: T,
+    /// If the opcode is ROR.
+    pub is_ror: T,
+    /// If the opcode is ORC.B.
+    pub is_orcb: T,
+    /// If the opcode is REV8.
+    pub is_rev8: T,
+    /// If the opcode is SH1ADD.
+    pub is_sh1add: T,
+    /// If the opcode is SH2ADD.
+    pub is_sh2add: T,
+    /// If the opcode is SH3ADD.
+    pub is_sh3add: T,
+}
+
+impl<T: Copy> BitManipCols<T> {
+    /// The opcode selectors, along with their opcodes.
+    pub fn selectors(&self) -> [(T, Opcode); 20] {
+        [
+            (self.is_andn, Opcode::ANDN),
+            (self.is_orn, Opcode::ORN),
+            (self.is_xnor, Opcode::XNOR),
+            (self.is_clz, Opcode::CLZ),
+            (self.is_ctz, Opcode::CTZ),
+            (self.is_cpop, Opcode::CPOP),
+            (self.is_max, Opcode::MAX),
+            (self.is_maxu, Opcode::MAXU),
+            (self.is_min, Opcode::MIN),
+            (self.is_minu, Opcode::MINU),
+            (self.is_sextb, Opcode::SEXTB),
+            (self.is_sexth, Opcode::SEXTH),
+            (self.is_zexth, Opcode::ZEXTH),
+            (self.is_rol, Opcode::ROL),
+            (self.is_ror, Opcode::ROR),
+            (self.is_orcb, Opcode::ORCB),
+            (self.is_rev8, Opcode::REV8),
+            (self.is_sh1add, Opcode::SH1ADD),
+            (self.is_sh2add, Opcode::SH2ADD),
+            (self.is_sh3add, Opcode::SH3ADD),
+        ]
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for BitManipChip {
+    type Record = ExecutionRecord;
+
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "BitManip".to_string()
+    }
+
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(BitManipCols::<Column>::layout())
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        _: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        // Generate the trace rows for each event.
+        let mut rows = input
+            .bitmanip_events
+            .par_iter()
+            .map(|event| {
+                let mut row = [F::zero(); NUM_BITMANIP_COLS];
+                let mut new_byte_lookup_events: Vec<ByteLookupEvent> = Vec::new();
+                let cols: &mut BitManipCols<F> = row.as_mut_slice().borrow_mut();
+                self.event_to_row(event, cols, &mut new_byte_lookup_events);
+
+                row
+            })
+            .collect::<Vec<_>>();
+
+        // Pad the trace to a power of two depending on the proof shape in `input`.
+        pad_rows_fixed(
+            &mut rows,
+            || [F::zero(); NUM_BITMANIP_COLS],
+            input.fixed_log2_rows::<F, Self>(self),
+        );
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_BITMANIP_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut BitManipCols<F> =
+                trace.values[i * NUM_BITMANIP_COLS..(i + 1) * NUM_BITMANIP_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn generate_dependencies(&self, input: &Self::Record, output: &mut Self::Record) {
+        let chunk_size = std::cmp::max(input.bitmanip_events.len() / num_cpus::get(), 1);
+
+        let blu_batches = input
+            .bitmanip_events
+            .par_chunks(chunk_size)
+            .map(|events| {
+                let mut blu: HashMap<u32, HashMap<ByteLookupEvent, usize>> = HashMap::new();
+                events.iter().for_each(|event| {
+                    let mut row = [F::zero(); NUM_BITMANIP_COLS];
+                    let cols: &mut BitManipCols<F> = row.as_mut_slice().borrow_mut();
+                    self.event_to_row(event, cols, &mut blu);
+                });
+                blu
+            })
+            .collect::<Vec<_>>();
+
+        output.add_sharded_byte_lookup_events(blu_batches.iter().collect_vec());
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.bitmanip_events.is_empty()
+    }
+}
+
+impl BitManipChip {
+    /// Create a row from an event.
+    fn event_to_row<F: PrimeField32>(
+        &self,
+        event: &AluEvent,
+        cols: &mut BitManipCols<F>,
+        blu: &mut impl ByteRecord,
+    ) {
+        let (b, c) = (event.b, event.c);
+        cols.shard = F::from_canonical_u32(event.shard);
+        cols.a = Word::from(event.a);
+        cols.b = Word::from(b);
+        cols.c = Word::from(c);
+
+        let bit = |value: u32, i: usize| (value >> i) & 1 == 1;
+        for i in 0..WORD_BITS {
+            cols.b_bits[i] = F::from_bool(bit(b, i));
+            cols.c_bits[i] = F::from_bool(bit(c, i));
+            cols.leading_zeros[i] = F::from_bool(b.leading_zeros() as usize > i);
+            cols.trailing_zeros[i] = F::from_bool(b.trailing_zeros() as usize > i);
+            cols.prefix_eq[i] = F::from_bool((b ^ c).leading_zeros() as usize > i);
+        }
+
+        let is_signed = matches!(event.opcode, Opcode::MAX | Opcode::MIN);
+        cols.lt = F::from_bool(if is_signed { (b as i32) < (c as i32) } else { b < c });
+
+        for (i, byte) in b.to_le_bytes().into_iter().enumerate() {
+            let byte = F::from_canonical_u8(byte);
+            cols.byte_nonzero[i] = F::from_bool(!byte.is_zero());
+            cols.byte_inverse[i] = byte.try_inverse().unwrap_or_default();
+        }
+
+        let mut rotated = b;
+        for (stage, bits) in cols.rotation.iter_mut().enumerate() {
+            if bit(c, stage) {
+                match event.opcode {
+                    Opcode::ROL => rotated = rotated.rotate_left(1 << stage),
+                    Opcode::ROR => rotated = rotated.rotate_right(1 << stage),
+                    _ => {}
+                }
+            }
+            for (i, bit_col) in bits.iter_mut().enumerate() {
+                *bit_col = F::from_bool(bit(rotated, i));
+            }
+        }
+
+        let shift = match event.opcode {
+            Opcode::SH1ADD => Some(1),
+            Opcode::SH2ADD => Some(2),
+            Opcode::SH3ADD => Some(3),
+            _ => None,
+        };
+        if let Some(shift) = shift {
+            let shifted = b << shift;
+            cols.shifted = Word::from(shifted);
+            cols.add_operation.populate(blu, event.shard, shifted, c);
+        }
+
+        let selector = match event.opcode {
+            Opcode::ANDN => &mut cols.is_andn,
+            Opcode::ORN => &mut cols.is_orn,
+            Opcode::XNOR => &mut cols.is_xnor,
+            Opcode::CLZ => &mut cols.is_clz,
+            Opcode::CTZ => &mut cols.is_ctz,
+            Opcode::CPOP => &mut cols.is_cpop,
+            Opcode::MAX => &mut cols.is_max,
+            Opcode::MAXU => &mut cols.is_maxu,
+            Opcode::MIN => &mut cols.is_min,
+            Opcode::MINU => &mut cols.is_minu,
+            Opcode::SEXTB => &mut cols.is_sextb,
+            Opcode::SEXTH => &mut cols.is_sexth,
+            Opcode::ZEXTH => &mut cols.is_zexth,
+            Opcode::ROL => &mut cols.is_rol,
+            Opcode::ROR => &mut cols.is_ror,
+            Opcode::ORCB => &mut cols.is_orcb,
+            Opcode::REV8 => &mut cols.is_rev8,
+            Opcode::SH1ADD => &mut cols.is_sh1add,
+            Opcode::SH2ADD => &mut cols.is_sh2add,
+            Opcode::SH3ADD => &mut cols.is_sh3add,
+            opcode => unreachable!("{opcode} is not a bit-manipulation instruction"),
+        };
+        *selector = F::one();
+    }
+}
+
+/// The little-endian bytes of a word given by its bits, least significant first.
+fn bytes_of<E: AbstractField>(bits: &[E; WORD_BITS]) -> Word<E> {
+    Word(core::array::from_fn(|byte| {
+        (0..8).fold(E::zero(), |acc, i| {
+            acc + bits[8 * byte + i].clone() * E::from_canonical_u32(1 << i)
+        })
+    }))
+}
+
+impl<F> BaseAir<F> for BitManipChip {
+    fn width(&self) -> usize {
+        NUM_BITMANIP_COLS
+    }
+}
+
+impl<AB> Air<AB> for BitManipChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &BitManipCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &BitManipCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder.when_transition().assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Check that the operation flags are boolean and that at most one of them is set.
+        let mut is_real = AB::Expr::zero();
+        let mut opcode = AB::Expr::zero();
+        for (selector, selector_opcode) in local.selectors() {
+            builder.assert_bool(selector);
+            is_real += selector.into();
+            opcode += selector * selector_opcode.as_field::<AB::F>();
+        }
+        builder.assert_bool(is_real.clone());
+
+        // Decompose the operands into bits.
+        let one = AB::Expr::one();
+        let b_bits: [AB::Expr; WORD_BITS] = local.b_bits.map(Into::into);
+        let c_bits: [AB::Expr; WORD_BITS] = local.c_bits.map(Into::into);
+        for (b_bit, c_bit) in local.b_bits.into_iter().zip(local.c_bits) {
+            builder.assert_bool(b_bit);
+            builder.assert_bool(c_bit);
+        }
+        builder.assert_word_eq(local.b, bytes_of(&b_bits));
+        builder.assert_word_eq(local.c, bytes_of(&c_bits));
+
+        // ANDN, ORN and XNOR, bit by bit.
+        let xor = |b: &AB::Expr, c: &AB::Expr| {
+            b.clone() + c.clone() - b.clone() * c.clone() * AB::F::two()
+        };
+        let andn = core::array::from_fn(|i| b_bits[i].clone() * (one.clone() - c_bits[i].clone()));
+        let orn = core::array::from_fn(|i| {
+            one.clone() - c_bits[i].clone() + b_bits[i].clone() * c_bits[i].clone()
+        });
+        let xnor = core::array::from_fn(|i| one.clone() - xor(&b_bits[i], &c_bits[i]));
+        builder.when(local.is_andn).assert_word_eq(local.a, bytes_of(&andn));
+        builder.when(local.is_orn).assert_word_eq(local.a, bytes_of(&orn));
+        builder.when(local.is_xnor).assert_word_eq(local.a, bytes_of(&xnor));
+
+        // CLZ and CTZ count the prefixes of zero bits, whose flags are running products of the
+        // negated bits starting from the most and least significant bit.
+        let msb_first = (0..WORD_BITS).rev();
+        for (i, bit) in msb_first.clone().enumerate() {
+            let previous = if i == 0 { is_real.clone() } else { local.leading_zeros[i - 1].into() };
+            builder
+                .assert_eq(local.leading_zeros[i], previous * (one.clone() - b_bits[bit].clone()));
+        }
+        for i in 0..WORD_BITS {
+            let previous =
+                if i == 0 { is_real.clone() } else { local.trailing_zeros[i - 1].into() };
+            builder
+                .assert_eq(local.trailing_zeros[i], previous * (one.clone() - b_bits[i].clone()));
+        }
+        let count = |flags: &[AB::Var]| {
+            Word([
+                flags.iter().fold(AB::Expr::zero(), |acc, &flag| acc + flag),
+                AB::Expr::zero(),
+                AB::Expr::zero(),
+                AB::Expr::zero(),
+            ])
+        };
+        builder.when(local.is_clz).assert_word_eq(local.a, count(&local.leading_zeros));
+        builder.when(local.is_ctz).assert_word_eq(local.a, count(&local.trailing_zeros));
+        builder.when(local.is_cpop).assert_word_eq(local.a, count(&local.b_bits));
+
+        // MIN and MAX compare the operands at their most significant differing bit, which is the
+        // first one after the prefix of equal bits. For signed operands the most significant bit
+        // compares the other way around.
+        let is_signed = local.is_max + local.is_min;
+        let mut lt = is_signed.clone() * b_bits[31].clone() * (one.clone() - c_bits[31].clone())
+            + (one.clone() - is_signed) * (one.clone() - b_bits[31].clone()) * c_bits[31].clone();
+        for (i, bit) in msb_first.enumerate() {
+            let previous = if i == 0 { is_real.clone() } else { local.prefix_eq[i - 1].into() };
+            builder.assert_eq(
+                local.prefix_eq[i],
+                previous.clone() * (one.clone() - xor(&b_bits[bit], &c_bits[bit])),
+            );
+            if i > 0 {
+                lt += previous * (one.clone() - b_bits[bit].clone()) * c_bits[bit].clone();
+            }
+        }
+        builder.assert_eq(local.lt, lt);
+        let min: Word<AB::Expr> =
+            Word(core::array::from_fn(|i| local.c[i] + local.lt * (local.b[i] - local.c[i])));
+        let max: Word<AB::Expr> =
+            Word(core::array::from_fn(|i| local.b[i] + local.lt * (local.c[i] - local.b[i])));
+        builder.when(local.is_min + local.is_minu).assert_word_eq(local.a, min);
+        builder.when(local.is_max + local.is_maxu).assert_word_eq(local.a, max);
+
+        // SEXT.B, SEXT.H, ZEXT.H and REV8 move the bytes of `b` around.
+        let byte_mask = AB::F::from_canonical_u8(0xff);
+        let sign_b = b_bits[7].clone() * byte_mask;
+        let sign_h = b_bits[15].clone() * byte_mask;
+        builder.when(local.is_sextb).assert_word_eq(
+            local.a,
+            Word([local.b[0].into(), sign_b.clone(), sign_b.clone(), sign_b]),
+        );
+        builder.when(local.is_sexth).assert_word_eq(
+            local.a,
+            Word([local.b[0].into(), local.b[1].into(), sign_h.clone(), sign_h]),
+        );
+        builder.when(local.is_zexth).assert_word_eq(
+            local.a,
+            Word([local.b[0].into(), local.b[1].into(), AB::Expr::zero(), AB::Expr::zero()]),
+        );
+        builder
+            .when(local.is_rev8)
+            .assert_word_eq(local.a, Word([local.b[3], local.b[2], local.b[1], local.b[0]]));
+
+        // ORC.B sets the non-zero bytes of `b` to 0xff.
+        for i in 0..4 {
+            builder.assert_eq(local.b[i] * local.byte_inverse[i], local.byte_nonzero[i]);
+            builder.assert_zero(local.b[i] * (one.clone() - local.byte_nonzero[i]));
+        }
+        builder
+            .when(local.is_orcb)
+            .assert_word_eq(local.a, Word(local.byte_nonzero.map(|nonzero| nonzero * byte_mask)));
+
+        // ROL and ROR go through the stages of the barrel rotator, each of which rotates the bits
+        // by `2^stage` if the bit of the rotation amount is set.
+        let is_rotation = local.is_rol + local.is_ror;
+        let mut bits = b_bits.clone();
+        for (stage, rotated) in local.rotation.iter().enumerate() {
+            let offset = 1 << stage;
+            for (i, &rotated_bit) in rotated.iter().enumerate() {
+                let right = bits[(i + offset) % WORD_BITS].clone();
+                let left = bits[(i + WORD_BITS - offset) % WORD_BITS].clone();
+                builder.assert_eq(
+                    rotated_bit,
+                    bits[i].clone()
+                        + c_bits[stage].clone()
+                            * (local.is_ror * right + local.is_rol * left
+                                - is_rotation.clone() * bits[i].clone()),
+                );
+            }
+            bits = rotated.map(Into::into);
+        }
+        builder.when(is_rotation).assert_word_eq(local.a, bytes_of(&bits));
+
+        // SH1ADD, SH2ADD and SH3ADD add `c` to `b` shifted left by one, two or three bits.
+        for (shift, is_shadd) in [(1, local.is_sh1add), (2, local.is_sh2add), (3, local.is_sh3add)]
+        {
+            let shifted = core::array::from_fn(|i| {
+                if i < shift {
+                    AB::Expr::zero()
+                } else {
+                    b_bits[i - shift].clone()
+                }
+            });
+            builder.when(is_shadd).assert_word_eq(local.shifted, bytes_of(&shifted));
+        }
+        let is_shadd = local.is_sh1add + local.is_sh2add + local.is_sh3add;
+        AddOperation::<AB::F>::eval(
+            builder,
+            local.shifted,
+            local.c,
+            local.add_operation,
+            is_shadd.clone(),
+        );
+        builder.when(is_shadd).assert_word_eq(local.a, local.add_operation.value);
+
+        // Receive the arguments.
+        builder.receive_alu(opcode, local.a, local.b, local.c, local.shard, local.nonce, is_real);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_matrix::dense::RowMajorMatrix;
+    use sp1_core_executor::{events::AluEvent, ExecutionRecord, Opcode};
+    use sp1_stark::{air::MachineAir, baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig};
+
+    use super::BitManipChip;
+    use crate::utils::{uni_stark_prove as prove, uni_stark_verify as verify};
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn prove_babybear() {
+        let config = BabyBearPoseidon2::new();
+        let mut challenger = config.challenger();
+
+        let operands = [
+            (0x12345678, 0x0000f0f0),
+            (0xfffffff3, 5),
+            (0x80000000, 0x7fffffff),
+            (0, 0),
+            (0x00ff0080, 31),
+            (7, 0xffffffff),
+        ];
+        let mut shard = ExecutionRecord::default();
+        for opcode in [
+            Opcode::ANDN,
+            Opcode::ORN,
+            Opcode::XNOR,
+            Opcode::CLZ,
+            Opcode::CTZ,
+            Opcode::CPOP,
+            Opcode::MAX,
+            Opcode::MAXU,
+            Opcode::MIN,
+            Opcode::MINU,
+            Opcode::SEXTB,
+            Opcode::SEXTH,
+            Opcode::ZEXTH,
+            Opcode::ROL,
+            Opcode::ROR,
+            Opcode::ORCB,
+            Opcode::REV8,
+            Opcode::SH1ADD,
+            Opcode::SH2ADD,
+            Opcode::SH3ADD,
+        ] {
+            for (b, c) in operands {
+                let a = bitmanip(opcode, b, c);
+                shard.bitmanip_events.push(AluEvent::new(0, 0, opcode, a, b, c));
+            }
+        }
+
+        let chip = BitManipChip::default();
+        let trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&shard, &mut ExecutionRecord::default());
+        let proof = prove::<BabyBearPoseidon2, _>(&config, &chip, &mut challenger, trace);
+
+        let mut challenger = config.challenger();
+        verify(&config, &chip, &mut challenger, &proof).unwrap();
+    }
+
+    /// The expected results, computed independently of the executor.
+    fn bitmanip(opcode: Opcode, b: u32, c: u32) -> u32 {
+        match opcode {
+            Opcode::ANDN => b & !c,
+            Opcode::ORN => b | !c,
+            Opcode::XNOR => !(b ^ c),
+            Opcode::CLZ => b.leading_zeros(),
+            Opcode::CTZ => b.trailing_zeros(),
+            Opcode::CPOP => b.count_ones(),
+            Opcode::MAX => (b as i32).max(c as i32) as u32,
+            Opcode::MAXU => b.max(c),
+            Opcode::MIN => (b as i32).min(c as i32) as u32,
+            Opcode::MINU => b.min(c),
+            Opcode::SEXTB => b as i8 as u32,
+            Opcode::SEXTH => b as i16 as u32,
+            Opcode::ZEXTH => b & 0xffff,
+            Opcode::ROL => b.rotate_left(c),
+            Opcode::ROR => b.rotate_right(c),
+            Opcode::ORCB => {
+                u32::from_le_bytes(b.to_le_bytes().map(|byte| if byte == 0 { 0 } else { 0xff }))
+            }
+            Opcode::REV8 => b.swap_bytes(),
+            Opcode::SH1ADD => (b << 1).wrapping_add(c),
+            Opcode::SH2ADD => (b << 2).wrapping_add(c),
+            Opcode::SH3ADD => (b << 3).wrapping_add(c),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -15,7 +15,7 @@ use sp1_core_executor::{
 };
 use sp1_derive::AlignedBorrow;
 use sp1_stark::{
-    air::{MachineAir, SP1AirBuilder},
+    air::{Column, ColumnDescriptor, ColumnLayout, MachineAir, SP1AirBuilder},
     Word,
 };
 
@@ -66,6 +66,10 @@ impl<F: PrimeField> MachineAir<F> for BitwiseChip {
         "Bitwise".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(BitwiseCols::<Column>::layout())
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -75,7 +75,10 @@ use sp1_core_executor::{
 };
 use sp1_derive::AlignedBorrow;
 use sp1_primitives::consts::WORD_SIZE;
-use sp1_stark::{air::MachineAir, Word};
+use sp1_stark::{
+    air::{Column, ColumnDescriptor, ColumnLayout, MachineAir},
+    Word,
+};
 
 use crate::{
     air::SP1CoreAirBuilder,
@@ -218,6 +221,10 @@ impl<F: PrimeField> MachineAir<F> for DivRemChip {
         "DivRem".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(DivRemCols::<Column>::layout())
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -267,9 +274,9 @@ impl<F: PrimeField> MachineAir<F> for DivRemChip {
                     cols.c_neg = cols.c_msb;
                     cols.is_overflow =
                         F::from_bool(event.b as i32 == i32::MIN && event.c as i32 == -1);
-                    cols.abs_remainder = Word::from((remainder as i32).abs() as u32);
-                    cols.abs_c = Word::from((event.c as i32).abs() as u32);
-                    cols.max_abs_c_or_1 = Word::from(u32::max(1, (event.c as i32).abs() as u32));
+                    cols.abs_remainder = Word::from((remainder as i32).unsigned_abs());
+                    cols.abs_c = Word::from((event.c as i32).unsigned_abs());
+                    cols.max_abs_c_or_1 = Word::from(u32::max(1, (event.c as i32).unsigned_abs()));
                 } else {
                     cols.abs_remainder = cols.remainder;
                     cols.abs_c = cols.c;
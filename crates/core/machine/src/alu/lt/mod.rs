@@ -15,7 +15,7 @@ use sp1_core_executor::{
 };
 use sp1_derive::AlignedBorrow;
 use sp1_stark::{
-    air::{BaseAirBuilder, MachineAir, SP1AirBuilder},
+    air::{BaseAirBuilder, Column, ColumnDescriptor, ColumnLayout, MachineAir, SP1AirBuilder},
     Word,
 };
 
@@ -101,6 +101,10 @@ impl<F: PrimeField32> MachineAir<F> for LtChip {
         "Lt".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(LtCols::<Column>::layout())
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
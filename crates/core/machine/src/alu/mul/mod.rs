@@ -45,7 +45,10 @@ use sp1_core_executor::{
 };
 use sp1_derive::AlignedBorrow;
 use sp1_primitives::consts::WORD_SIZE;
-use sp1_stark::{air::MachineAir, MachineRecord, Word};
+use sp1_stark::{
+    air::{Column, ColumnDescriptor, ColumnLayout, MachineAir},
+    MachineRecord, Word,
+};
 
 use crate::{air::SP1CoreAirBuilder, alu::mul::utils::get_msb, utils::pad_rows_fixed};
 
@@ -128,6 +131,10 @@ impl<F: PrimeField> MachineAir<F> for MulChip {
         "Mul".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(MulCols::<Column>::layout())
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -47,7 +47,10 @@ use sp1_core_executor::{
 };
 use sp1_derive::AlignedBorrow;
 use sp1_primitives::consts::WORD_SIZE;
-use sp1_stark::{air::MachineAir, Word};
+use sp1_stark::{
+    air::{Column, ColumnDescriptor, ColumnLayout, MachineAir},
+    Word,
+};
 
 use crate::{air::SP1CoreAirBuilder, utils::pad_rows_fixed};
 
@@ -110,6 +113,10 @@ impl<F: PrimeField> MachineAir<F> for ShiftLeft {
         "ShiftLeft".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(ShiftLeftCols::<Column>::layout())
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
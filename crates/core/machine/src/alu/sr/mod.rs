@@ -59,7 +59,10 @@ use sp1_core_executor::{
 };
 use sp1_derive::AlignedBorrow;
 use sp1_primitives::consts::WORD_SIZE;
-use sp1_stark::{air::MachineAir, Word};
+use sp1_stark::{
+    air::{Column, ColumnDescriptor, ColumnLayout, MachineAir},
+    Word,
+};
 
 use crate::{
     air::SP1CoreAirBuilder,
@@ -143,6 +146,10 @@ impl<F: PrimeField> MachineAir<F> for ShiftRightChip {
         "ShiftRight".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(ShiftRightCols::<Column>::layout())
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -3,7 +3,7 @@ use std::borrow::BorrowMut;
 use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
 use sp1_core_executor::{ByteOpcode, ExecutionRecord, Program};
-use sp1_stark::air::MachineAir;
+use sp1_stark::air::{Column, ColumnDescriptor, ColumnLayout, MachineAir};
 
 use crate::utils::zeroed_f_vec;
 
@@ -23,6 +23,10 @@ impl<F: Field> MachineAir<F> for ByteChip<F> {
         "Byte".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(ByteMultCols::<Column>::layout())
+    }
+
     fn preprocessed_width(&self) -> usize {
         NUM_BYTE_PREPROCESSED_COLS
     }
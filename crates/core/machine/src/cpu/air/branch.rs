@@ -92,22 +92,22 @@ impl CpuChip {
                 local.branching,
             );
 
-            // When we are not branching, assert that local.pc + 4 <==> next.pc.
+            // When we are not branching, assert that local.pc + instruction size <==> next.pc.
             builder
                 .when_transition()
                 .when(next.is_real)
                 .when(local.not_branching)
-                .assert_eq(local.pc + AB::Expr::from_canonical_u8(4), next.pc);
+                .assert_eq(local.pc + self.instruction_size::<AB>(local), next.pc);
 
             // When local.not_branching is true, assert that local.is_real is true.
             builder.when(local.not_branching).assert_one(local.is_real);
 
-            // When the last row is real and local.not_branching, assert that local.pc + 4 <==>
-            // local.next_pc.
+            // When the last row is real and local.not_branching, assert that local.pc +
+            // instruction size <==> local.next_pc.
             builder
                 .when(local.is_real)
                 .when(local.not_branching)
-                .assert_eq(local.pc + AB::Expr::from_canonical_u8(4), local.next_pc);
+                .assert_eq(local.pc + self.instruction_size::<AB>(local), local.next_pc);
 
             // Assert that either we are branching or not branching when the instruction is a
             // branch.
@@ -60,12 +60,16 @@ pub struct OpcodeSelectorCols<T> {
     /// Miscellaneous.
     pub is_auipc: T,
     pub is_unimpl: T,
+
+    /// Whether the instruction is compressed, so that the pc advances by 2 instead of 4.
+    pub is_compressed: T,
 }
 
 impl<F: PrimeField> OpcodeSelectorCols<F> {
     pub fn populate(&mut self, instruction: Instruction) {
         self.imm_b = F::from_bool(instruction.imm_b);
         self.imm_c = F::from_bool(instruction.imm_c);
+        self.is_compressed = F::from_bool(instruction.compressed);
 
         if instruction.is_alu_instruction() {
             self.is_alu = F::one();
@@ -133,6 +137,7 @@ impl<T> IntoIterator for OpcodeSelectorCols<T> {
             self.is_jal,
             self.is_auipc,
             self.is_unimpl,
+            self.is_compressed,
         ];
         assert_eq!(columns.len(), NUM_OPCODE_SELECTOR_COLS);
         columns.into_iter()
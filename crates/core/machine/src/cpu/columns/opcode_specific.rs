@@ -1,4 +1,5 @@
 use crate::cpu::columns::{AuipcCols, BranchCols, JumpCols, MemoryColumns};
+use sp1_stark::air::{column_path, Column, ColumnDescriptor, ColumnLayout};
 use std::{
     fmt::{Debug, Formatter},
     mem::{size_of, transmute},
@@ -71,3 +72,31 @@ impl<T: Copy> OpcodeSpecificCols<T> {
         unsafe { &mut self.ecall }
     }
 }
+
+/// Each column is named after every view that uses it, e.g. `memory.addr_word[0]|branch.a_gt_b`.
+impl ColumnLayout for OpcodeSpecificCols<Column> {
+    fn layout_at(path: &str, offset: usize) -> ColumnDescriptor {
+        let views = [
+            MemoryColumns::<Column>::column_names(),
+            BranchCols::<Column>::column_names(),
+            JumpCols::<Column>::column_names(),
+            AuipcCols::<Column>::column_names(),
+            EcallCols::<Column>::column_names(),
+        ];
+        let prefixes = ["memory", "branch", "jump", "auipc", "ecall"];
+        let columns = (0..NUM_OPCODE_SPECIFIC_COLS)
+            .map(|i| {
+                let name = views
+                    .iter()
+                    .zip(prefixes)
+                    .filter_map(|(view, prefix)| {
+                        view.get(i).map(|field| column_path(prefix, field))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("|");
+                Column::layout_at(&column_path(path, &name), offset + i)
+            })
+            .collect();
+        ColumnDescriptor::with_children(path, offset, columns)
+    }
+}
@@ -8,7 +8,10 @@ use sp1_core_executor::{
     Register::X0,
 };
 use sp1_primitives::consts::WORD_SIZE;
-use sp1_stark::{air::MachineAir, Word};
+use sp1_stark::{
+    air::{Column, ColumnDescriptor, ColumnLayout, MachineAir},
+    Word,
+};
 use std::{array, borrow::BorrowMut};
 
 use p3_field::{PrimeField, PrimeField32};
@@ -33,6 +36,10 @@ impl<F: PrimeField32> MachineAir<F> for CpuChip {
         "CPU".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(CpuCols::<Column>::layout())
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
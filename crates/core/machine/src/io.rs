@@ -1,5 +1,7 @@
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sp1_core_executor::SP1ReduceProof;
+use sp1_primitives::consts::input_regions_layout;
 use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkVerifyingKey};
 
 /// Standard input for the prover.
@@ -10,17 +12,20 @@ pub struct SP1Stdin {
     pub buffer: Vec<Vec<u8>>,
     pub ptr: usize,
     pub proofs: Vec<(SP1ReduceProof<BabyBearPoseidon2>, StarkVerifyingKey<BabyBearPoseidon2>)>,
+    /// Read-only blobs mapped into the memory of the program before it starts, whose digests the
+    /// program commits to when it gets them.
+    pub regions: Vec<Vec<u8>>,
 }
 
 impl SP1Stdin {
     /// Create a new `SP1Stdin`.
     pub const fn new() -> Self {
-        Self { buffer: Vec::new(), ptr: 0, proofs: Vec::new() }
+        Self { buffer: Vec::new(), ptr: 0, proofs: Vec::new(), regions: Vec::new() }
     }
 
     /// Create a `SP1Stdin` from a slice of bytes.
     pub fn from(data: &[u8]) -> Self {
-        Self { buffer: vec![data.to_vec()], ptr: 0, proofs: Vec::new() }
+        Self { buffer: vec![data.to_vec()], ptr: 0, proofs: Vec::new(), regions: Vec::new() }
     }
 
     /// Read a value from the buffer.
@@ -53,6 +58,25 @@ impl SP1Stdin {
         self.buffer.push(vec);
     }
 
+    /// Write a read-only blob that the program gets with `sp1_zkvm::io::input_region(index)`,
+    /// where `index` is the number of regions written before it, returning its SHA-256 digest.
+    ///
+    /// The region is mapped into the memory of the program before it starts instead of being
+    /// copied in like the input stream. The program commits the digest of the region to its own
+    /// channel of public values the first time it gets the region, so the verifier must check
+    /// `SP1PublicValues::input_region_digest(index)` against the returned digest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regions don't fit in memory.
+    pub fn write_region(&mut self, data: Vec<u8>) -> [u8; 32] {
+        let digest = Sha256::digest(&data).into();
+        self.regions.push(data);
+        let lens = self.regions.iter().map(Vec::len).collect::<Vec<_>>();
+        assert!(input_regions_layout(&lens).is_some(), "the input regions don't fit in memory");
+        digest
+    }
+
     pub fn write_proof(
         &mut self,
         proof: SP1ReduceProof<BabyBearPoseidon2>,
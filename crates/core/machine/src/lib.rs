@@ -30,7 +30,7 @@ pub mod utils;
 /// This string should be updated whenever any step in verifying an SP1 proof changes, including
 /// core, recursion, and plonk-bn254. This string is used to download SP1 artifacts and the gnark
 /// docker image.
-pub const SP1_CIRCUIT_VERSION: &str = "v3.0.0-rc3-scroll";
+pub const SP1_CIRCUIT_VERSION: &str = "v3.0.0-rc5-scroll";
 
 // Re-export the `SP1ReduceProof` struct from sp1_core_machine.
 //
@@ -11,8 +11,8 @@ use sp1_core_executor::{events::MemoryInitializeFinalizeEvent, ExecutionRecord,
 use sp1_derive::AlignedBorrow;
 use sp1_stark::{
     air::{
-        AirInteraction, BaseAirBuilder, InteractionScope, MachineAir, PublicValues, SP1AirBuilder,
-        SP1_PROOF_NUM_PV_ELTS,
+        AirInteraction, BaseAirBuilder, Column, ColumnDescriptor, ColumnLayout, InteractionScope,
+        MachineAir, PublicValues, SP1AirBuilder, SP1_PROOF_NUM_PV_ELTS,
     },
     InteractionKind, Word,
 };
@@ -54,6 +54,10 @@ impl<F: PrimeField32> MachineAir<F> for MemoryGlobalChip {
         }
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(MemoryInitCols::<Column>::layout())
+    }
+
     fn generate_dependencies(&self, _input: &ExecutionRecord, _output: &mut ExecutionRecord) {
         // Do nothing since this chip has no dependencies.
     }
@@ -11,7 +11,10 @@ use p3_matrix::{dense::RowMajorMatrix, Matrix};
 use sp1_core_executor::{ExecutionRecord, Program};
 use sp1_derive::AlignedBorrow;
 use sp1_stark::{
-    air::{AirInteraction, InteractionScope, MachineAir, SP1AirBuilder},
+    air::{
+        AirInteraction, Column, ColumnDescriptor, ColumnLayout, InteractionScope, MachineAir,
+        SP1AirBuilder,
+    },
     InteractionKind, Word,
 };
 
@@ -77,6 +80,10 @@ impl<F: PrimeField32> MachineAir<F> for MemoryLocalChip {
         "MemoryLocal".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(MemoryLocalCols::<Column>::layout())
+    }
+
     fn generate_dependencies(&self, _input: &ExecutionRecord, _output: &mut ExecutionRecord) {
         // Do nothing since this chip has no dependencies.
     }
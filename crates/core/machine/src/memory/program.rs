@@ -11,8 +11,8 @@ use sp1_core_executor::{ExecutionRecord, Program};
 use sp1_derive::AlignedBorrow;
 use sp1_stark::{
     air::{
-        AirInteraction, InteractionScope, MachineAir, PublicValues, SP1AirBuilder,
-        SP1_PROOF_NUM_PV_ELTS,
+        AirInteraction, Column, ColumnDescriptor, ColumnLayout, InteractionScope, MachineAir,
+        PublicValues, SP1AirBuilder, SP1_PROOF_NUM_PV_ELTS,
     },
     InteractionKind, Word,
 };
@@ -66,6 +66,10 @@ impl<F: PrimeField> MachineAir<F> for MemoryProgramChip {
         "MemoryProgram".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(MemoryProgramMultCols::<Column>::layout())
+    }
+
     fn preprocessed_width(&self) -> usize {
         NUM_MEMORY_PROGRAM_PREPROCESSED_COLS
     }
@@ -10,7 +10,7 @@ use p3_field::PrimeField;
 use p3_matrix::{dense::RowMajorMatrix, Matrix};
 use sp1_core_executor::{ExecutionRecord, Program};
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::{MachineAir, SP1AirBuilder};
+use sp1_stark::air::{Column, ColumnDescriptor, ColumnLayout, MachineAir, SP1AirBuilder};
 
 use crate::cpu::columns::{InstructionCols, OpcodeSelectorCols};
 
@@ -56,6 +56,10 @@ impl<F: PrimeField> MachineAir<F> for ProgramChip {
         "Program".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(ProgramMultiplicityCols::<Column>::layout())
+    }
+
     fn preprocessed_width(&self) -> usize {
         NUM_PROGRAM_PREPROCESSED_COLS
     }
@@ -65,9 +69,8 @@ impl<F: PrimeField> MachineAir<F> for ProgramChip {
         let mut rows = program
             .instructions
             .iter()
-            .enumerate()
-            .map(|(i, &instruction)| {
-                let pc = program.pc_base + (i as u32 * 4);
+            .zip(program.pcs())
+            .map(|(&instruction, pc)| {
                 let mut row = [F::zero(); NUM_PROGRAM_PREPROCESSED_COLS];
                 let cols: &mut ProgramPreprocessedCols<F> = row.as_mut_slice().borrow_mut();
                 cols.pc = F::from_canonical_u32(pc);
@@ -115,12 +118,8 @@ impl<F: PrimeField> MachineAir<F> for ProgramChip {
 
         let mut rows = input
             .program
-            .instructions
-            .clone()
-            .into_iter()
-            .enumerate()
-            .map(|(i, _)| {
-                let pc = input.program.pc_base + (i as u32 * 4);
+            .pcs()
+            .map(|pc| {
                 let mut row = [F::zero(); NUM_PROGRAM_MULT_COLS];
                 let cols: &mut ProgramMultiplicityCols<F> = row.as_mut_slice().borrow_mut();
                 cols.shard = F::from_canonical_u32(input.public_values.execution_shard);
@@ -180,7 +179,6 @@ mod tests {
 
     use std::sync::Arc;
 
-    use hashbrown::HashMap;
     use p3_baby_bear::BabyBear;
 
     use p3_matrix::dense::RowMajorMatrix;
@@ -201,13 +199,7 @@ mod tests {
             Instruction::new(Opcode::ADD, 31, 30, 29, false, false),
         ];
         let shard = ExecutionRecord {
-            program: Arc::new(Program {
-                instructions,
-                pc_start: 0,
-                pc_base: 0,
-                memory_image: HashMap::new(),
-                preprocessed_shape: None,
-            }),
+            program: Arc::new(Program::new(instructions, 0, 0)),
             ..Default::default()
         };
         let chip = ProgramChip::new();
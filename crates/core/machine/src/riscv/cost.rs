@@ -190,6 +190,15 @@ impl CostEstimator for ExecutionReport {
         total_area += (lt_events as u64) * costs[&RiscvAirDiscriminants::Lt];
         total_chips += 1;
 
+        let bitmanip_events = self
+            .opcode_counts
+            .iter()
+            .filter(|(opcode, _)| opcode.is_bitmanip())
+            .map(|(_, count)| *count)
+            .sum::<u64>();
+        total_area += bitmanip_events * costs[&RiscvAirDiscriminants::BitManip];
+        total_chips += 1;
+
         let memory_global_initialize_events = self.touched_memory_addresses;
         total_area += (memory_global_initialize_events as u64)
             * costs[&RiscvAirDiscriminants::MemoryGlobalInit];
@@ -0,0 +1,516 @@
+//! Differential fuzzing of the executor against a reference RV32IM model and the RISC-V AIR.
+//!
+//! Random straight-line programs are executed by the [`Executor`] and by [`Reference`], and their
+//! final registers and memory are compared. The programs are then proven, which checks every
+//! constraint of the shard when the `debug` feature is enabled, and cells of the traces of the ALU
+//! and memory chips are mutated to check that the AIR rejects them.
+//!
+//! The seed and the number of programs can be set with `FUZZ_SEED` and `FUZZ_ITERATIONS`.
+
+use hashbrown::HashMap;
+use p3_baby_bear::BabyBear;
+use p3_field::{AbstractExtensionField, AbstractField, Field, PrimeField32};
+use p3_matrix::{dense::RowMajorMatrix, Matrix};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use sp1_core_executor::{ExecutionRecord, Executor, Instruction, Opcode, Program};
+use sp1_stark::{
+    air::MachineAir, baby_bear_poseidon2::BabyBearPoseidon2, debug_constraints, Challenge,
+    CpuProver, MachineRecord, SP1CoreOpts,
+};
+
+use super::RiscvAir;
+use crate::utils::{run_test, setup_logger};
+
+/// The register holding the base address of the memory the programs load from and store to.
+const BASE_REGISTER: u32 = 2;
+
+/// The base address of the memory the programs load from and store to.
+const BASE: u32 = 0x10000;
+
+/// The size in bytes of the memory the programs load from and store to.
+const MEMORY_SIZE: u32 = 256;
+
+/// The number of random instructions in a program, besides one of every opcode.
+const PROGRAM_LEN: usize = 64;
+
+const ALU_OPCODES: [Opcode; 38] = [
+    Opcode::ADD,
+    Opcode::SUB,
+    Opcode::XOR,
+    Opcode::OR,
+    Opcode::AND,
+    Opcode::SLL,
+    Opcode::SRL,
+    Opcode::SRA,
+    Opcode::SLT,
+    Opcode::SLTU,
+    Opcode::MUL,
+    Opcode::MULH,
+    Opcode::MULHU,
+    Opcode::MULHSU,
+    Opcode::DIV,
+    Opcode::DIVU,
+    Opcode::REM,
+    Opcode::REMU,
+    Opcode::ANDN,
+    Opcode::ORN,
+    Opcode::XNOR,
+    Opcode::CLZ,
+    Opcode::CTZ,
+    Opcode::CPOP,
+    Opcode::MAX,
+    Opcode::MAXU,
+    Opcode::MIN,
+    Opcode::MINU,
+    Opcode::SEXTB,
+    Opcode::SEXTH,
+    Opcode::ZEXTH,
+    Opcode::ROL,
+    Opcode::ROR,
+    Opcode::ORCB,
+    Opcode::REV8,
+    Opcode::SH1ADD,
+    Opcode::SH2ADD,
+    Opcode::SH3ADD,
+];
+
+const MEMORY_OPCODES: [Opcode; 8] = [
+    Opcode::LB,
+    Opcode::LH,
+    Opcode::LW,
+    Opcode::LBU,
+    Opcode::LHU,
+    Opcode::SB,
+    Opcode::SH,
+    Opcode::SW,
+];
+
+fn seed() -> u64 {
+    std::env::var("FUZZ_SEED").map_or(0x5eed, |seed| seed.parse().expect("invalid FUZZ_SEED"))
+}
+
+fn iterations() -> usize {
+    std::env::var("FUZZ_ITERATIONS").map_or(2, |n| n.parse().expect("invalid FUZZ_ITERATIONS"))
+}
+
+/// A random word, biased towards the values at the edges of signed and unsigned arithmetic.
+fn random_value(rng: &mut StdRng) -> u32 {
+    match rng.gen_range(0..8) {
+        0 => 0,
+        1 => 1,
+        2 => u32::MAX,
+        3 => i32::MIN as u32,
+        4 => i32::MAX as u32,
+        5 => rng.gen_range(0..32),
+        _ => rng.gen(),
+    }
+}
+
+/// A random instruction with the given opcode that doesn't write to the base register.
+fn random_instruction(rng: &mut StdRng, opcode: Opcode) -> Instruction {
+    let rd = rng.gen_range(BASE_REGISTER + 1..32);
+    let width = match opcode {
+        Opcode::LB | Opcode::LBU | Opcode::SB => 1,
+        Opcode::LH | Opcode::LHU | Opcode::SH => 2,
+        Opcode::LW | Opcode::SW => 4,
+        _ => {
+            let rs1 = rng.gen_range(0..32);
+            return if rng.gen_bool(0.5) {
+                Instruction::new(opcode, rd, rs1, rng.gen_range(0..32), false, false)
+            } else {
+                Instruction::new(opcode, rd, rs1, random_value(rng), false, true)
+            };
+        }
+    };
+    let offset = rng.gen_range(0..MEMORY_SIZE / width) * width;
+    if matches!(opcode, Opcode::SB | Opcode::SH | Opcode::SW) {
+        // The first operand of a store is the register holding the value to store.
+        Instruction::new(opcode, rng.gen_range(0..32), BASE_REGISTER, offset, false, true)
+    } else {
+        Instruction::new(opcode, rd, BASE_REGISTER, offset, false, true)
+    }
+}
+
+/// A random straight-line program that uses every ALU and memory opcode at least once, starting
+/// from random registers and memory.
+fn random_program(rng: &mut StdRng) -> Program {
+    let mut body = ALU_OPCODES
+        .iter()
+        .chain(&MEMORY_OPCODES)
+        .map(|&opcode| random_instruction(rng, opcode))
+        .collect::<Vec<_>>();
+    let opcodes = ALU_OPCODES.iter().chain(&MEMORY_OPCODES).copied().collect::<Vec<_>>();
+    for _ in 0..PROGRAM_LEN {
+        let opcode = *opcodes.choose(rng).unwrap();
+        body.push(random_instruction(rng, opcode));
+    }
+    body.shuffle(rng);
+
+    let mut instructions = vec![Instruction::new(Opcode::ADD, BASE_REGISTER, 0, BASE, false, true)];
+    for register in BASE_REGISTER + 1..32 {
+        instructions.push(Instruction::new(
+            Opcode::ADD,
+            register,
+            0,
+            random_value(rng),
+            false,
+            true,
+        ));
+    }
+    instructions.extend(body);
+
+    let mut program = Program::new(instructions, 0, 0);
+    program.memory_image =
+        (0..MEMORY_SIZE).step_by(4).map(|offset| (BASE + offset, rng.gen())).collect();
+    program
+}
+
+/// A reference model of RV32IM with Zba and Zbb for straight-line programs, written independently
+/// of the executor.
+struct Reference {
+    registers: [u32; 32],
+    memory: HashMap<u32, u32>,
+}
+
+impl Reference {
+    fn new(program: &Program) -> Self {
+        Self { registers: [0; 32], memory: program.memory_image.clone() }
+    }
+
+    fn run(&mut self, program: &Program) {
+        for instruction in &program.instructions {
+            self.execute(instruction);
+        }
+    }
+
+    fn execute(&mut self, instruction: &Instruction) {
+        let b = self.registers[instruction.op_b as usize];
+        let c = if instruction.imm_c {
+            instruction.op_c
+        } else {
+            self.registers[instruction.op_c as usize]
+        };
+        let (signed_b, signed_c) = (b as i32, c as i32);
+        let addr = b.wrapping_add(c);
+        let word = self.memory.get(&(addr & !3)).copied().unwrap_or(0);
+        let shift = (addr & 3) * 8;
+
+        let value = match instruction.opcode {
+            Opcode::ADD => b.wrapping_add(c),
+            Opcode::SUB => b.wrapping_sub(c),
+            Opcode::XOR => b ^ c,
+            Opcode::OR => b | c,
+            Opcode::AND => b & c,
+            Opcode::SLL => b << (c & 31),
+            Opcode::SRL => b >> (c & 31),
+            Opcode::SRA => (signed_b >> (c & 31)) as u32,
+            Opcode::SLT => u32::from(signed_b < signed_c),
+            Opcode::SLTU => u32::from(b < c),
+            Opcode::MUL => b.wrapping_mul(c),
+            Opcode::MULH => ((i64::from(signed_b) * i64::from(signed_c)) >> 32) as u32,
+            Opcode::MULHU => ((u64::from(b) * u64::from(c)) >> 32) as u32,
+            Opcode::MULHSU => ((i64::from(signed_b) * i64::from(c)) >> 32) as u32,
+            Opcode::DIV if c == 0 => u32::MAX,
+            Opcode::DIV => signed_b.wrapping_div(signed_c) as u32,
+            Opcode::DIVU if c == 0 => u32::MAX,
+            Opcode::DIVU => b / c,
+            Opcode::REM if c == 0 => b,
+            Opcode::REM => signed_b.wrapping_rem(signed_c) as u32,
+            Opcode::REMU if c == 0 => b,
+            Opcode::REMU => b % c,
+            Opcode::ANDN => b & !c,
+            Opcode::ORN => b | !c,
+            Opcode::XNOR => !(b ^ c),
+            Opcode::CLZ => b.leading_zeros(),
+            Opcode::CTZ => b.trailing_zeros(),
+            Opcode::CPOP => b.count_ones(),
+            Opcode::MAX => signed_b.max(signed_c) as u32,
+            Opcode::MAXU => b.max(c),
+            Opcode::MIN => signed_b.min(signed_c) as u32,
+            Opcode::MINU => b.min(c),
+            Opcode::SEXTB => b as i8 as u32,
+            Opcode::SEXTH => b as i16 as u32,
+            Opcode::ZEXTH => b & 0xffff,
+            Opcode::ROL => b.rotate_left(c & 31),
+            Opcode::ROR => b.rotate_right(c & 31),
+            Opcode::ORCB => {
+                u32::from_le_bytes(b.to_le_bytes().map(|byte| if byte == 0 { 0 } else { 0xff }))
+            }
+            Opcode::REV8 => b.swap_bytes(),
+            Opcode::SH1ADD => (b << 1).wrapping_add(c),
+            Opcode::SH2ADD => (b << 2).wrapping_add(c),
+            Opcode::SH3ADD => (b << 3).wrapping_add(c),
+            Opcode::LB => (word >> shift) as u8 as i8 as u32,
+            Opcode::LH => (word >> shift) as u16 as i16 as u32,
+            Opcode::LW => word,
+            Opcode::LBU => (word >> shift) as u8 as u32,
+            Opcode::LHU => (word >> shift) as u16 as u32,
+            Opcode::SB | Opcode::SH | Opcode::SW => {
+                let mask = match instruction.opcode {
+                    Opcode::SB => 0xff,
+                    Opcode::SH => 0xffff,
+                    _ => u32::MAX,
+                };
+                let value = self.registers[instruction.op_a as usize];
+                let stored = (word & !(mask << shift)) | ((value & mask) << shift);
+                self.memory.insert(addr & !3, stored);
+                return;
+            }
+            opcode => panic!("the reference model doesn't support {opcode}"),
+        };
+        if instruction.op_a != 0 {
+            self.registers[instruction.op_a as usize] = value;
+        }
+    }
+}
+
+/// Execute a program with the executor and check its final state against the reference model.
+fn execute_and_compare(program: &Program) -> Executor<'static> {
+    let mut reference = Reference::new(program);
+    reference.run(program);
+
+    let mut runtime = Executor::new(program.clone(), SP1CoreOpts::default());
+    runtime.run().unwrap();
+    assert_eq!(runtime.registers(), reference.registers, "registers differ from the reference");
+    for addr in (BASE..BASE + MEMORY_SIZE).step_by(4) {
+        let expected = reference.memory.get(&addr).copied().unwrap_or(0);
+        assert_eq!(runtime.word(addr), expected, "memory at {addr:#x} differs from the reference");
+    }
+    runtime
+}
+
+#[test]
+fn executor_matches_reference() {
+    let mut rng = StdRng::seed_from_u64(seed());
+    for _ in 0..iterations() * 8 {
+        execute_and_compare(&random_program(&mut rng));
+    }
+}
+
+#[test]
+fn random_programs_prove() {
+    setup_logger();
+    let mut rng = StdRng::seed_from_u64(seed());
+    for _ in 0..iterations() {
+        let program = random_program(&mut rng);
+        execute_and_compare(&program);
+        run_test::<CpuProver<_, _>>(program).unwrap();
+    }
+}
+
+/// Add a random nonzero value to cells of the trace of a chip, one at a time, and check that each
+/// change either violates a constraint of the chip or unbalances the interactions of the shard.
+///
+/// The cumulative sums of every other chip of the shard are computed from their unmutated traces,
+/// so a change is only accepted if the interactions of the mutated chip still cancel against them,
+/// as the verifier requires. Many columns, like the operands of the ALU chips, are only bound by
+/// interactions with the byte lookups and the CPU, so a constraint violation alone is not enough.
+///
+/// `rows` selects the rows of the shard to mutate and `columns` the names of the columns.
+fn assert_mutations_rejected(
+    chip_name: &str,
+    rows: impl Fn(&ExecutionRecord) -> Vec<usize>,
+    columns: impl Fn(&str) -> bool,
+) {
+    let mut rng = StdRng::seed_from_u64(seed());
+    let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+    let chip = machine.chips().iter().find(|chip| chip.name() == chip_name).unwrap();
+    let columns = chip
+        .column_names()
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| columns(name))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    assert!(!columns.is_empty(), "no column of {chip_name} selected");
+
+    for _ in 0..iterations() {
+        let mut runtime = execute_and_compare(&random_program(&mut rng));
+        runtime.records.iter_mut().enumerate().for_each(|(i, shard)| {
+            shard.public_values.shard = (i + 1) as u32;
+        });
+        machine.generate_dependencies(&mut runtime.records, &SP1CoreOpts::default(), None);
+        let record = &runtime.records[0];
+        let rows = rows(record);
+        assert!(!rows.is_empty(), "no row of {chip_name} selected");
+
+        let trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(record, &mut ExecutionRecord::default());
+        let public_values = record.public_values::<BabyBear>();
+        let challenges = (0..4)
+            .map(|_| {
+                Challenge::<BabyBearPoseidon2>::from_base_fn(|_| {
+                    BabyBear::from_canonical_u32(rng.gen_range(0..BabyBear::ORDER_U32))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // The cumulative sums of the other chips of the shard, which cancel the ones of the chip
+        // exactly when the interactions of the shard balance.
+        let (other_global_sum, other_local_sum) = machine
+            .chips()
+            .iter()
+            .filter(|other| other.name() != chip_name && other.included(record))
+            .map(|other| {
+                let preprocessed = other.generate_preprocessed_trace(&record.program);
+                let main = other.generate_trace(record, &mut ExecutionRecord::default());
+                let (_, global_sum, local_sum) =
+                    other.generate_permutation_trace(preprocessed.as_ref(), &main, &challenges);
+                (global_sum, local_sum)
+            })
+            .fold(
+                (Challenge::<BabyBearPoseidon2>::zero(), Challenge::<BabyBearPoseidon2>::zero()),
+                |(g, l), (global_sum, local_sum)| (g + global_sum, l + local_sum),
+            );
+
+        let check = |main: &RowMajorMatrix<BabyBear>| {
+            let (perm, global_sum, local_sum) =
+                chip.generate_permutation_trace(None, main, &challenges);
+            let violations = debug_constraints::<BabyBearPoseidon2, _>(
+                chip,
+                None,
+                main,
+                &perm,
+                &challenges,
+                &public_values,
+                &[global_sum, local_sum],
+            );
+            let balanced = (global_sum + other_global_sum).is_zero()
+                && (local_sum + other_local_sum).is_zero();
+            (violations, balanced)
+        };
+
+        let (violations, balanced) = check(&trace);
+        assert!(violations.is_empty(), "the unmutated {chip_name} trace is rejected");
+        assert!(balanced, "the interactions of the unmutated shard do not balance");
+
+        for &row in &rows {
+            for &column in &columns {
+                let mut mutated = trace.clone();
+                let delta = rng.gen_range(1..BabyBear::ORDER_U32);
+                mutated.values[row * trace.width() + column] += BabyBear::from_canonical_u32(delta);
+                let (violations, balanced) = check(&mutated);
+                assert!(
+                    !violations.is_empty() || !balanced,
+                    "adding {delta} to {} in row {row} of {chip_name} is not rejected",
+                    chip.column_names()[column],
+                );
+            }
+        }
+    }
+}
+
+/// The rows of the first few events of a chip, whose trace has a row per event.
+fn first_rows(events: usize) -> Vec<usize> {
+    (0..events.min(4)).collect()
+}
+
+/// Select the operand words of an ALU chip and the given opcode selectors.
+///
+/// Other columns are left alone, since some of them are only constrained for some operands, such
+/// as the inverse in `is_c_0` of the `DivRem` chip.
+fn operands_and(selectors: &'static [&'static str]) -> impl Fn(&str) -> bool {
+    move |name| {
+        ["a[", "b[", "c["].iter().any(|prefix| name.starts_with(prefix))
+            || selectors.contains(&name)
+    }
+}
+
+#[test]
+fn mutated_add_sub_trace_is_rejected() {
+    assert_mutations_rejected(
+        "AddSub",
+        |record| first_rows(record.add_events.len() + record.sub_events.len()),
+        |name| {
+            ["add_operation.value[", "operand_1[", "operand_2[", "is_add", "is_sub"]
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        },
+    );
+}
+
+#[test]
+fn mutated_lt_trace_is_rejected() {
+    assert_mutations_rejected(
+        "Lt",
+        |record| first_rows(record.lt_events.len()),
+        operands_and(&["is_slt", "is_sltu"]),
+    );
+}
+
+#[test]
+fn mutated_shift_left_trace_is_rejected() {
+    assert_mutations_rejected(
+        "ShiftLeft",
+        |record| first_rows(record.shift_left_events.len()),
+        operands_and(&["is_real"]),
+    );
+}
+
+#[test]
+fn mutated_shift_right_trace_is_rejected() {
+    assert_mutations_rejected(
+        "ShiftRight",
+        |record| first_rows(record.shift_right_events.len()),
+        operands_and(&["is_srl", "is_sra"]),
+    );
+}
+
+#[test]
+fn mutated_divrem_trace_is_rejected() {
+    assert_mutations_rejected(
+        "DivRem",
+        |record| first_rows(record.divrem_events.len()),
+        operands_and(&["is_div", "is_divu", "is_rem", "is_remu"]),
+    );
+}
+
+#[test]
+fn mutated_bitmanip_trace_is_rejected() {
+    assert_mutations_rejected(
+        "BitManip",
+        |record| first_rows(record.bitmanip_events.len()),
+        operands_and(&["is_andn", "is_clz", "is_max", "is_rol", "is_sh1add"]),
+    );
+}
+
+#[test]
+fn mutated_mul_trace_is_rejected() {
+    assert_mutations_rejected(
+        "Mul",
+        |record| first_rows(record.mul_events.len()),
+        operands_and(&["is_mul", "is_mulh", "is_mulhu", "is_mulhsu"]),
+    );
+}
+
+#[test]
+fn mutated_cpu_memory_access_is_rejected() {
+    assert_mutations_rejected(
+        "CPU",
+        |record| {
+            let rows = record.cpu_events.iter().enumerate();
+            rows.filter(|(_, event)| event.instruction.is_memory_instruction())
+                .map(|(row, _)| row)
+                .take(4)
+                .collect()
+        },
+        |name| {
+            name.starts_with("op_a_access.access.value[")
+                || name.contains("memory.memory_access.access.value[")
+                || name.contains("memory.addr_word[")
+        },
+    );
+}
+
+#[test]
+fn mutated_memory_local_trace_is_rejected() {
+    assert_mutations_rejected(
+        "MemoryLocal",
+        |_| vec![0],
+        |name| {
+            ["addr", "initial_value[", "final_value["]
+                .iter()
+                .any(|field| name.starts_with(&format!("memory_local_entries[0].{field}")))
+        },
+    );
+}
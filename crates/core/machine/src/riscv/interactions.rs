@@ -0,0 +1,188 @@
+use p3_field::PrimeField32;
+use sp1_core_executor::{events::AluEvent, ByteOpcode, ExecutionRecord};
+use sp1_stark::{
+    air::InteractionScope, interaction_report, InteractionKind, InteractionReport,
+    StarkGenericConfig, StarkMachine, StarkProvingKey,
+};
+
+use super::RiscvAir;
+
+/// Find the messages that don't balance across the chips of the RISC-V machine, and name the
+/// event of the record each of them originates from.
+///
+/// For the local scope, exactly one shard must be given.
+pub fn riscv_interaction_report<SC: StarkGenericConfig>(
+    machine: &StarkMachine<SC, RiscvAir<SC::Val>>,
+    pk: &StarkProvingKey<SC>,
+    shards: &[ExecutionRecord],
+    interaction_kinds: Vec<InteractionKind>,
+    scope: InteractionScope,
+) -> InteractionReport
+where
+    SC::Val: PrimeField32,
+{
+    let mut report = interaction_report(machine, pk, shards, interaction_kinds, scope);
+    report.describe_origins(|imbalance| {
+        let site = imbalance.sites().next()?;
+        describe_interaction(&shards[site.shard], imbalance.kind, &imbalance.values)
+    });
+    report
+}
+
+/// Describe the event of a record that a message of the given kind originates from.
+///
+/// Returns `None` for kinds whose messages don't correspond to a single event.
+pub fn describe_interaction(
+    record: &ExecutionRecord,
+    kind: InteractionKind,
+    values: &[u32],
+) -> Option<String> {
+    match kind {
+        InteractionKind::Memory => {
+            let [shard, timestamp, addr, value @ ..] = values else { return None };
+            Some(format!(
+                "MemoryRecord {{ shard: {shard}, timestamp: {timestamp}, value: {:#x} }} at \
+                 address {addr:#x}",
+                word(value)?
+            ))
+        }
+        InteractionKind::Alu => {
+            let [opcode, a @ .., shard, nonce] = values else { return None };
+            let (a, b, c) = (word(a.get(0..4)?)?, word(a.get(4..8)?)?, word(a.get(8..12)?)?);
+            let event = alu_events(record).find(|event| {
+                event.opcode as u32 == *opcode
+                    && (event.shard, event.a, event.b, event.c) == (*shard, a, b, c)
+                    && record.nonce_lookup.get(&event.lookup_id) == Some(nonce)
+            });
+            Some(match event {
+                Some(event) => format!(
+                    "AluEvent {{ lookup_id: {:?}, shard: {}, clk: {}, opcode: {}, a: {:#x}, b: \
+                     {:#x}, c: {:#x} }}",
+                    event.lookup_id, event.shard, event.clk, event.opcode, a, b, c
+                ),
+                None => format!(
+                    "an ALU operation with opcode {opcode} and nonce {nonce} in shard {shard} \
+                     that has no AluEvent"
+                ),
+            })
+        }
+        InteractionKind::Byte => {
+            let [opcode, a1, a2, b, c] = values else { return None };
+            let opcode = ByteOpcode::all().into_iter().find(|op| *op as u32 == *opcode)?;
+            Some(format!(
+                "ByteLookupEvent {{ opcode: {opcode:?}, a1: {a1}, a2: {a2}, b: {b}, c: {c} }}"
+            ))
+        }
+        InteractionKind::Syscall => {
+            let [shard, clk, nonce, syscall_id, arg1, arg2] = values else { return None };
+            let event = record.syscall_events.iter().find(|event| {
+                (event.shard, event.clk, event.nonce, event.syscall_id, event.arg1, event.arg2)
+                    == (*shard, *clk, *nonce, *syscall_id, *arg1, *arg2)
+            });
+            Some(match event {
+                Some(event) => format!("{event:?}"),
+                None => format!(
+                    "a syscall with id {syscall_id:#x} at shard {shard} clk {clk} that has no \
+                     SyscallEvent"
+                ),
+            })
+        }
+        InteractionKind::Program => {
+            let [pc, ..] = values else { return None };
+            let instruction = record.program.fetch(*pc)?;
+            Some(format!("{instruction:?} at pc {pc:#x}"))
+        }
+        InteractionKind::Instruction | InteractionKind::Range | InteractionKind::Field => None,
+    }
+}
+
+/// The ALU events of a record, across every ALU chip.
+fn alu_events(record: &ExecutionRecord) -> impl Iterator<Item = &AluEvent> {
+    record
+        .add_events
+        .iter()
+        .chain(&record.sub_events)
+        .chain(&record.mul_events)
+        .chain(&record.bitwise_events)
+        .chain(&record.shift_left_events)
+        .chain(&record.shift_right_events)
+        .chain(&record.divrem_events)
+        .chain(&record.lt_events)
+        .chain(&record.bitmanip_events)
+}
+
+/// Combine little-endian byte values into a word.
+fn word(bytes: &[u32]) -> Option<u32> {
+    let bytes: [u32; 4] = bytes.try_into().ok()?;
+    Some(bytes.iter().rev().fold(0, |acc, byte| (acc << 8) | byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_core_executor::{programs::tests::simple_program, Executor};
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, SP1CoreOpts};
+
+    use super::*;
+
+    #[test]
+    fn missing_alu_event_is_reported() {
+        let program = simple_program();
+        let mut runtime = Executor::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (pk, _) = machine.setup(&program);
+        machine.generate_dependencies(&mut runtime.records, &SP1CoreOpts::default(), None);
+
+        let mut shard = runtime.records[0].clone();
+        let report = riscv_interaction_report(
+            &machine,
+            &pk,
+            &[shard.clone()],
+            vec![InteractionKind::Alu],
+            InteractionScope::Local,
+        );
+        assert!(report.is_balanced(), "{report}");
+
+        // The CPU still sends the operation, but the AddSub chip no longer receives it.
+        let event = shard.add_events.pop().unwrap();
+        let report = riscv_interaction_report(
+            &machine,
+            &pk,
+            &[shard],
+            vec![InteractionKind::Alu],
+            InteractionScope::Local,
+        );
+        let imbalances =
+            report.imbalances_of(InteractionKind::Alu, InteractionScope::Local).collect::<Vec<_>>();
+        assert_eq!(imbalances.len(), 1, "{report}");
+        assert_eq!(imbalances[0].balance, 1);
+        assert!(imbalances[0].receivers.is_empty());
+        assert_eq!(imbalances[0].senders[0].chip, "CPU");
+        assert_eq!(imbalances[0].values[1..5], event.a.to_le_bytes().map(u32::from));
+    }
+
+    #[test]
+    fn missing_alu_event_is_in_constraint_report() {
+        let program = simple_program();
+        let mut runtime = Executor::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (pk, _) = machine.setup(&program);
+        machine.generate_dependencies(&mut runtime.records, &SP1CoreOpts::default(), None);
+
+        // Debugging the constraints reports the imbalance instead of panicking.
+        let mut shard = runtime.records[0].clone();
+        shard.add_events.pop().unwrap();
+        let report =
+            machine.debug_constraints(&pk, vec![shard], &mut machine.config().challenger());
+        assert!(!report.is_ok());
+        assert!(report.violations.is_empty(), "{report}");
+        assert!(
+            report
+                .interactions
+                .imbalances_of(InteractionKind::Alu, InteractionScope::Local)
+                .any(|imbalance| imbalance.senders.iter().any(|site| site.chip == "CPU")),
+            "{report}"
+        );
+    }
+}
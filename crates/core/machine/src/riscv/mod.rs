@@ -1,7 +1,11 @@
 pub mod cost;
 
+#[cfg(test)]
+mod fuzz;
+mod interactions;
 mod shape;
 
+pub use interactions::*;
 use itertools::Itertools;
 pub use shape::*;
 use sp1_core_executor::{
@@ -38,7 +42,10 @@ pub const MAX_NUMBER_OF_SHARDS: usize = 1 << MAX_LOG_NUMBER_OF_SHARDS;
 /// A module for importing all the different RISC-V chips.
 pub(crate) mod riscv_chips {
     pub use crate::{
-        alu::{AddSubChip, BitwiseChip, DivRemChip, LtChip, MulChip, ShiftLeft, ShiftRightChip},
+        alu::{
+            AddSubChip, BitManipChip, BitwiseChip, DivRemChip, LtChip, MulChip, ShiftLeft,
+            ShiftRightChip,
+        },
         bytes::ByteChip,
         cpu::CpuChip,
         memory::MemoryGlobalChip,
@@ -151,6 +158,8 @@ pub enum RiscvAir<F: PrimeField32> {
     Bn254ScalarMul(bn254_scalar::Bn254ScalarMulChip),
     MemCopy32(memcpy::MemCopy32Chip),
     MemCopy64(memcpy::MemCopy64Chip),
+    /// An AIR for the RISC-V Zba and Zbb bit-manipulation instructions.
+    BitManip(BitManipChip),
 }
 
 impl<F: PrimeField32> RiscvAir<F> {
@@ -368,6 +377,11 @@ impl<F: PrimeField32> RiscvAir<F> {
         costs.insert(RiscvAirDiscriminants::ByteLookup, byte.cost());
         chips.push(byte);
 
+        // Appended after the existing chips so that adding it leaves their indices unchanged.
+        let bitmanip = Chip::new(RiscvAir::BitManip(BitManipChip::default()));
+        costs.insert(RiscvAirDiscriminants::BitManip, bitmanip.cost());
+        chips.push(bitmanip);
+
         (chips, costs)
     }
 
@@ -394,6 +408,7 @@ impl<F: PrimeField32> RiscvAir<F> {
             (RiscvAir::ShiftRight(ShiftRightChip::default()), record.shift_right_events.len()),
             (RiscvAir::ShiftLeft(ShiftLeft::default()), record.shift_left_events.len()),
             (RiscvAir::Lt(LtChip::default()), record.lt_events.len()),
+            (RiscvAir::BitManip(BitManipChip::default()), record.bitmanip_events.len()),
             (
                 RiscvAir::MemoryLocal(MemoryLocalChip::new()),
                 record
@@ -416,6 +431,7 @@ impl<F: PrimeField32> RiscvAir<F> {
             RiscvAir::Lt(LtChip::default()),
             RiscvAir::ShiftLeft(ShiftLeft::default()),
             RiscvAir::ShiftRight(ShiftRightChip::default()),
+            RiscvAir::BitManip(BitManipChip::default()),
             RiscvAir::MemoryLocal(MemoryLocalChip::new()),
             RiscvAir::SyscallCore(SyscallChip::core()),
         ]
@@ -522,6 +538,7 @@ impl<F: PrimeField32> RiscvAir<F> {
             Self::Lt(_) => unreachable!("Invalid for core chip"),
             Self::ShiftRight(_) => unreachable!("Invalid for core chip"),
             Self::ShiftLeft(_) => unreachable!("Invalid for core chip"),
+            Self::BitManip(_) => unreachable!("Invalid for core chip"),
             Self::ByteLookup(_) => unreachable!("Invalid for core chip"),
             Self::SyscallCore(_) => unreachable!("Invalid for core chip"),
             Self::SyscallPrecompile(_) => unreachable!("Invalid for syscall precompile chip"),
@@ -575,15 +592,19 @@ pub mod tests {
         utils::{prove, run_test, setup_logger},
     };
 
+    use p3_air::BaseAir;
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::dense::RowMajorMatrix;
     use sp1_core_executor::{
         programs::tests::{
             fibonacci_program, simple_memory_program, simple_program, ssz_withdrawals_program,
         },
-        Instruction, Opcode, Program,
+        ExecutionRecord, Executor, Instruction, Opcode, Program,
     };
     use sp1_stark::{
-        baby_bear_poseidon2::BabyBearPoseidon2, CpuProver, SP1CoreOpts, StarkProvingKey,
-        StarkVerifyingKey,
+        air::MachineAir, baby_bear_poseidon2::BabyBearPoseidon2, CpuProver, SP1CoreOpts,
+        StarkProvingKey, StarkVerifyingKey,
     };
 
     #[test]
@@ -593,6 +614,26 @@ pub mod tests {
         run_test::<CpuProver<_, _>>(program).unwrap();
     }
 
+    #[test]
+    fn test_compressed_prove() {
+        setup_logger();
+        // main:
+        //     c.li x29, 5
+        //     addi x30, x0, 37
+        //     c.jal 6
+        //     addi x31, x0, 1
+        //     c.add x31, x30, x29
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true).into_compressed(),
+            Instruction::new(Opcode::ADD, 30, 0, 37, false, true),
+            Instruction::new(Opcode::JAL, 1, 6, 0, true, true).into_compressed(),
+            Instruction::new(Opcode::ADD, 31, 0, 1, false, true),
+            Instruction::new(Opcode::ADD, 31, 30, 29, false, false).into_compressed(),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        run_test::<CpuProver<_, _>>(program).unwrap();
+    }
+
     #[test]
     fn test_shift_prove() {
         utils::setup_logger();
@@ -789,4 +830,46 @@ pub mod tests {
         }
         assert_eq!(vk.chip_ordering, deserialized_vk.chip_ordering);
     }
+
+    #[test]
+    fn test_column_names_match_width() {
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        for chip in machine.chips() {
+            assert_eq!(chip.column_names().len(), chip.width(), "{}", chip.name());
+            if let Some(layout) = chip.column_layout() {
+                assert_eq!(layout.offset, 0, "{}", chip.name());
+                assert_eq!(layout.width, chip.width(), "{}", chip.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_trace_csv_round_trip() {
+        let mut runtime = Executor::new(fibonacci_program(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let mut records = runtime.records;
+        records[0].public_values.shard = 1;
+        machine.generate_dependencies(&mut records, &SP1CoreOpts::default(), None);
+        let shard = &records[0];
+
+        let dir = tempfile::tempdir().unwrap();
+        machine.write_trace_csvs(shard, dir.path()).unwrap();
+
+        for chip in machine.shard_chips(shard) {
+            let path = dir.path().join(format!("{}.csv", chip.name()));
+            let csv = std::fs::read_to_string(path).unwrap();
+            let mut lines = csv.lines();
+            let header = lines.next().unwrap().split(',').map(str::to_string).collect::<Vec<_>>();
+            assert_eq!(header, chip.column_names(), "{}", chip.name());
+
+            let values = lines
+                .flat_map(|line| line.split(','))
+                .map(|value| BabyBear::from_canonical_u32(value.parse().unwrap()))
+                .collect::<Vec<_>>();
+            let trace: RowMajorMatrix<BabyBear> =
+                chip.generate_trace(shard, &mut ExecutionRecord::default());
+            assert_eq!(values, trace.values, "{}", chip.name());
+        }
+    }
 }
@@ -13,8 +13,8 @@ use crate::{
 };
 
 use super::{
-    AddSubChip, BitwiseChip, ByteChip, CpuChip, DivRemChip, LtChip, MemoryGlobalChip, MulChip,
-    ProgramChip, RiscvAir, ShiftLeft, ShiftRightChip, SyscallChip,
+    AddSubChip, BitManipChip, BitwiseChip, ByteChip, CpuChip, DivRemChip, LtChip, MemoryGlobalChip,
+    MulChip, ProgramChip, RiscvAir, ShiftLeft, ShiftRightChip, SyscallChip,
 };
 
 #[derive(Debug, Error)]
@@ -317,9 +317,13 @@ impl<F: PrimeField32> Default for CoreShapeConfig<F> {
 
         let mut allowed_core_log_heights = vec![];
 
-        let small_cpu_heights = vec![16, 18, 20];
+        let small_cpu_heights = [16, 18, 20];
 
-        for height in small_cpu_heights {
+        // Every core chip multiplies the number of shapes by its number of allowed heights, so the
+        // BitManip chip only appears in a copy of each cluster, tried after the ones without it.
+        for (height, bitmanip) in
+            [false, true].into_iter().flat_map(|b| small_cpu_heights.iter().map(move |h| (*h, b)))
+        {
             assert!(height > 1);
             // Get the heights for the short shape cluster (for small shards).
             let cpu_heights = vec![Some(height)];
@@ -333,7 +337,7 @@ impl<F: PrimeField32> Default for CoreShapeConfig<F> {
             let memory_local_heights = vec![Some(height - 1), Some(height), Some(height + 1)];
             let syscall_heights = vec![None, Some(height - 1), Some(height)];
 
-            let short_allowed_log_heights = HashMap::from([
+            let mut short_allowed_log_heights = HashMap::from([
                 (RiscvAir::Cpu(CpuChip::default()), cpu_heights),
                 (RiscvAir::DivRem(DivRemChip::default()), divrem_heights),
                 (RiscvAir::Add(AddSubChip::default()), add_sub_heights),
@@ -345,6 +349,12 @@ impl<F: PrimeField32> Default for CoreShapeConfig<F> {
                 (RiscvAir::MemoryLocal(MemoryLocalChip::new()), memory_local_heights),
                 (RiscvAir::SyscallCore(SyscallChip::core()), syscall_heights),
             ]);
+            if bitmanip {
+                short_allowed_log_heights.insert(
+                    RiscvAir::BitManip(BitManipChip::default()),
+                    vec![Some(height - 1), Some(height)],
+                );
+            }
             allowed_core_log_heights.push((short_allowed_log_heights, true));
         }
 
@@ -378,9 +388,30 @@ impl<F: PrimeField32> Default for CoreShapeConfig<F> {
 #[cfg(test)]
 mod tests {
     use p3_baby_bear::BabyBear;
+    use sp1_core_executor::{Executor, Instruction, Opcode};
+    use sp1_stark::SP1CoreOpts;
 
     use super::*;
 
+    #[test]
+    fn test_fix_shape_with_bitmanip() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true),
+            Instruction::new(Opcode::ANDN, 30, 29, 29, false, false),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+        let shape_config = CoreShapeConfig::<BabyBear>::default();
+        shape_config.fix_preprocessed_shape(&mut program).unwrap();
+
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let record = &mut runtime.records[0];
+        assert!(!record.bitmanip_events.is_empty());
+        shape_config.fix_shape(record).unwrap();
+        let bitmanip = RiscvAir::<BabyBear>::BitManip(BitManipChip::default()).name();
+        assert!(record.shape.as_ref().unwrap().inner.contains_key(&bitmanip));
+    }
+
     #[test]
     #[ignore]
     fn test_making_shapes() {
@@ -9,7 +9,9 @@ use p3_field::PrimeField32;
 use p3_matrix::{dense::RowMajorMatrix, Matrix};
 use sp1_core_executor::{events::SyscallEvent, ExecutionRecord, Program};
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::{InteractionScope, MachineAir, SP1AirBuilder};
+use sp1_stark::air::{
+    Column, ColumnDescriptor, ColumnLayout, InteractionScope, MachineAir, SP1AirBuilder,
+};
 
 use crate::utils::pad_rows_fixed;
 
@@ -74,6 +76,10 @@ impl<F: PrimeField32> MachineAir<F> for SyscallChip {
         format!("Syscall{}", self.shard_kind).to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(SyscallCols::<Column>::layout())
+    }
+
     fn generate_dependencies(&self, _input: &ExecutionRecord, _output: &mut ExecutionRecord) {
         // Do nothing since this chip has no dependencies.
     }
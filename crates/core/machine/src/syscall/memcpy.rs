@@ -6,7 +6,7 @@ use sp1_core_executor::syscalls::{Syscall, SyscallCode, SyscallContext};
 use sp1_core_executor::{ExecutionRecord, Program};
 use sp1_curves::params::Limbs;
 use sp1_stark::air::InteractionScope;
-use sp1_stark::air::{MachineAir, SP1AirBuilder};
+use sp1_stark::air::{Column, ColumnDescriptor, ColumnLayout, MachineAir, SP1AirBuilder};
 use std::borrow::{Borrow, BorrowMut};
 use std::marker::PhantomData;
 
@@ -72,6 +72,10 @@ impl<F: PrimeField32, NumWords: ArrayLength + Send + Sync, NumBytes: ArrayLength
         format!("MemCopy{}Chip", NumWords::USIZE)
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(MemCopyCols::<Column, NumWords>::layout())
+    }
+
     fn generate_trace(&self, input: &Self::Record, output: &mut Self::Record) -> RowMajorMatrix<F> {
         let mut rows = vec![];
         let mut new_byte_lookup_events = vec![];
@@ -22,6 +22,7 @@ use sp1_derive::AlignedBorrow;
 use sp1_stark::air::InteractionScope;
 use sp1_stark::air::MachineAir;
 use sp1_stark::air::SP1AirBuilder;
+use sp1_stark::air::{Column, ColumnDescriptor, ColumnLayout};
 use typenum::U8;
 
 use crate::air::MemoryAirBuilder;
@@ -70,6 +71,10 @@ impl<F: PrimeField32> MachineAir<F> for Bn254ScalarMacChip {
         "Bn254ScalarMac".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(Bn254ScalarMacCols::<Column>::layout())
+    }
+
     fn generate_trace(&self, input: &Self::Record, output: &mut Self::Record) -> RowMajorMatrix<F> {
         let events = input.get_precompile_events(SyscallCode::BN254_SCALAR_MAC);
 
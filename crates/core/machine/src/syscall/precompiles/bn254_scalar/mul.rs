@@ -21,6 +21,7 @@ use sp1_derive::AlignedBorrow;
 use sp1_stark::air::InteractionScope;
 use sp1_stark::air::MachineAir;
 use sp1_stark::air::SP1AirBuilder;
+use sp1_stark::air::{Column, ColumnDescriptor, ColumnLayout};
 
 use crate::air::MemoryAirBuilder;
 use crate::utils::limbs_from_prev_access;
@@ -65,6 +66,10 @@ impl<F: PrimeField32> MachineAir<F> for Bn254ScalarMulChip {
         "Bn254ScalarMul".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(Bn254ScalarMulCols::<Column>::layout())
+    }
+
     fn generate_trace(&self, input: &Self::Record, output: &mut Self::Record) -> RowMajorMatrix<F> {
         let events = input.get_precompile_events(SyscallCode::BN254_SCALAR_MUL);
 
@@ -23,7 +23,10 @@ use sp1_curves::{
     params::{limbs_from_vec, FieldParameters, Limbs},
 };
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::{BaseAirBuilder, InteractionScope, MachineAir, SP1AirBuilder};
+use sp1_stark::air::{
+    BaseAirBuilder, Column, ColumnDescriptor, ColumnLayout, InteractionScope, MachineAir,
+    SP1AirBuilder,
+};
 use typenum::U32;
 
 use crate::{
@@ -211,6 +214,10 @@ impl<F: PrimeField32, E: EdwardsParameters> MachineAir<F> for EdDecompressChip<E
         "EdDecompress".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(EdDecompressCols::<Column>::layout())
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
@@ -21,7 +21,10 @@ use sp1_curves::{
     weierstrass::{FieldType, FpOpField},
 };
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::{BaseAirBuilder, InteractionScope, MachineAir, Polynomial, SP1AirBuilder};
+use sp1_stark::air::{
+    BaseAirBuilder, Column, ColumnDescriptor, ColumnLayout, InteractionScope, MachineAir,
+    Polynomial, SP1AirBuilder,
+};
 
 use crate::{
     memory::{value_as_limbs, MemoryReadCols, MemoryWriteCols},
@@ -87,6 +90,10 @@ impl<F: PrimeField32, P: FpOpField> MachineAir<F> for FpOpChip<P> {
         }
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(FpOpCols::<Column, P>::layout())
+    }
+
     fn generate_trace(&self, input: &Self::Record, output: &mut Self::Record) -> RowMajorMatrix<F> {
         // All the fp events for a given curve are coalesce to the curve's Add operation.  Only retrieve
         // precompile events for that operation.
@@ -20,7 +20,10 @@ use sp1_curves::{
     weierstrass::{FieldType, FpOpField},
 };
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::{BaseAirBuilder, InteractionScope, MachineAir, Polynomial, SP1AirBuilder};
+use sp1_stark::air::{
+    BaseAirBuilder, Column, ColumnDescriptor, ColumnLayout, InteractionScope, MachineAir,
+    Polynomial, SP1AirBuilder,
+};
 use std::mem::size_of;
 use typenum::Unsigned;
 
@@ -139,6 +142,10 @@ impl<F: PrimeField32, P: FpOpField> MachineAir<F> for Fp2MulAssignChip<P> {
         }
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(Fp2MulAssignCols::<Column, P>::layout())
+    }
+
     fn generate_trace(&self, input: &Self::Record, output: &mut Self::Record) -> RowMajorMatrix<F> {
         let events = match P::FIELD_TYPE {
             FieldType::Bn254 => input.get_precompile_events(SyscallCode::BN254_FP2_MUL),
@@ -142,7 +142,6 @@ mod test {
         riscv::RiscvAir,
         utils::{prove, setup_logger, tests::KECCAK256_ELF},
     };
-    use sp1_primitives::io::SP1PublicValues;
 
     use rand::{Rng, SeedableRng};
     use sp1_core_executor::Program;
@@ -180,10 +179,9 @@ mod test {
         let config = BabyBearPoseidon2::new();
 
         let program = Program::from(KECCAK256_ELF).unwrap();
-        let (proof, public_values, _) =
+        let (proof, mut public_values, _) =
             prove::<_, CpuProver<_, _>>(program, &stdin, config, SP1CoreOpts::default(), None)
                 .unwrap();
-        let mut public_values = SP1PublicValues::from(&public_values);
 
         let config = BabyBearPoseidon2::new();
         let mut challenger = config.challenger();
@@ -28,7 +28,10 @@ use sp1_curves::{
 };
 use sp1_derive::AlignedBorrow;
 use sp1_stark::{
-    air::{BaseAirBuilder, InteractionScope, MachineAir, Polynomial, SP1AirBuilder},
+    air::{
+        BaseAirBuilder, Column, ColumnDescriptor, ColumnLayout, InteractionScope, MachineAir,
+        Polynomial, SP1AirBuilder,
+    },
     MachineRecord,
 };
 use std::{
@@ -100,6 +103,10 @@ impl<F: PrimeField32> MachineAir<F> for Uint256MulChip {
         "Uint256MulMod".to_string()
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(Uint256MulCols::<Column>::layout())
+    }
+
     fn generate_trace(
         &self,
         input: &ExecutionRecord,
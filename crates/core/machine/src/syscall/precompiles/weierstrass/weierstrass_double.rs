@@ -25,7 +25,9 @@ use sp1_curves::{
     AffinePoint, CurveType, EllipticCurve,
 };
 use sp1_derive::AlignedBorrow;
-use sp1_stark::air::{InteractionScope, MachineAir, SP1AirBuilder};
+use sp1_stark::air::{
+    Column, ColumnDescriptor, ColumnLayout, InteractionScope, MachineAir, SP1AirBuilder,
+};
 
 use crate::{
     memory::{MemoryCols, MemoryWriteCols},
@@ -176,6 +178,10 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
         }
     }
 
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        Some(WeierstrassDoubleAssignCols::<Column, E::BaseField>::layout())
+    }
+
     fn generate_dependencies(&self, input: &Self::Record, output: &mut Self::Record) {
         let events = match E::CURVE_TYPE {
             CurveType::Secp256k1 => &input.get_precompile_events(SyscallCode::SECP256K1_DOUBLE),
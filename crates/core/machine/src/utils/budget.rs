@@ -0,0 +1,168 @@
+use std::{collections::HashMap, mem::size_of};
+
+use p3_air::BaseAir;
+use p3_baby_bear::BabyBear;
+use sp1_core_executor::events::{AluEvent, CpuEvent, MemoryLocalEvent};
+use sp1_stark::{air::MachineAir, Chip, MemoryModel, SP1ProverOpts, DIGEST_SIZE};
+
+use crate::{
+    bytes::trace::NUM_ROWS,
+    memory::NUM_LOCAL_MEMORY_ENTRIES_PER_ROW,
+    riscv::{RiscvAir, RiscvAirDiscriminants},
+};
+
+/// The blowup factor of the low-degree extension of the core traces.
+const LDE_BLOWUP: usize = 2;
+
+/// The degree of the extension field the permutation trace and the quotient are defined over.
+const EXTENSION_DEGREE: usize = 4;
+
+/// The number of commitments of a shard with a Merkle tree over its low-degree extensions: the
+/// main traces, the permutation traces and the quotient chunks.
+const NUM_COMMITMENTS: usize = 3;
+
+/// Selects prover options that fit a memory budget, estimating the memory used per shard from the
+/// widths of the RISC-V chips.
+pub trait MemoryBudget {
+    /// Get options whose estimated peak memory usage fits in `bytes`.
+    fn with_memory_budget(bytes: u64) -> Self;
+}
+
+impl MemoryBudget for SP1ProverOpts {
+    fn with_memory_budget(bytes: u64) -> Self {
+        SP1ProverOpts::with_memory_budget_for(bytes, &riscv_memory_model())
+    }
+}
+
+/// The number of cells a row of `chip` takes while its shard is proven: the preprocessed, main
+/// and permutation traces and the quotient chunks, each with its low-degree extension.
+fn cells_per_row(chip: &Chip<BabyBear, RiscvAir<BabyBear>>) -> u64 {
+    let trace = chip.preprocessed_width()
+        + chip.width()
+        + EXTENSION_DEGREE * chip.permutation_width()
+        + EXTENSION_DEGREE * chip.quotient_width();
+    (trace * (1 + LDE_BLOWUP)) as u64
+}
+
+/// The number of cells of the Merkle trees committing to a row of the tallest trace of a shard.
+///
+/// Each tree hashes every row of the low-degree extensions into a digest and has about as many
+/// inner nodes as leaves.
+const fn merkle_cells_per_row() -> u64 {
+    (NUM_COMMITMENTS * 2 * DIGEST_SIZE * LDE_BLOWUP) as u64
+}
+
+/// The [MemoryModel] of the RISC-V machine.
+///
+/// Every cycle of a core shard is assumed to emit a cpu event, the most expensive ALU event and a
+/// local memory event, and every precompile shard to be filled by the most expensive precompile,
+/// which overestimates the memory used by most programs.
+pub fn riscv_memory_model() -> MemoryModel {
+    let chips = RiscvAir::<BabyBear>::chips()
+        .into_iter()
+        .map(|chip| (RiscvAirDiscriminants::from(chip.air()), cells_per_row(&chip)))
+        .collect::<HashMap<_, _>>();
+
+    let alu_cells = [
+        RiscvAirDiscriminants::Add,
+        RiscvAirDiscriminants::Bitwise,
+        RiscvAirDiscriminants::Mul,
+        RiscvAirDiscriminants::DivRem,
+        RiscvAirDiscriminants::ShiftLeft,
+        RiscvAirDiscriminants::ShiftRight,
+        RiscvAirDiscriminants::Lt,
+        RiscvAirDiscriminants::BitManip,
+    ]
+    .iter()
+    .map(|chip| chips[chip])
+    .max()
+    .unwrap();
+    let memory_local_cells = chips[&RiscvAirDiscriminants::MemoryLocal];
+
+    // A precompile event is spread over `rows_per_event` rows of its chip, and also takes a row of
+    // the precompile syscall chip and a row of the local memory chip per memory access.
+    let deferred_cells = RiscvAir::<BabyBear>::get_all_precompile_airs()
+        .into_iter()
+        .map(|(air, local_mem_events)| {
+            let rows_per_event = air.rows_per_event() as u64;
+            let per_event = chips[&RiscvAirDiscriminants::SyscallPrecompile]
+                + (local_mem_events as u64).div_ceil(NUM_LOCAL_MEMORY_ENTRIES_PER_ROW as u64)
+                    * memory_local_cells;
+            cells_per_row(&Chip::new(air)) + per_event.div_ceil(rows_per_event)
+        })
+        .max()
+        .unwrap();
+
+    MemoryModel {
+        cells_per_cycle: chips[&RiscvAirDiscriminants::Cpu]
+            + alu_cells
+            + memory_local_cells
+            + merkle_cells_per_row(),
+        cells_per_deferred_row: deferred_cells + merkle_cells_per_row(),
+        cells_per_memory_event: chips[&RiscvAirDiscriminants::MemoryGlobalInit]
+            + chips[&RiscvAirDiscriminants::MemoryGlobalFinal]
+            + merkle_cells_per_row(),
+        fixed_cells_per_shard: NUM_ROWS as u64
+            * (chips[&RiscvAirDiscriminants::ByteLookup] + merkle_cells_per_row()),
+        bytes_per_cell: size_of::<BabyBear>() as u64,
+        record_bytes_per_cycle: (size_of::<CpuEvent>()
+            + size_of::<AluEvent>()
+            + size_of::<MemoryLocalEvent>()) as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_matrix::Matrix;
+    use sp1_core_executor::{programs::tests::fibonacci_program, ExecutionRecord, Executor};
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, SP1CoreOpts};
+
+    use super::*;
+
+    #[test]
+    fn test_memory_model_bounds_measured_shard() {
+        let mut runtime = Executor::new(fibonacci_program(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        machine.generate_dependencies(&mut runtime.records, &SP1CoreOpts::default(), None);
+        let shard = &runtime.records[0];
+
+        // Measure the traces of the core chips of the shard. The memory initialization chips are
+        // proven in separate shards, so they are left out.
+        let memory_init_final = RiscvAir::<BabyBear>::memory_init_final_airs()
+            .iter()
+            .map(MachineAir::name)
+            .collect::<Vec<_>>();
+        let mut cpu_height = 0;
+        let mut max_height = 0;
+        let mut cells = 0;
+        for chip in machine.shard_chips(shard) {
+            if memory_init_final.contains(&chip.name()) {
+                continue;
+            }
+            let trace = chip.generate_trace(shard, &mut ExecutionRecord::default());
+            let height = trace.height();
+            if chip.name() == "CPU" {
+                cpu_height = height;
+            }
+            max_height = max_height.max(height);
+            cells += height as u64 * cells_per_row(chip);
+        }
+        cells += max_height as u64 * merkle_cells_per_row();
+        let measured = cells * size_of::<BabyBear>() as u64;
+
+        let model = riscv_memory_model();
+        let opts = SP1CoreOpts { shard_size: cpu_height, ..SP1CoreOpts::default() };
+        let estimate = opts.estimate_memory(&MemoryModel {
+            cells_per_deferred_row: 0,
+            cells_per_memory_event: 0,
+            record_bytes_per_cycle: 0,
+            ..model
+        });
+        assert!(
+            estimate.bytes_per_shard >= measured,
+            "estimated {} bytes per shard, measured {measured}",
+            estimate.bytes_per_shard
+        );
+    }
+}
@@ -1,16 +1,20 @@
+mod budget;
 pub mod concurrency;
 mod logger;
 #[cfg(any(test, feature = "programs"))]
 mod programs;
 mod prove;
 mod span;
+mod trace_dump;
 mod tracer;
 
+pub use budget::*;
 pub use logger::*;
 use p3_field::Field;
 pub use prove::*;
 use sp1_curves::params::Limbs;
 pub use span::*;
+pub use trace_dump::*;
 pub use tracer::*;
 
 #[cfg(any(test, feature = "programs"))]
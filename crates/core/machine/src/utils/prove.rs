@@ -27,21 +27,21 @@ use p3_matrix::Matrix;
 use crate::{
     io::SP1Stdin,
     riscv::cost::CostEstimator,
-    utils::{chunk_vec, concurrency::TurnBasedSync},
+    utils::{chunk_vec, concurrency::TurnBasedSync, dump_shard, riscv_memory_model},
 };
 use sp1_core_executor::events::sorted_table_lines;
 use sp1_primitives::io::SP1PublicValues;
 
 use sp1_core_executor::{
     subproof::NoOpSubproofVerifier, ExecutionError, ExecutionRecord, ExecutionReport, Executor,
-    Program, SP1Context,
+    Program, ProverEvent, ProvingInterrupted, SP1Context,
 };
 use sp1_stark::{
     air::{MachineAir, PublicValues},
     Com, CpuProver, DebugConstraintBuilder, InteractionBuilder, MachineProof, MachineProver,
     MachineRecord, OpeningProof, PcsProverData, ProverConstraintFolder, SP1CoreOpts,
-    StarkGenericConfig, StarkMachine, StarkProvingKey, StarkVerifyingKey, UniConfig, Val,
-    VerifierConstraintFolder,
+    StarkGenericConfig, StarkMachine, StarkProvingKey, StarkVerifyingKey, TraceDumpOpts, UniConfig,
+    Val, VerifierConstraintFolder,
 };
 
 #[derive(Error, Debug)]
@@ -52,6 +52,8 @@ pub enum SP1CoreProverError {
     IoError(io::Error),
     #[error("serialization error: {0}")]
     SerializationError(bincode::Error),
+    #[error("{0}")]
+    Interrupted(#[from] ProvingInterrupted),
 }
 
 pub fn prove_simple<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<SC::Val>>>(
@@ -96,13 +98,16 @@ where
     Ok((proof, runtime.state.global_clk))
 }
 
+/// Prove the execution of `program` with a fresh setup.
+///
+/// Returns the proof, the public values the program committed to and the number of cycles.
 pub fn prove<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<SC::Val>>>(
     program: Program,
     stdin: &SP1Stdin,
     config: SC,
     opts: SP1CoreOpts,
     shape_config: Option<&CoreShapeConfig<SC::Val>>,
-) -> Result<(MachineProof<SC>, Vec<u8>, u64), SP1CoreProverError>
+) -> Result<(MachineProof<SC>, SP1PublicValues, u64), SP1CoreProverError>
 where
     SC::Challenger: 'static + Clone + Send,
     <SC as StarkGenericConfig>::Val: PrimeField32,
@@ -124,6 +129,11 @@ where
     )
 }
 
+/// Prove the execution of `program` with the given proving key and context.
+///
+/// Returns the proof, the public values the program committed to and the number of cycles. The
+/// public values are returned because the digest in the proof can't be recomputed from the stdout
+/// stream once the program commits to named channels.
 pub fn prove_with_context<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<SC::Val>>>(
     prover: &P,
     pk: &P::DeviceProvingKey,
@@ -132,7 +142,7 @@ pub fn prove_with_context<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<
     opts: SP1CoreOpts,
     context: SP1Context,
     shape_config: Option<&CoreShapeConfig<SC::Val>>,
-) -> Result<(MachineProof<SC>, Vec<u8>, u64), SP1CoreProverError>
+) -> Result<(MachineProof<SC>, SP1PublicValues, u64), SP1CoreProverError>
 where
     SC::Val: PrimeField32,
     SC::Challenger: 'static + Clone + Send,
@@ -140,9 +150,15 @@ where
     Com<SC>: Send + Sync,
     PcsProverData<SC>: Send + Sync,
 {
+    // Report the estimated memory usage before proving.
+    tracing::info!("core prover {}", opts.estimate_memory(&riscv_memory_model()));
+
     // Setup the runtime.
+    let observer = context.observer();
+    observer.start();
     let mut runtime = Executor::with_context(program.clone(), opts, context);
     runtime.write_vecs(&stdin.buffer);
+    runtime.write_regions(&stdin.regions);
     for proof in stdin.proofs.iter() {
         let (proof, vk) = proof.clone();
         runtime.write_proof(proof, vk);
@@ -151,6 +167,12 @@ where
     #[cfg(feature = "debug")]
     let (all_records_tx, all_records_rx) = std::sync::mpsc::channel::<Vec<ExecutionRecord>>();
 
+    // The preprocessed traces are dumped alongside the shards, so fetch them from the device once.
+    let dump_pk = opts.trace_dump.enabled.then(|| pk.to_host());
+    let dump_pk = dump_pk.as_ref();
+    let dump_chips = TraceDumpOpts::chips();
+    let dump_chips = dump_chips.as_deref();
+
     // Record the start of the process.
     let proving_start = Instant::now();
     let span = tracing::Span::current().clone();
@@ -161,19 +183,32 @@ where
         let checkpoint_generator_span = tracing::Span::current().clone();
         let (checkpoints_tx, checkpoints_rx) =
             sync_channel::<(usize, File, bool)>(opts.checkpoints_channel_capacity);
+        let checkpoint_generator_observer = observer.clone();
         let checkpoint_generator_handle: ScopedJoinHandle<Result<_, SP1CoreProverError>> =
             s.spawn(move || {
                 let _span = checkpoint_generator_span.enter();
+                let observer = checkpoint_generator_observer;
                 tracing::debug_span!("checkpoint generator").in_scope(|| {
                     let mut index = 0;
                     loop {
+                        // Stop producing checkpoints if proving was cancelled or timed out.
+                        // Dropping the sender lets the trace generation workers drain and exit.
+                        if let Err(interrupted) = observer.check() {
+                            break Err(interrupted.into());
+                        }
+
                         // Enter the span.
                         let span = tracing::debug_span!("batch");
                         let _span = span.enter();
 
                         // Execute the runtime until we reach a checkpoint.
                         let (checkpoint, done) =
-                            runtime.execute_state().map_err(SP1CoreProverError::ExecutionError)?;
+                            runtime.execute_state().map_err(|error| match error {
+                                ExecutionError::Interrupted(interrupted) => {
+                                    SP1CoreProverError::Interrupted(interrupted)
+                                }
+                                error => SP1CoreProverError::ExecutionError(error),
+                            })?;
 
                         // Save the checkpoint to a temp file.
                         let mut checkpoint_file =
@@ -184,10 +219,15 @@ where
 
                         // Send the checkpoint.
                         checkpoints_tx.send((index, checkpoint_file, done)).unwrap();
+                        observer.emit(ProverEvent::CheckpointProduced {
+                            index,
+                            done,
+                            cycles: runtime.state.global_clk,
+                        });
 
                         // If we've reached the final checkpoint, break out of the loop.
                         if done {
-                            break Ok(runtime.state.public_values_stream);
+                            break Ok(runtime.public_values());
                         }
 
                         // Update the index.
@@ -220,6 +260,7 @@ where
             let state = Arc::clone(&state);
             let deferred = Arc::clone(&deferred);
             let program = program.clone();
+            let observer = observer.clone();
 
             let span = tracing::Span::current().clone();
 
@@ -231,6 +272,16 @@ where
                         let received = { checkpoints_rx.lock().unwrap().recv() };
 
                         if let Ok((index, mut checkpoint, done)) = received {
+                            // If proving was interrupted, drain the checkpoint without tracing it
+                            // while still taking our turns so that the other workers can proceed.
+                            if observer.is_interrupted() {
+                                record_gen_sync.wait_for_turn(index);
+                                record_gen_sync.advance_turn();
+                                trace_gen_sync.wait_for_turn(index);
+                                trace_gen_sync.advance_turn();
+                                continue;
+                            }
+
                             // Trace the checkpoint and reconstruct the execution records.
                             let (mut records, _) = tracing::debug_span!("trace checkpoint")
                                 .in_scope(|| trace_checkpoint(program.clone(), &checkpoint, opts));
@@ -341,10 +392,17 @@ where
 
         // Spawn the phase 1 prover thread.
         let phase_1_prover_span = tracing::Span::current().clone();
+        let phase_1_prover_observer = observer.clone();
         let phase_1_prover_handle = s.spawn(move || {
             let _span = phase_1_prover_span.enter();
+            let observer = phase_1_prover_observer;
             tracing::debug_span!("phase 1 prover").in_scope(|| {
                 for (records, traces) in p1_records_and_traces_rx.iter() {
+                    // Keep draining the channel without committing if proving was interrupted.
+                    if observer.is_interrupted() {
+                        continue;
+                    }
+
                     tracing::debug_span!("batch").in_scope(|| {
                         let span = tracing::Span::current().clone();
 
@@ -398,7 +456,7 @@ where
         });
 
         // Wait until the checkpoint generator handle has fully finished.
-        let public_values_stream = checkpoint_generator_handle.join().unwrap().unwrap();
+        let public_values = checkpoint_generator_handle.join().unwrap()?;
 
         // Wait until the records and traces have been fully generated.
         p1_record_and_trace_gen_handles.into_iter().for_each(|handle| handle.join().unwrap());
@@ -406,6 +464,9 @@ where
         // Wait until the phase 1 prover has completely finished.
         let mut challenger = phase_1_prover_handle.join().unwrap();
 
+        // Don't start phase 2 if proving was interrupted during phase 1.
+        observer.check()?;
+
         // Sample for the global permutation challenges.
         // Obtain the challenges used for the global permutation argument.
         let mut global_permutation_challenges: Vec<SC::Challenge> = Vec::new();
@@ -440,6 +501,7 @@ where
             let state = Arc::clone(&state);
             let deferred = Arc::clone(&deferred);
             let program = program.clone();
+            let observer = observer.clone();
 
             let span = tracing::Span::current().clone();
 
@@ -450,6 +512,11 @@ where
                 let _span = span.enter();
                 tracing::debug_span!("phase 2 trace generation").in_scope(|| {
                     loop {
+                        // Stop tracing checkpoints if proving was interrupted.
+                        if observer.is_interrupted() {
+                            break;
+                        }
+
                         // Receive the latest checkpoint.
                         let received = { checkpoints.lock().unwrap().pop_front() };
                         if let Some((index, mut checkpoint, done)) = received {
@@ -521,6 +588,28 @@ where
                                 }
                             }
 
+                            // Dump the selected shards so that their chips can be replayed.
+                            if let Some(dump_pk) = dump_pk {
+                                for record in records
+                                    .iter()
+                                    .filter(|r| opts.trace_dump.includes(r.public_values.shard))
+                                {
+                                    if let Err(error) = dump_shard(
+                                        prover.machine(),
+                                        dump_pk,
+                                        record,
+                                        &TraceDumpOpts::dir(),
+                                        dump_chips,
+                                    ) {
+                                        tracing::warn!(
+                                            "failed to dump shard {}: {}",
+                                            record.public_values.shard,
+                                            error
+                                        );
+                                    }
+                                }
+                            }
+
                             #[cfg(feature = "debug")]
                             all_records_tx.send(records.clone()).unwrap();
 
@@ -580,17 +669,26 @@ where
 
         // Spawn the phase 2 prover thread.
         let p2_prover_span = tracing::Span::current().clone();
+        let p2_prover_observer = observer.clone();
         let p2_prover_handle = s.spawn(move || {
             let _span = p2_prover_span.enter();
+            let observer = p2_prover_observer;
             let mut shard_proofs = Vec::new();
             tracing::debug_span!("phase 2 prover").in_scope(|| {
                 for (records, traces) in p2_records_and_traces_rx.into_iter() {
+                    // Keep draining the channel without proving if proving was interrupted, so
+                    // that the trace generation workers never block on a full channel.
+                    if observer.is_interrupted() {
+                        continue;
+                    }
+
                     tracing::debug_span!("batch").in_scope(|| {
                         let span = tracing::Span::current().clone();
                         shard_proofs.par_extend(
                             records.into_par_iter().zip(traces.into_par_iter()).map(
                                 |(record, (global_traces, local_traces))| {
                                     let _span = span.enter();
+                                    let start = Instant::now();
 
                                     let global_data = prover.commit(&record, global_traces);
                                     let local_data = prover.commit(&record, local_traces);
@@ -621,6 +719,11 @@ where
                                             );
                                         }
                                     }
+
+                                    observer.emit(ProverEvent::ShardProven {
+                                        shard: record.public_values.shard,
+                                        elapsed: start.elapsed(),
+                                    });
                                     proof
                                 },
                             ),
@@ -636,6 +739,7 @@ where
 
         // Wait until the phase 2 prover has finished.
         let shard_proofs = p2_prover_handle.join().unwrap();
+        observer.check()?;
 
         // Log some of the `ExecutionReport` information.
         let report_aggregate = report_aggregate.lock().unwrap();
@@ -659,6 +763,8 @@ where
 
         let proof = MachineProof::<SC> { shard_proofs };
         let cycles = report_aggregate.total_instruction_count();
+        observer
+            .emit(ProverEvent::CoreProofFinished { num_shards: proof.shard_proofs.len(), cycles });
 
         // Print the summary.
         let proving_time = proving_start.elapsed().as_secs_f64();
@@ -675,10 +781,12 @@ where
         {
             let all_records = all_records_rx.iter().flatten().collect::<Vec<_>>();
             let mut challenger = prover.machine().config().challenger();
-            prover.machine().debug_constraints(&pk.to_host(), all_records, &mut challenger);
+            let report =
+                prover.machine().debug_constraints(&pk.to_host(), all_records, &mut challenger);
+            assert!(report.is_ok(), "{report}");
         }
 
-        Ok((proof, public_values_stream, cycles))
+        Ok((proof, public_values, cycles))
     })
 }
 
@@ -690,10 +798,11 @@ pub fn run_test_io<P: MachineProver<BabyBearPoseidon2, RiscvAir<BabyBear>>>(
     let runtime = tracing::debug_span!("runtime.run(...)").in_scope(|| {
         let mut runtime = Executor::new(program, SP1CoreOpts::default());
         runtime.write_vecs(&inputs.buffer);
+        runtime.write_regions(&inputs.regions);
         runtime.run().unwrap();
         runtime
     });
-    let public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+    let public_values = runtime.public_values();
 
     let _ = run_test_core::<P>(runtime, inputs, None)?;
     Ok(public_values)
@@ -765,7 +874,14 @@ where
     let prove_span = tracing::debug_span!("prove").entered();
 
     #[cfg(feature = "debug")]
-    prover.machine().debug_constraints(&pk.to_host(), records.clone(), &mut challenger.clone());
+    {
+        let report = prover.machine().debug_constraints(
+            &pk.to_host(),
+            records.clone(),
+            &mut challenger.clone(),
+        );
+        assert!(report.is_ok(), "{report}");
+    }
 
     let proof = prover.prove(&pk, records, &mut challenger, SP1CoreOpts::default()).unwrap();
     prove_span.exit();
@@ -835,7 +951,7 @@ where
         + for<'a> Air<p3_uni_stark::ProverConstraintFolder<'a, UniConfig<SC>>>
         + for<'a> Air<p3_uni_stark::DebugConstraintBuilder<'a, SC::Val>>,
 {
-    p3_uni_stark::prove(&UniConfig(config.clone()), air, challenger, trace, &vec![])
+    uni_stark_prove_with_public_values(config, air, challenger, trace, &vec![])
 }
 
 #[cfg(not(debug_assertions))]
@@ -850,7 +966,41 @@ where
     A: Air<p3_uni_stark::SymbolicAirBuilder<SC::Val>>
         + for<'a> Air<p3_uni_stark::ProverConstraintFolder<'a, UniConfig<SC>>>,
 {
-    p3_uni_stark::prove(&UniConfig(config.clone()), air, challenger, trace, &vec![])
+    uni_stark_prove_with_public_values(config, air, challenger, trace, &vec![])
+}
+
+#[cfg(debug_assertions)]
+#[cfg(not(doctest))]
+pub fn uni_stark_prove_with_public_values<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<SC::Val>,
+    public_values: &Vec<SC::Val>,
+) -> Proof<UniConfig<SC>>
+where
+    SC: StarkGenericConfig,
+    A: Air<p3_uni_stark::SymbolicAirBuilder<SC::Val>>
+        + for<'a> Air<p3_uni_stark::ProverConstraintFolder<'a, UniConfig<SC>>>
+        + for<'a> Air<p3_uni_stark::DebugConstraintBuilder<'a, SC::Val>>,
+{
+    p3_uni_stark::prove(&UniConfig(config.clone()), air, challenger, trace, public_values)
+}
+
+#[cfg(not(debug_assertions))]
+pub fn uni_stark_prove_with_public_values<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<SC::Val>,
+    public_values: &Vec<SC::Val>,
+) -> Proof<UniConfig<SC>>
+where
+    SC: StarkGenericConfig,
+    A: Air<p3_uni_stark::SymbolicAirBuilder<SC::Val>>
+        + for<'a> Air<p3_uni_stark::ProverConstraintFolder<'a, UniConfig<SC>>>,
+{
+    p3_uni_stark::prove(&UniConfig(config.clone()), air, challenger, trace, public_values)
 }
 
 #[cfg(debug_assertions)]
@@ -867,7 +1017,7 @@ where
         + for<'a> Air<p3_uni_stark::VerifierConstraintFolder<'a, UniConfig<SC>>>
         + for<'a> Air<p3_uni_stark::DebugConstraintBuilder<'a, SC::Val>>,
 {
-    p3_uni_stark::verify(&UniConfig(config.clone()), air, challenger, proof, &vec![])
+    uni_stark_verify_with_public_values(config, air, challenger, proof, &vec![])
 }
 
 #[cfg(not(debug_assertions))]
@@ -882,7 +1032,22 @@ where
     A: Air<p3_uni_stark::SymbolicAirBuilder<SC::Val>>
         + for<'a> Air<p3_uni_stark::VerifierConstraintFolder<'a, UniConfig<SC>>>,
 {
-    p3_uni_stark::verify(&UniConfig(config.clone()), air, challenger, proof, &vec![])
+    uni_stark_verify_with_public_values(config, air, challenger, proof, &vec![])
+}
+
+pub fn uni_stark_verify_with_public_values<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<UniConfig<SC>>,
+    public_values: &Vec<SC::Val>,
+) -> Result<(), p3_uni_stark::VerificationError>
+where
+    SC: StarkGenericConfig,
+    A: Air<p3_uni_stark::SymbolicAirBuilder<SC::Val>>
+        + for<'a> Air<p3_uni_stark::VerifierConstraintFolder<'a, UniConfig<SC>>>,
+{
+    p3_uni_stark::verify(&UniConfig(config.clone()), air, challenger, proof, public_values)
 }
 
 use p3_air::Air;
@@ -0,0 +1,329 @@
+use std::{
+    fmt::Display,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+use p3_baby_bear::BabyBear;
+use p3_challenger::FieldChallenger;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use serde::{Deserialize, Serialize};
+use sp1_core_executor::ExecutionRecord;
+use sp1_stark::{
+    air::{InteractionScope, MachineAir},
+    baby_bear_poseidon2::BabyBearPoseidon2,
+    debug_constraints, Challenge, ConstraintReport, ConstraintViolation, InteractionKind,
+    InteractionReport, MachineRecord, StarkGenericConfig, StarkMachine, StarkProvingKey,
+};
+use thiserror::Error;
+
+use super::{uni_stark_prove_with_public_values, uni_stark_verify_with_public_values};
+use crate::riscv::{riscv_interaction_report, RiscvAir};
+
+/// The traces of a chip saved alongside a shard's record.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChipTraceDump<F> {
+    /// The name of the chip.
+    pub name: String,
+    /// The preprocessed trace, if the chip has one.
+    pub preprocessed: Option<RowMajorMatrix<F>>,
+    /// The main trace.
+    pub main: RowMajorMatrix<F>,
+}
+
+/// The record of a shard and the traces of some of its chips, saved while proving so that a
+/// failing chip can be replayed without re-executing the program.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShardTraceDump<F> {
+    /// The shard number, as committed to in the shard's public values.
+    pub shard: u32,
+    /// The record of the shard, after its dependencies were generated and its shape was fixed.
+    pub record: ExecutionRecord,
+    /// The traces of the dumped chips.
+    pub chips: Vec<ChipTraceDump<F>>,
+}
+
+impl<F: Serialize + for<'de> Deserialize<'de>> ShardTraceDump<F> {
+    /// Save the dump to the given path.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(&mut writer, self).map_err(io::Error::other)
+    }
+
+    /// Load a dump saved with [`ShardTraceDump::save`].
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        bincode::deserialize_from(reader).map_err(io::Error::other)
+    }
+
+    /// The traces of the chip with the given name, if they were dumped.
+    pub fn chip(&self, name: &str) -> Option<&ChipTraceDump<F>> {
+        self.chips.iter().find(|chip| chip.name == name)
+    }
+}
+
+/// Generate the traces of the selected chips of a shard and save them with its record to
+/// `dir/shard-{n}.bin`, returning the path of the dump.
+///
+/// Every chip included in the shard is dumped if `chips` is `None`.
+pub fn dump_shard<SC: StarkGenericConfig>(
+    machine: &StarkMachine<SC, RiscvAir<SC::Val>>,
+    pk: &StarkProvingKey<SC>,
+    record: &ExecutionRecord,
+    dir: &Path,
+    chips: Option<&[String]>,
+) -> io::Result<PathBuf>
+where
+    SC::Val: PrimeField32,
+{
+    let chips = machine
+        .shard_chips(record)
+        .filter(|chip| chips.map_or(true, |chips| chips.contains(&chip.name())))
+        .map(|chip| ChipTraceDump {
+            name: chip.name(),
+            preprocessed: pk.chip_ordering.get(&chip.name()).map(|index| pk.traces[*index].clone()),
+            main: chip.generate_trace(record, &mut ExecutionRecord::default()),
+        })
+        .collect();
+    let shard = record.public_values.shard;
+    let dump = ShardTraceDump { shard, record: record.clone(), chips };
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("shard-{shard}.bin"));
+    dump.save(&path)?;
+    tracing::info!("dumped shard {} to {}", shard, path.display());
+    Ok(path)
+}
+
+/// An error that prevents a dumped chip from being replayed.
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("failed to load the trace dump: {0}")]
+    Io(#[from] io::Error),
+    #[error("chip {0} was not dumped, available chips: {1}")]
+    ChipNotDumped(String, String),
+    #[error("chip {0} is not a chip of the RISC-V machine")]
+    UnknownChip(String),
+}
+
+/// The outcome of replaying a dumped chip.
+#[derive(Clone, Debug)]
+pub struct ReplayReport {
+    /// The name of the replayed chip.
+    pub chip: String,
+    /// The shard the chip was dumped from.
+    pub shard: u32,
+    /// The constraints of the chip that do not hold on the dumped traces.
+    pub constraints: ConstraintReport,
+    /// The local messages that don't balance across the chips of the shard.
+    pub interactions: InteractionReport,
+    /// The result of proving and verifying the chip on its own with the uni-stark prover.
+    pub uni_stark: Result<(), String>,
+}
+
+impl ReplayReport {
+    /// Whether every check passed.
+    pub fn is_ok(&self) -> bool {
+        self.constraints.is_ok() && self.interactions.is_balanced() && self.uni_stark.is_ok()
+    }
+}
+
+impl Display for ReplayReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "chip {} of shard {}:", self.chip, self.shard)?;
+        writeln!(f, "constraints: {}", self.constraints)?;
+        writeln!(f, "local interactions: {}", self.interactions)?;
+        match &self.uni_stark {
+            Ok(()) => write!(f, "uni-stark: proved and verified"),
+            Err(error) => write!(f, "uni-stark: {error}"),
+        }
+    }
+}
+
+/// Replay a chip of a shard dumped while proving.
+///
+/// The constraints of the chip are checked on the dumped traces, the local interactions of every
+/// chip of the shard are checked against the dumped record, and the chip is proven on its own with
+/// the uni-stark prover, which ignores its interactions.
+pub fn replay_trace_dump(path: &Path, chip_name: &str) -> Result<ReplayReport, ReplayError> {
+    let dump = ShardTraceDump::<BabyBear>::load(path)?;
+    let Some(traces) = dump.chip(chip_name) else {
+        let available = dump.chips.iter().map(|chip| chip.name.as_str()).collect::<Vec<_>>();
+        return Err(ReplayError::ChipNotDumped(chip_name.to_string(), available.join(", ")));
+    };
+
+    let config = BabyBearPoseidon2::new();
+    let machine = RiscvAir::machine(config.clone());
+    let Some(chip) = machine.chips().iter().find(|chip| chip.name() == chip_name) else {
+        return Err(ReplayError::UnknownChip(chip_name.to_string()));
+    };
+    let (pk, _) = machine.setup(&dump.record.program);
+    let public_values = dump.record.public_values::<BabyBear>();
+
+    // Check the constraints of the chip, including its permutation trace.
+    let mut challenger = config.challenger();
+    pk.observe_into(&mut challenger);
+    let permutation_challenges = (0..4)
+        .map(|_| challenger.sample_ext_element())
+        .collect::<Vec<Challenge<BabyBearPoseidon2>>>();
+    let (permutation_trace, global_sum, local_sum) = chip.generate_permutation_trace(
+        traces.preprocessed.as_ref(),
+        &traces.main,
+        &permutation_challenges,
+    );
+    let violations = debug_constraints::<BabyBearPoseidon2, _>(
+        chip,
+        traces.preprocessed.as_ref(),
+        &traces.main,
+        &permutation_trace,
+        &permutation_challenges,
+        &public_values,
+        &[global_sum, local_sum],
+    );
+    let constraints = ConstraintReport {
+        violations: violations
+            .into_iter()
+            .map(|violation| ConstraintViolation { shard: dump.shard as usize, ..violation })
+            .collect(),
+        ..ConstraintReport::default()
+    };
+
+    // The interactions of a chip only balance against the other chips of the shard.
+    let interactions = riscv_interaction_report(
+        &machine,
+        &pk,
+        &[dump.record.clone()],
+        InteractionKind::all_kinds(),
+        InteractionScope::Local,
+    );
+
+    let uni_stark = panic::catch_unwind(AssertUnwindSafe(|| {
+        prove_chip(&config, chip.air(), traces.main.clone(), &public_values)
+    }))
+    .unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Err(format!("panicked: {message}"))
+    });
+
+    Ok(ReplayReport {
+        chip: chip_name.to_string(),
+        shard: dump.shard,
+        constraints,
+        interactions,
+        uni_stark,
+    })
+}
+
+/// Prove and verify a single chip with the uni-stark prover.
+///
+/// The uni-stark prover has no preprocessed traces, so chips with preprocessed columns can't be
+/// proven on their own.
+fn prove_chip(
+    config: &BabyBearPoseidon2,
+    air: &RiscvAir<BabyBear>,
+    trace: RowMajorMatrix<BabyBear>,
+    public_values: &Vec<BabyBear>,
+) -> Result<(), String> {
+    macro_rules! prove_and_verify {
+        ($chip:expr) => {{
+            let mut challenger = config.challenger();
+            let proof = uni_stark_prove_with_public_values(
+                config,
+                $chip,
+                &mut challenger,
+                trace,
+                public_values,
+            );
+            let mut challenger = config.challenger();
+            uni_stark_verify_with_public_values(
+                config,
+                $chip,
+                &mut challenger,
+                &proof,
+                public_values,
+            )
+            .map_err(|error| format!("verification failed: {error:?}"))
+        }};
+    }
+
+    match air {
+        RiscvAir::Program(_) | RiscvAir::ByteLookup(_) | RiscvAir::ProgramMemory(_) => {
+            Err(format!("{} has preprocessed columns and can't be proven alone", air.name()))
+        }
+        RiscvAir::Cpu(chip) => prove_and_verify!(chip),
+        RiscvAir::Add(chip) => prove_and_verify!(chip),
+        RiscvAir::Bitwise(chip) => prove_and_verify!(chip),
+        RiscvAir::Mul(chip) => prove_and_verify!(chip),
+        RiscvAir::DivRem(chip) => prove_and_verify!(chip),
+        RiscvAir::Lt(chip) => prove_and_verify!(chip),
+        RiscvAir::ShiftLeft(chip) => prove_and_verify!(chip),
+        RiscvAir::ShiftRight(chip) => prove_and_verify!(chip),
+        RiscvAir::BitManip(chip) => prove_and_verify!(chip),
+        RiscvAir::MemoryGlobalInit(chip) => prove_and_verify!(chip),
+        RiscvAir::MemoryGlobalFinal(chip) => prove_and_verify!(chip),
+        RiscvAir::MemoryLocal(chip) => prove_and_verify!(chip),
+        RiscvAir::SyscallCore(chip) => prove_and_verify!(chip),
+        RiscvAir::SyscallPrecompile(chip) => prove_and_verify!(chip),
+        RiscvAir::Sha256Extend(chip) => prove_and_verify!(chip),
+        RiscvAir::Sha256Compress(chip) => prove_and_verify!(chip),
+        RiscvAir::Ed25519Add(chip) => prove_and_verify!(chip),
+        RiscvAir::Ed25519Decompress(chip) => prove_and_verify!(chip),
+        RiscvAir::K256Decompress(chip) => prove_and_verify!(chip),
+        RiscvAir::Secp256k1Add(chip) => prove_and_verify!(chip),
+        RiscvAir::Secp256k1Double(chip) => prove_and_verify!(chip),
+        RiscvAir::KeccakP(chip) => prove_and_verify!(chip),
+        RiscvAir::Bn254Add(chip) => prove_and_verify!(chip),
+        RiscvAir::Bn254Double(chip) => prove_and_verify!(chip),
+        RiscvAir::Bls12381Add(chip) => prove_and_verify!(chip),
+        RiscvAir::Bls12381Double(chip) => prove_and_verify!(chip),
+        RiscvAir::Uint256Mul(chip) => prove_and_verify!(chip),
+        RiscvAir::Bls12381Decompress(chip) => prove_and_verify!(chip),
+        RiscvAir::Bls12381Fp(chip) => prove_and_verify!(chip),
+        RiscvAir::Bls12381Fp2Mul(chip) => prove_and_verify!(chip),
+        RiscvAir::Bls12381Fp2AddSub(chip) => prove_and_verify!(chip),
+        RiscvAir::Bn254Fp(chip) => prove_and_verify!(chip),
+        RiscvAir::Bn254Fp2Mul(chip) => prove_and_verify!(chip),
+        RiscvAir::Bn254Fp2AddSub(chip) => prove_and_verify!(chip),
+        RiscvAir::Bn254ScalarMac(chip) => prove_and_verify!(chip),
+        RiscvAir::Bn254ScalarMul(chip) => prove_and_verify!(chip),
+        RiscvAir::MemCopy32(chip) => prove_and_verify!(chip),
+        RiscvAir::MemCopy64(chip) => prove_and_verify!(chip),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_core_executor::{events::AluEvent, Opcode};
+
+    use super::*;
+
+    #[test]
+    fn replay_dumped_chip() {
+        let mut shard = ExecutionRecord::default();
+        shard.public_values.shard = 1;
+        shard.add_events =
+            (0..100).map(|i| AluEvent::new(0, 0, Opcode::ADD, 2 * i, i, i)).collect();
+
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (pk, _) = machine.setup(&shard.program);
+        let dir = tempfile::tempdir().unwrap();
+        let chips = ["AddSub".to_string()];
+        let path = dump_shard(&machine, &pk, &shard, dir.path(), Some(&chips)).unwrap();
+
+        let report = replay_trace_dump(&path, "AddSub").unwrap();
+        assert_eq!(report.shard, 1);
+        assert!(report.constraints.is_ok(), "{}", report.constraints);
+        assert_eq!(report.uni_stark, Ok(()));
+
+        let dump = ShardTraceDump::<BabyBear>::load(&path).unwrap();
+        assert_eq!(dump.chips.len(), 1);
+        assert!(matches!(replay_trace_dump(&path, "CPU"), Err(ReplayError::ChipNotDumped(..))));
+    }
+}
@@ -10,7 +10,7 @@ use typenum::{Unsigned, U2, U4};
 
 use generic_array::{sequence::GenericSequence, ArrayLength, GenericArray};
 use num::BigUint;
-use sp1_stark::air::Polynomial;
+use sp1_stark::air::{ColumnDescriptor, ColumnLayout, Polynomial};
 
 use p3_field::Field;
 
@@ -26,6 +26,12 @@ pub const NB_BITS_PER_LIMB: usize = 8;
 #[derive(Debug, Clone)]
 pub struct Limbs<T, N: ArrayLength>(pub GenericArray<T, N>);
 
+impl<T: ColumnLayout, N: ArrayLength> ColumnLayout for Limbs<T, N> {
+    fn layout_at(path: &str, offset: usize) -> ColumnDescriptor {
+        GenericArray::<T, N>::layout_at(path, offset)
+    }
+}
+
 pub trait FieldParameters:
     Send + Sync + Copy + 'static + Debug + Serialize + DeserializeOwned + NumLimbs
 {
@@ -87,7 +87,65 @@ pub fn aligned_borrow_derive(input: TokenStream) -> TokenStream {
         }
     };
 
-    TokenStream::from(methods)
+    // Describe the columns of the struct, so that debugging tools can name them. The impl only
+    // holds when every field type has a layout, so structs with foreign fields still compile.
+    let column_layout = match &ast.data {
+        Data::Struct(data) => {
+            let mut generics = ast.generics.clone();
+            let layout_where_clause = generics.make_where_clause();
+            for field in data.fields.iter() {
+                let ty = &field.ty;
+                layout_where_clause
+                    .predicates
+                    .push(parse_quote!(#ty: sp1_stark::air::ColumnLayout));
+            }
+            let (layout_impl_generics, _, layout_where_clause) = generics.split_for_impl();
+
+            // A newtype like `Word<T>(pub [T; 4])` has the layout of its field.
+            let is_newtype =
+                data.fields.len() == 1 && data.fields.iter().all(|f| f.ident.is_none());
+            let body = if is_newtype {
+                let ty = &data.fields.iter().next().unwrap().ty;
+                quote! {
+                    <#ty as sp1_stark::air::ColumnLayout>::layout_at(path, offset)
+                }
+            } else {
+                let fields = data.fields.iter().enumerate().map(|(i, field)| {
+                    let ty = &field.ty;
+                    let field_name =
+                        field.ident.as_ref().map_or_else(|| i.to_string(), ToString::to_string);
+                    quote! {
+                        layout.push_field::<#ty>(#field_name);
+                    }
+                });
+                quote! {
+                    let mut layout = sp1_stark::air::ColumnDescriptor::with_children(
+                        path,
+                        offset,
+                        std::vec::Vec::new(),
+                    );
+                    #(#fields)*
+                    layout
+                }
+            };
+
+            quote! {
+                #[allow(private_bounds)]
+                impl #layout_impl_generics sp1_stark::air::ColumnLayout for #name #type_generics #layout_where_clause {
+                    fn layout_at(path: &str, offset: usize) -> sp1_stark::air::ColumnDescriptor {
+                        #body
+                    }
+                }
+            }
+        }
+        _ => quote! {},
+    };
+
+    TokenStream::from(quote! {
+        #methods
+
+        #column_layout
+    })
 }
 
 #[proc_macro_derive(
@@ -184,6 +242,20 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                 }
             });
 
+            let column_layout_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as sp1_stark::air::MachineAir<F>>::column_layout(x)
+                }
+            });
+
+            let column_names_arms = variants.iter().map(|(variant_name, field)| {
+                let field_ty = &field.ty;
+                quote! {
+                    #name::#variant_name(x) => <#field_ty as sp1_stark::air::MachineAir<F>>::column_names(x)
+                }
+            });
+
             let commit_scope_arms = variants.iter().map(|(variant_name, field)| {
                 let field_ty = &field.ty;
                 quote! {
@@ -244,6 +316,18 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                         }
                     }
 
+                    fn column_layout(&self) -> Option<sp1_stark::air::ColumnDescriptor> {
+                        match self {
+                            #(#column_layout_arms,)*
+                        }
+                    }
+
+                    fn column_names(&self) -> Vec<String> {
+                        match self {
+                            #(#column_names_arms,)*
+                        }
+                    }
+
                     fn commit_scope(&self) -> InteractionScope {
                         match self {
                             #(#commit_scope_arms,)*
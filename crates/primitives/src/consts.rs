@@ -1,9 +1,44 @@
 /// The maximum size of the memory in bytes.
 pub const MAXIMUM_MEMORY_SIZE: u32 = u32::MAX;
 
+/// The address the stack of guest programs grows down from.
+///
+/// Must match the stack pointer set by the entrypoint of `sp1-zkvm`.
+pub const STACK_TOP: u32 = 0x0020_0400;
+
 /// The size of a word in bytes.
 pub const WORD_SIZE: usize = 4;
 
+/// The end of the memory of guest programs, which must be lower than the BabyBear prime.
+pub const MAX_MEMORY: u32 = 0x7800_0000;
+
+/// The address of the word holding the address of the table of input regions, or zero if there
+/// are none.
+///
+/// The table holds the number of regions, followed by the address and length in bytes of each,
+/// and the regions follow it up to this word. The heap of `sp1-zkvm` ends at the table, or at this
+/// word if there are no regions.
+pub const INPUT_REGIONS_PTR: u32 = MAX_MEMORY - WORD_SIZE as u32;
+
+/// The address of the table of input regions with the given lengths and the addresses of the
+/// regions, each word-aligned, or `None` if they don't fit above [`STACK_TOP`].
+pub fn input_regions_layout(lens: &[usize]) -> Option<(u32, Vec<u32>)> {
+    let table_len = (WORD_SIZE * (1 + 2 * lens.len())) as u64;
+    let regions_len =
+        lens.iter().map(|len| (*len as u64).next_multiple_of(WORD_SIZE as u64)).sum::<u64>();
+    let table = (INPUT_REGIONS_PTR as u64).checked_sub(table_len + regions_len)?;
+    if table < STACK_TOP as u64 {
+        return None;
+    }
+    let mut addr = table + table_len;
+    let mut addresses = Vec::with_capacity(lens.len());
+    for len in lens {
+        addresses.push(addr as u32);
+        addr += (*len as u64).next_multiple_of(WORD_SIZE as u64);
+    }
+    Some((table as u32, addresses))
+}
+
 /// Converts a slice of words to a byte vector in little endian.
 pub fn words_to_bytes_le_vec(words: &[u32]) -> Vec<u8> {
     words.iter().flat_map(|word| word.to_le_bytes().to_vec()).collect::<Vec<_>>()
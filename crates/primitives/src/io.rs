@@ -1,18 +1,66 @@
-use crate::types::Buffer;
+use std::collections::BTreeMap;
+
+use crate::{
+    channels::{input_region_channel, public_values_digest},
+    types::Buffer,
+};
 use num_bigint::BigUint;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+/// The file descriptor the guest writes named public values to.
+///
+/// Each write is a frame of the little-endian `u32` length of the channel name, the name, and the
+/// bytes to append to the channel.
+pub const FD_PUBLIC_VALUE_CHANNELS: u32 = 10;
+
+/// A named channel of public values, either with its bytes or only with their digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PublicValueChannel {
+    /// The bytes committed to the channel.
+    Revealed(Vec<u8>),
+    /// The SHA-256 digest of the bytes committed to the channel, which are kept private.
+    Hidden([u8; 32]),
+}
+
+impl PublicValueChannel {
+    /// The SHA-256 digest of the bytes committed to the channel.
+    pub fn digest(&self) -> [u8; 32] {
+        match self {
+            Self::Revealed(data) => Sha256::digest(data).into(),
+            Self::Hidden(digest) => *digest,
+        }
+    }
+}
+
 /// Public values for the prover.
+///
+/// If the program committed to named channels, the public values are Merkleized: the digest the
+/// proof commits to is the root of a tree whose leaves are the default stream, under the empty
+/// name, followed by the named channels in order of their names. Any named channel can then be
+/// hidden, leaving only its digest, without changing the root.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SP1PublicValues {
     buffer: Buffer,
+    channels: BTreeMap<String, PublicValueChannel>,
 }
 
 impl SP1PublicValues {
     /// Create a new `SP1PublicValues`.
     pub const fn new() -> Self {
-        Self { buffer: Buffer::new() }
+        Self { buffer: Buffer::new(), channels: BTreeMap::new() }
+    }
+
+    /// Create a `SP1PublicValues` from the default stream and the named channels.
+    pub fn from_channels(
+        data: &[u8],
+        channels: impl IntoIterator<Item = (String, Vec<u8>)>,
+    ) -> Self {
+        let channels = channels
+            .into_iter()
+            .map(|(name, data)| (name, PublicValueChannel::Revealed(data)))
+            .collect();
+        Self { buffer: Buffer::from(data), channels }
     }
 
     pub fn raw(&self) -> String {
@@ -21,7 +69,7 @@ impl SP1PublicValues {
 
     /// Create a `SP1PublicValues` from a slice of bytes.
     pub fn from(data: &[u8]) -> Self {
-        Self { buffer: Buffer::from(data) }
+        Self { buffer: Buffer::from(data), channels: BTreeMap::new() }
     }
 
     pub fn as_slice(&self) -> &[u8] {
@@ -52,25 +100,85 @@ impl SP1PublicValues {
         self.buffer.write_slice(slice);
     }
 
+    /// The named channel, if the program committed to it.
+    pub fn channel(&self, name: &str) -> Option<&PublicValueChannel> {
+        self.channels.get(name)
+    }
+
+    /// The names of the channels, in order.
+    pub fn channel_names(&self) -> impl Iterator<Item = &str> {
+        self.channels.keys().map(String::as_str)
+    }
+
+    /// Read a value from the start of the named channel, or return `None` if it is missing or
+    /// hidden.
+    pub fn read_channel<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        match self.channels.get(name)? {
+            PublicValueChannel::Revealed(data) => {
+                Some(bincode::deserialize(data).expect("failed to deserialize"))
+            }
+            PublicValueChannel::Hidden(_) => None,
+        }
+    }
+
+    /// The digest of the input region with the given index, which the program commits to the
+    /// first time it gets the region, or `None` if it didn't or the channel is hidden.
+    pub fn input_region_digest(&self, index: usize) -> Option<[u8; 32]> {
+        match self.channels.get(&input_region_channel(index))? {
+            PublicValueChannel::Revealed(data) => data.as_slice().try_into().ok(),
+            PublicValueChannel::Hidden(_) => None,
+        }
+    }
+
+    /// Replace the bytes of the named channel with their digest.
+    pub fn hide_channel(&mut self, name: &str) {
+        if let Some(channel) = self.channels.get_mut(name) {
+            *channel = PublicValueChannel::Hidden(channel.digest());
+        }
+    }
+
+    /// A copy of the public values with all channels but the given ones hidden. The default
+    /// stream stays revealed.
+    pub fn reveal_only(&self, names: &[&str]) -> Self {
+        let mut public_values = self.clone();
+        for channel in self.channel_names().filter(|name| !names.contains(name)) {
+            public_values.hide_channel(channel);
+        }
+        public_values
+    }
+
+    /// Whether the program committed to named channels, which makes the digest of the public
+    /// values a Merkle root.
+    pub fn has_channels(&self) -> bool {
+        !self.channels.is_empty()
+    }
+
     /// Hash the public values.
+    ///
+    /// Without named channels, this is the SHA-256 hash of the stream. Otherwise it is the root of
+    /// the Merkle tree of the channels, which hidden channels contribute their digest to.
     pub fn hash(&self) -> Vec<u8> {
-        let mut hasher = Sha256::new();
-        hasher.update(self.buffer.data.as_slice());
-        hasher.finalize().to_vec()
+        let stream_digest = Sha256::digest(self.buffer.data.as_slice()).into();
+        let channels =
+            self.channels.iter().map(|(name, channel)| (name.as_str(), channel.digest()));
+        public_values_digest(stream_digest, channels).to_vec()
     }
 
-    /// Hash the public values, mask the top 3 bits and return a BigUint. Matches the implementation
-    /// of `hashPublicValues` in the Solidity verifier.
+    /// Hash the public values, mask the top 3 bits and return a BigUint.
+    ///
+    /// Without named channels, this matches the implementation of `hashPublicValues` in the
+    /// Solidity verifier:
     ///
     /// ```solidity
     /// sha256(publicValues) & bytes32(uint256((1 << 253) - 1));
     /// ```
+    ///
+    /// With named channels, the hash is the masked Merkle root of [`Self::hash`], which the
+    /// Solidity verifier doesn't compute, so the SDK refuses to make Plonk and Groth16 proofs of
+    /// such public values.
     pub fn hash_bn254(&self) -> BigUint {
         // Hash the public values.
-        let mut hasher = Sha256::new();
-        hasher.update(self.buffer.data.as_slice());
-        let hash_result = hasher.finalize();
-        let mut hash = hash_result.to_vec();
+        let mut hash = self.hash();
 
         // Mask the top 3 bits.
         hash[0] &= 0b00011111;
@@ -104,4 +212,37 @@ mod tests {
 
         assert_eq!(hash, expected_hash_biguint);
     }
+
+    #[test]
+    fn test_hash_channels() {
+        let public_values = SP1PublicValues::from_channels(
+            b"abc",
+            [("owner".to_string(), b"alice".to_vec()), ("balance".to_string(), vec![1, 2])],
+        );
+        let expected = "b8970fd89d05a8f4f45e5ad4a5dd1b6926af8cf7e5f61c8705002dc73a82fae6";
+        assert_eq!(hex::encode(public_values.hash()), expected);
+        assert_ne!(public_values.hash(), SP1PublicValues::from(b"abc").hash());
+
+        // Hiding a channel keeps the hash.
+        let revealed = public_values.reveal_only(&["owner"]);
+        assert_eq!(revealed.hash(), public_values.hash());
+        assert_eq!(
+            revealed.channel("owner"),
+            Some(&PublicValueChannel::Revealed(b"alice".to_vec()))
+        );
+        assert!(matches!(revealed.channel("balance"), Some(PublicValueChannel::Hidden(_))));
+        assert_eq!(revealed.read_channel::<[u8; 2]>("balance"), None);
+    }
+
+    #[test]
+    fn test_input_region_digest() {
+        let digest = Sha256::digest(b"hello").into();
+        let public_values = SP1PublicValues::from_channels(
+            b"",
+            [(input_region_channel(0), Sha256::digest(b"hello").to_vec())],
+        );
+        assert_eq!(public_values.input_region_digest(0), Some(digest));
+        assert_eq!(public_values.input_region_digest(1), None);
+        assert_eq!(public_values.reveal_only(&[]).input_region_digest(0), None);
+    }
 }
@@ -10,6 +10,8 @@ pub mod consts;
 pub mod io;
 pub mod types;
 
+pub use sp1_channels as channels;
+
 lazy_static! {
     // These constants are created by a RNG.
 
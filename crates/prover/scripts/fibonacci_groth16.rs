@@ -60,6 +60,7 @@ fn main() {
             buffer: vec![bincode::serialize::<u32>(&iterations).unwrap()],
             ptr: 0,
             proofs: vec![],
+            regions: vec![],
         };
         let leaf_proving_start = Instant::now();
         let proof = prover
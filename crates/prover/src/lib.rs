@@ -26,13 +26,15 @@ use std::{
     path::Path,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        mpsc::sync_channel,
+        mpsc::{sync_channel, RecvTimeoutError},
         Arc, Mutex, OnceLock,
     },
     thread,
+    time::Duration,
 };
 
 use lru::LruCache;
+use rayon::prelude::*;
 
 use tracing::instrument;
 
@@ -41,7 +43,10 @@ use p3_baby_bear::BabyBear;
 use p3_challenger::CanObserve;
 use p3_field::{AbstractField, PrimeField, PrimeField32};
 use p3_matrix::dense::RowMajorMatrix;
-use sp1_core_executor::{ExecutionError, ExecutionReport, Executor, Program, SP1Context};
+use sp1_core_executor::{
+    DebugInfo, ExecutionError, ExecutionReport, Executor, ExecutorPool, Program, ProverEvent,
+    ProverObserver, SP1Context,
+};
 use sp1_core_machine::{
     io::SP1Stdin,
     reduce::SP1ReduceProof,
@@ -85,6 +90,7 @@ pub use types::*;
 use utils::{sp1_commited_values_digest_bn254, sp1_vkey_digest_bn254, words_to_bytes};
 
 use components::{DefaultProverComponents, SP1ProverComponents};
+use shapes::{VkMap, VkMapError};
 
 pub use sp1_core_machine::SP1_CIRCUIT_VERSION;
 
@@ -105,13 +111,8 @@ const CORE_CACHE_SIZE: usize = 5;
 const COMPRESS_CACHE_SIZE: usize = 3;
 pub const REDUCE_BATCH_SIZE: usize = 2;
 
-// TODO: FIX
-//
-// const SHAPES_URL_PREFIX: &str = "https://sp1-circuits.s3.us-east-2.amazonaws.com/shapes";
-// const SHAPES_VERSION: &str = "146079e0e";
-// lazy_static! {
-//     static ref SHAPES_INIT: Once = Once::new();
-// }
+/// How often the compress workers waiting for proofs check whether proving was interrupted.
+const INTERRUPTION_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
 pub type CompressAir<F> = RecursionAir<F, COMPRESS_DEGREE>;
 pub type ShrinkAir<F> = RecursionAir<F, SHRINK_DEGREE>;
@@ -161,48 +162,13 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
     /// Initializes a new [SP1Prover].
     #[instrument(name = "initialize prover", level = "debug", skip_all)]
     pub fn new() -> Self {
-        // TODO: Enable
-        //
-        // Initialize the shapes (only once).
-        // SHAPES_INIT.call_once(|| {
-        //     let shapes_dir = Self::shapes_dir();
-        //     if !shapes_dir.exists() {
-        //         std::fs::create_dir_all(&shapes_dir).expect("failed to create shapes directory");
-
-        //         let url = format!("{}-{}/allowed_vk_map.bin", SHAPES_URL_PREFIX, SHAPES_VERSION);
-        //         let allowed_vk_map_bytes = reqwest::blocking::get(url)
-        //             .expect("failed to download allowed_vk_map.bin")
-        //             .bytes()
-        //             .expect("failed to read response body");
-        //         let allowed_vk_map_bytes = allowed_vk_map_bytes.as_ref().to_vec();
-        //         std::fs::write(shapes_dir.join("allowed_vk_map.bin"), allowed_vk_map_bytes)
-        //             .unwrap();
-
-        //         let url = format!("{}-{}/dummy_vk_map.bin", SHAPES_URL_PREFIX, SHAPES_VERSION);
-        //         let dummy_vk_map_bytes = reqwest::blocking::get(url)
-        //             .expect("failed to download dummy_vk_map.bin")
-        //             .bytes()
-        //             .expect("failed to read response body");
-        //         let dummy_vk_map_bytes = dummy_vk_map_bytes.as_ref().to_vec();
-        //         std::fs::write(shapes_dir.join("dummy_vk_map.bin"), dummy_vk_map_bytes).unwrap();
-        //     }
-        // });
-
         Self::uninitialized()
     }
 
-    // TODO: FIX
-    //
-    // Get the shapes directory.
-    // pub fn shapes_dir() -> PathBuf {
-    //     dirs::home_dir()
-    //         .expect("failed to get home directory")
-    //         .join(".sp1")
-    //         .join("shapes")
-    //         .join(SHAPES_VERSION)
-    // }
-
     /// Creates a new [SP1Prover] with lazily initialized components.
+    ///
+    /// If `VERIFY_VK=true`, the allowed vk map is read from [shapes::vk_map_dir] and its root must
+    /// match the one pinned with `VK_ROOT`. Otherwise the bundled dummy vk map is used.
     pub fn uninitialized() -> Self {
         // Initialize the provers.
         let core_machine = RiscvAir::machine(CoreSC::default());
@@ -249,23 +215,23 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
 
         tracing::info!("vk verification: {}", vk_verification);
 
-        // Read the shapes from the shapes directory and deserialize them into memory.
-        let allowed_vk_map: BTreeMap<[BabyBear; DIGEST_SIZE], usize> = if vk_verification {
-            unreachable!()
-            // TODO: FIX
-            //
-            // let vk_allowed_map_bytes =
-            //     std::fs::read(Self::shapes_dir().join("allowed_vk_map.bin")).unwrap();
-            // bincode::deserialize(&vk_allowed_map_bytes).unwrap()
+        // Read the vk map and check it against the pinned root.
+        let VkMap { map: allowed_vk_map, root, tree: merkle_tree } = if vk_verification {
+            let path = shapes::vk_map_dir().join(shapes::ALLOWED_VK_MAP_FILE);
+            let vk_map = VkMap::read(&path).unwrap_or_else(|e| {
+                panic!("failed to read the allowed vk map from {}: {}", path.display(), e)
+            });
+            let pinned_root = shapes::pinned_vk_root()
+                .and_then(|root| root.ok_or(VkMapError::MissingPinnedRoot))
+                .unwrap_or_else(|e| panic!("{}", e));
+            vk_map.check_root(&pinned_root).unwrap_or_else(|e| panic!("{}", e));
+            vk_map
         } else {
-            // TODO: FIX
-            //
-            // let vk_dummy_map_bytes =
-            //     std::fs::read(Self::shapes_dir().join("dummy_vk_map.bin")).unwrap();
-            bincode::deserialize(include_bytes!("../dummy_vk_map.bin")).unwrap()
+            let dummy_vk_map: BTreeMap<[BabyBear; DIGEST_SIZE], usize> =
+                bincode::deserialize(include_bytes!("../dummy_vk_map.bin")).unwrap();
+            VkMap::new(dummy_vk_map).unwrap()
         };
-
-        let (root, merkle_tree) = MerkleTree::commit(allowed_vk_map.keys().copied().collect());
+        tracing::info!("vk root: {}", shapes::format_digest(&root));
 
         Self {
             core_prover,
@@ -323,11 +289,55 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         let opts = SP1CoreOpts::default();
         let mut runtime = Executor::with_context(program, opts, context);
         runtime.write_vecs(&stdin.buffer);
+        Self::run_stdin(&mut runtime, elf, stdin)
+    }
+
+    /// Execute an SP1 program once for each of the inputs, in parallel, reusing the runtimes of the
+    /// runs that are done for the next ones.
+    ///
+    /// Each run gets a context of its own from `context`, and the result of each run is in the
+    /// order of the inputs.
+    #[instrument(name = "execute_batch", level = "info", skip_all)]
+    pub fn execute_batch<'a>(
+        &'a self,
+        elf: &[u8],
+        stdins: &[SP1Stdin],
+        context: impl Fn() -> SP1Context<'a> + Sync,
+    ) -> Vec<Result<(SP1PublicValues, ExecutionReport), ExecutionError>> {
+        let program = self.get_program(elf).unwrap();
+        let pool = ExecutorPool::new(program, SP1CoreOpts::default());
+        stdins
+            .par_iter()
+            .map(|stdin| {
+                let mut context = context();
+                context.subproof_verifier.replace(Arc::new(self));
+                Self::run_stdin(&mut pool.get(context, &stdin.buffer), elf, stdin)
+            })
+            .collect()
+    }
+
+    /// Write the regions and proofs of `stdin` to a runtime that has its input stream, and
+    /// execute the program without tracing.
+    fn run_stdin(
+        runtime: &mut Executor<'_>,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+    ) -> Result<(SP1PublicValues, ExecutionReport), ExecutionError> {
+        runtime.write_regions(&stdin.regions);
         for (proof, vkey) in stdin.proofs.iter() {
             runtime.write_proof(proof.clone(), vkey.clone());
         }
-        runtime.run_fast()?;
-        Ok((SP1PublicValues::from(&runtime.state.public_values_stream), runtime.report))
+        if let Err(error) = runtime.run_fast() {
+            // The program is loaded without its debug info, so the backtrace of a failed halt is
+            // described from the ELF.
+            let Some(report) = runtime.halt_report() else { return Err(error) };
+            let mut report = report.clone();
+            if let Ok(debug_info) = DebugInfo::decode(elf) {
+                report.symbolize(&debug_info);
+            }
+            return Err(ExecutionError::HaltWithReport(Box::new(report)));
+        }
+        Ok((runtime.public_values(), std::mem::take(&mut runtime.report)))
     }
 
     /// Generate shard proofs which split up and prove the valid execution of a RISC-V program with
@@ -342,7 +352,7 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
     ) -> Result<SP1CoreProof, SP1CoreProverError> {
         context.subproof_verifier.replace(Arc::new(self));
         let program = self.get_program(&pk.elf).unwrap();
-        let (proof, public_values_stream, cycles) = sp1_core_machine::utils::prove_with_context::<
+        let (proof, public_values, cycles) = sp1_core_machine::utils::prove_with_context::<
             _,
             C::CoreProver,
         >(
@@ -357,7 +367,6 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
             self.core_shape_config.as_ref(),
         )?;
         Self::check_for_high_cycles(cycles);
-        let public_values = SP1PublicValues::from(&public_values_stream);
         Ok(SP1CoreProof {
             proof: SP1CoreProofData(proof.shard_proofs),
             stdin: stdin.clone(),
@@ -663,7 +672,6 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
     }
 
     /// Reduce shards proofs to a single shard proof using the recursion prover.
-    #[instrument(name = "compress", level = "info", skip_all)]
     pub fn compress(
         &self,
         vk: &SP1VerifyingKey,
@@ -671,6 +679,22 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
         opts: SP1ProverOpts,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        self.compress_with_observer(vk, proof, deferred_proofs, opts, &ProverObserver::default())
+    }
+
+    /// Reduce shards proofs to a single shard proof using the recursion prover, reporting the
+    /// progress of each layer to `observer` and stopping early if it is cancelled or times out.
+    #[instrument(name = "compress", level = "info", skip_all)]
+    pub fn compress_with_observer(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: SP1CoreProof,
+        deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+        opts: SP1ProverOpts,
+        observer: &ProverObserver,
+    ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        observer.start();
+
         // The batch size for reducing two layers of recursion.
         let batch_size = REDUCE_BATCH_SIZE;
         // The batch size for reducing the first layer of recursion.
@@ -704,6 +728,12 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
             expected_height += 1;
         }
 
+        // Calculate the number of proofs in each layer of the tree.
+        let mut layer_sizes = vec![num_first_layer_inputs];
+        while layer_sizes.len() <= expected_height {
+            layer_sizes.push(layer_sizes.last().unwrap().div_ceil(batch_size));
+        }
+
         // Generate the proofs.
         let span = tracing::Span::current().clone();
         let (vk, proof) = thread::scope(|s| {
@@ -721,7 +751,11 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                 s.spawn(move || {
                     for (index, input) in first_layer_inputs.into_iter().enumerate() {
                         input_sync.wait_for_turn(index);
-                        input_tx.lock().unwrap().send((index, 0, input)).unwrap();
+                        // Keep advancing the turns after an interruption so that the next layer
+                        // worker never waits on an input that will not be sent.
+                        if !observer.is_interrupted() {
+                            input_tx.lock().unwrap().send((index, 0, input)).unwrap();
+                        }
                         input_sync.advance_turn();
                     }
                 });
@@ -750,6 +784,13 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                     loop {
                         let received = { input_rx.lock().unwrap().recv() };
                         if let Ok((index, height, input)) = received {
+                            // If proving was interrupted, drain the input without doing any work.
+                            if observer.is_interrupted() {
+                                record_and_trace_sync.wait_for_turn(index);
+                                record_and_trace_sync.advance_turn();
+                                continue;
+                            }
+
                             // Get the program and witness stream.
                             let (program, witness_stream) = tracing::debug_span!(
                                 "get program and witness stream"
@@ -851,6 +892,13 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                     loop {
                         let received = { record_and_trace_rx.lock().unwrap().recv() };
                         if let Ok((index, height, program, record, traces)) = received {
+                            // If proving was interrupted, drain the records without proving them.
+                            if observer.is_interrupted() {
+                                prover_sync.wait_for_turn(index);
+                                prover_sync.advance_turn();
+                                continue;
+                            }
+
                             tracing::debug_span!("batch").in_scope(|| {
                                 // Get the keys.
                                 let (pk, vk) = tracing::debug_span!("Setup compress program")
@@ -863,11 +911,14 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                                 });
 
                                 #[cfg(feature = "debug")]
-                                self.compress_prover.debug_constraints(
-                                    &pk.to_host(),
-                                    vec![record.clone()],
-                                    &mut challenger.clone(),
-                                );
+                                {
+                                    let report = self.compress_prover.debug_constraints(
+                                        &pk.to_host(),
+                                        vec![record.clone()],
+                                        &mut challenger.clone(),
+                                    );
+                                    assert!(report.is_ok(), "{report}");
+                                }
 
                                 // Commit to the record and traces.
                                 let local_data = tracing::debug_span!("commit")
@@ -940,9 +991,30 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                         StarkVerifyingKey<InnerSC>,
                         ShardProof<InnerSC>,
                     )> = Vec::new();
+                    let mut num_proofs_per_layer = vec![0; layer_sizes.len()];
                     loop {
-                        let received = { proofs_rx.lock().unwrap().recv() };
+                        // Poll for proofs so that an interruption is noticed even if no more
+                        // proofs are coming. Exiting drops this worker's input sender, which lets
+                        // the other workers drain and exit.
+                        let received =
+                            { proofs_rx.lock().unwrap().recv_timeout(INTERRUPTION_POLL_INTERVAL) };
+                        if observer.is_interrupted() {
+                            break;
+                        }
+                        let received = match received {
+                            Err(RecvTimeoutError::Timeout) => continue,
+                            received => received,
+                        };
                         if let Ok((index, height, vk, proof)) = received {
+                            // Report the layer once all of its proofs have been generated.
+                            num_proofs_per_layer[height] += 1;
+                            if num_proofs_per_layer[height] == layer_sizes[height] {
+                                observer.emit(ProverEvent::CompressLayerFinished {
+                                    layer: height,
+                                    num_proofs: layer_sizes[height],
+                                });
+                            }
+
                             batch.push((index, height, vk, proof));
 
                             // Compute whether we've reached the root of the tree.
@@ -1012,9 +1084,12 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
             }
             handle.join().unwrap();
 
-            let (_, _, vk, proof) = proofs_rx.lock().unwrap().recv().unwrap();
-            (vk, proof)
-        });
+            observer.check()?;
+
+            let (_, height, vk, proof) = proofs_rx.lock().unwrap().recv().unwrap();
+            observer.emit(ProverEvent::CompressLayerFinished { layer: height, num_proofs: 1 });
+            Ok::<_, SP1RecursionProverError>((vk, proof))
+        })?;
 
         Ok(SP1ReduceProof { vk, proof })
     }
@@ -1,25 +1,36 @@
 use std::{
     collections::{BTreeMap, BTreeSet, HashSet},
+    env,
+    fmt::Write,
     fs::File,
     panic::{catch_unwind, AssertUnwindSafe},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
 use thiserror::Error;
 
 use p3_baby_bear::BabyBear;
-use p3_field::AbstractField;
+use p3_field::{AbstractField, PrimeField32};
 use serde::{Deserialize, Serialize};
-use sp1_core_machine::riscv::CoreShapeConfig;
-use sp1_recursion_circuit::machine::{
-    SP1CompressWithVKeyWitnessValues, SP1CompressWithVkeyShape, SP1DeferredShape,
-    SP1DeferredWitnessValues, SP1RecursionShape, SP1RecursionWitnessValues,
+use sp1_core_machine::{riscv::CoreShapeConfig, SP1_CIRCUIT_VERSION};
+use sp1_recursion_circuit::{
+    machine::{
+        SP1CompressWithVKeyWitnessValues, SP1CompressWithVkeyShape, SP1DeferredShape,
+        SP1DeferredWitnessValues, SP1RecursionShape, SP1RecursionWitnessValues,
+    },
+    merkle_tree::{MerkleProof, MerkleTree},
 };
 use sp1_recursion_core::{shape::RecursionShapeConfig, RecursionProgram};
 use sp1_stark::{MachineProver, ProofShape, DIGEST_SIZE};
 
-use crate::{components::SP1ProverComponents, CompressAir, HashableKey, SP1Prover};
+use crate::{components::SP1ProverComponents, CompressAir, HashableKey, InnerSC, SP1Prover};
+
+/// The name of the file containing the allowed vk map.
+pub const ALLOWED_VK_MAP_FILE: &str = "allowed_vk_map.bin";
+
+/// The name of the file containing the dummy vk map.
+pub const DUMMY_VK_MAP_FILE: &str = "dummy_vk_map.bin";
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum SP1ProofShape {
@@ -45,6 +56,112 @@ pub enum VkBuildError {
     Bincode(#[from] bincode::Error),
 }
 
+#[derive(Debug, Error)]
+pub enum VkMapError {
+    #[error("IO error: {0}")]
+    IO(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    Bincode(#[from] bincode::Error),
+    #[error("the vk map is empty")]
+    Empty,
+    #[error("invalid digest {0}: expected 8 hex encoded BabyBear elements")]
+    InvalidDigest(String),
+    #[error("vk map root {actual} does not match the pinned root {expected}")]
+    RootMismatch { expected: String, actual: String },
+    #[error("VK_ROOT must be set to the pinned root of the allowed vk map when VERIFY_VK=true")]
+    MissingPinnedRoot,
+}
+
+/// A map from the allowed recursion vk digests to their index, with the Merkle tree committing to
+/// them.
+pub struct VkMap {
+    pub map: BTreeMap<[BabyBear; DIGEST_SIZE], usize>,
+    pub root: [BabyBear; DIGEST_SIZE],
+    pub tree: MerkleTree<BabyBear, InnerSC>,
+}
+
+impl VkMap {
+    /// Commit to the given vk map.
+    pub fn new(map: BTreeMap<[BabyBear; DIGEST_SIZE], usize>) -> Result<Self, VkMapError> {
+        if map.is_empty() {
+            return Err(VkMapError::Empty);
+        }
+        let (root, tree) = MerkleTree::commit(map.keys().copied().collect());
+        Ok(Self { map, root, tree })
+    }
+
+    /// Read a vk map written by [build_vk_map_to_file].
+    pub fn read(path: &Path) -> Result<Self, VkMapError> {
+        let file = File::open(path)?;
+        Self::new(bincode::deserialize_from(file)?)
+    }
+
+    /// Open the Merkle proof of a vk digest, or return `None` if the vk is not in the map.
+    pub fn open(
+        &self,
+        vk_digest: &[BabyBear; DIGEST_SIZE],
+    ) -> Option<MerkleProof<BabyBear, InnerSC>> {
+        let index = *self.map.get(vk_digest)?;
+        let (_, proof) = self.tree.open(index);
+        Some(proof)
+    }
+
+    /// Check that the vk map commits to the `expected` root.
+    pub fn check_root(&self, expected: &[BabyBear; DIGEST_SIZE]) -> Result<(), VkMapError> {
+        if &self.root != expected {
+            return Err(VkMapError::RootMismatch {
+                expected: format_digest(expected),
+                actual: format_digest(&self.root),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The directory the vk maps are stored in.
+///
+/// Defaults to `~/.sp1/shapes/<circuit version>` and can be overridden with `VK_MAP_DIR`.
+pub fn vk_map_dir() -> PathBuf {
+    env::var("VK_MAP_DIR").map(PathBuf::from).unwrap_or_else(|_| {
+        dirs::home_dir()
+            .expect("failed to get home directory")
+            .join(".sp1")
+            .join("shapes")
+            .join(SP1_CIRCUIT_VERSION)
+    })
+}
+
+/// The pinned root of the allowed vk map, read from `VK_ROOT`.
+pub fn pinned_vk_root() -> Result<Option<[BabyBear; DIGEST_SIZE]>, VkMapError> {
+    env::var("VK_ROOT").ok().map(|root| parse_digest(&root)).transpose()
+}
+
+/// Format a digest as the hex encoding of its big-endian words.
+pub fn format_digest(digest: &[BabyBear; DIGEST_SIZE]) -> String {
+    digest.iter().fold("0x".to_string(), |mut hex, x| {
+        let _ = write!(hex, "{:08x}", x.as_canonical_u32());
+        hex
+    })
+}
+
+/// Parse a digest formatted with [format_digest].
+pub fn parse_digest(s: &str) -> Result<[BabyBear; DIGEST_SIZE], VkMapError> {
+    let invalid = || VkMapError::InvalidDigest(s.to_string());
+    let hex = s.strip_prefix("0x").unwrap_or(s);
+    if hex.len() != DIGEST_SIZE * 8 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let mut digest = [BabyBear::zero(); DIGEST_SIZE];
+    for (i, word) in digest.iter_mut().enumerate() {
+        let value = u32::from_str_radix(&hex[i * 8..(i + 1) * 8], 16).map_err(|_| invalid())?;
+        if value >= BabyBear::ORDER_U32 {
+            return Err(invalid());
+        }
+        *word = BabyBear::from_canonical_u32(value);
+    }
+    Ok(digest)
+}
+
 pub fn build_vk_map<C: SP1ProverComponents>(
     reduce_batch_size: usize,
     dummy: bool,
@@ -199,11 +316,17 @@ pub fn build_vk_map_to_file<C: SP1ProverComponents>(
 
     tracing::info!("Save the vk set to file");
     let mut file = if dummy {
-        File::create(build_dir.join("dummy_vk_map.bin"))?
+        File::create(build_dir.join(DUMMY_VK_MAP_FILE))?
     } else {
-        File::create(build_dir.join("vk_map.bin"))?
+        File::create(build_dir.join(ALLOWED_VK_MAP_FILE))?
     };
-    Ok(bincode::serialize_into(&mut file, &vk_map)?)
+    bincode::serialize_into(&mut file, &vk_map)?;
+
+    // Log the root so that it can be pinned with `VK_ROOT`.
+    if let Ok(vk_map) = VkMap::new(vk_map) {
+        tracing::info!("vk map root: {}", format_digest(&vk_map.root));
+    }
+    Ok(())
 }
 
 impl SP1ProofShape {
@@ -293,6 +416,29 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_digest_roundtrip() {
+        let digest: [BabyBear; DIGEST_SIZE] =
+            std::array::from_fn(|i| BabyBear::from_canonical_usize(i * 0x1234567));
+        assert_eq!(parse_digest(&format_digest(&digest)).unwrap(), digest);
+        assert!(parse_digest("0x1234").is_err());
+        assert!(parse_digest(&format!("0x{}", "f".repeat(64))).is_err());
+    }
+
+    #[test]
+    fn test_vk_map_membership() {
+        let map = (0..5)
+            .map(|i| ([BabyBear::from_canonical_usize(i); DIGEST_SIZE], i))
+            .collect::<BTreeMap<_, _>>();
+        let vk_map = VkMap::new(map).unwrap();
+        vk_map.check_root(&vk_map.root).unwrap();
+
+        let digest = [BabyBear::from_canonical_usize(3); DIGEST_SIZE];
+        let proof = vk_map.open(&digest).unwrap();
+        MerkleTree::verify(proof, digest, vk_map.root).unwrap();
+        assert!(vk_map.open(&[BabyBear::from_canonical_usize(7); DIGEST_SIZE]).is_none());
+    }
+
     #[test]
     #[ignore]
     fn test_generate_all_shapes() {
@@ -102,6 +102,7 @@ pub fn get_cycles(elf: &[u8], stdin: &SP1Stdin) -> u64 {
     let program = Program::from(elf).unwrap();
     let mut runtime = Executor::new(program, SP1CoreOpts::default());
     runtime.write_vecs(&stdin.buffer);
+    runtime.write_regions(&stdin.regions);
     runtime.run_fast().unwrap();
     runtime.state.global_clk
 }
@@ -36,6 +36,8 @@ pub enum PlonkVerificationError {
         "the public values in the sp1 proof do not match the public values in the inner plonk bn254 proof"
     )]
     InvalidPublicValues,
+    #[error("plonk bn254 proofs don't support named public value channels")]
+    UnsupportedChannels,
 }
 
 #[derive(Error, Debug)]
@@ -48,6 +50,8 @@ pub enum Groth16VerificationError {
         "the public values in the sp1 proof do not match the public values in the inner groth16 bn254 proof"
     )]
     InvalidPublicValues,
+    #[error("groth16 bn254 proofs don't support named public value channels")]
+    UnsupportedChannels,
 }
 
 impl<C: SP1ProverComponents> SP1Prover<C> {
@@ -425,6 +429,9 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
 
 /// Verify the vk_hash and public_values_hash in the public inputs of the PlonkBn254Proof match the
 /// expected values.
+///
+/// The Solidity verifier hashes the public values as a single stream, so the public values can't
+/// have named channels.
 pub fn verify_plonk_bn254_public_inputs(
     vk: &SP1VerifyingKey,
     public_values: &SP1PublicValues,
@@ -438,6 +445,10 @@ pub fn verify_plonk_bn254_public_inputs(
         return Err(PlonkVerificationError::InvalidVerificationKey.into());
     }
 
+    if public_values.has_channels() {
+        return Err(PlonkVerificationError::UnsupportedChannels.into());
+    }
+
     let public_values_hash = public_values.hash_bn254();
     if public_values_hash != expected_public_values_hash {
         return Err(PlonkVerificationError::InvalidPublicValues.into());
@@ -448,6 +459,9 @@ pub fn verify_plonk_bn254_public_inputs(
 
 /// Verify the vk_hash and public_values_hash in the public inputs of the Groth16Bn254Proof match
 /// the expected values.
+///
+/// The Solidity verifier hashes the public values as a single stream, so the public values can't
+/// have named channels.
 pub fn verify_groth16_bn254_public_inputs(
     vk: &SP1VerifyingKey,
     public_values: &SP1PublicValues,
@@ -461,6 +475,10 @@ pub fn verify_groth16_bn254_public_inputs(
         return Err(Groth16VerificationError::InvalidVerificationKey.into());
     }
 
+    if public_values.has_channels() {
+        return Err(Groth16VerificationError::UnsupportedChannels.into());
+    }
+
     let public_values_hash = public_values.hash_bn254();
     if public_values_hash != expected_public_values_hash {
         return Err(Groth16VerificationError::InvalidPublicValues.into());
@@ -1,11 +1,27 @@
-use sp1_core_executor::{ExecutionReport, HookEnv, SP1ContextBuilder};
-use sp1_core_machine::io::SP1Stdin;
+use sp1_core_executor::{
+    syscalls::{Syscall, SyscallCode},
+    CancellationToken, ExecutionReport, HookEnv, InputProvider, ProverEvent, SP1ContextBuilder,
+};
+use sp1_core_machine::{io::SP1Stdin, utils::MemoryBudget};
 use sp1_primitives::io::SP1PublicValues;
 use sp1_prover::{components::DefaultProverComponents, SP1ProvingKey};
 
 use anyhow::{Ok, Result};
+use serde::{de::DeserializeOwned, Serialize};
 use sp1_stark::{SP1CoreOpts, SP1ProverOpts};
-use std::time::Duration;
+use std::{
+    future::Future,
+    io::Write,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        mpsc::{channel, Receiver, TryRecvError},
+        Arc, Condvar, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    thread::Scope,
+    time::Duration,
+};
 
 use crate::{provers::ProofOpts, Prover, SP1ProofKind, SP1ProofWithPublicValues};
 
@@ -32,6 +48,11 @@ impl<'a> Execute<'a> {
     }
 
     /// Execute the program on the input, consuming the built action `self`.
+    ///
+    /// If the program misbehaves, for example by reading more input than was written, the error
+    /// is an [ExecutionError](crate::ExecutionError) with the pc of the faulting instruction. If
+    /// the program panics, the error is an `ExecutionError::HaltWithReport` with its message,
+    /// source location and backtrace.
     pub fn run(self) -> Result<(SP1PublicValues, ExecutionReport)> {
         let Self { prover, elf, stdin, mut context_builder } = self;
         let context = context_builder.build();
@@ -52,6 +73,30 @@ impl<'a> Execute<'a> {
         self
     }
 
+    /// Add a runtime hook whose result is computed by a future, like a query to a database.
+    ///
+    /// The future is polled to completion on the thread executing the program.
+    pub fn with_async_hook<Fut: Future<Output = Vec<Vec<u8>>> + 'a>(
+        mut self,
+        fd: u32,
+        f: impl FnMut(HookEnv, &[u8]) -> Fut + Send + Sync + 'a,
+    ) -> Self {
+        self.context_builder.async_hook(fd, f);
+        self
+    }
+
+    /// Add a runtime hook from a deserializable request to a serializable response.
+    ///
+    /// The hook may be called from within SP1 with [`sp1_zkvm::io::hook`], using the same types.
+    pub fn with_typed_hook<Req: DeserializeOwned + 'a, Resp: Serialize + 'a>(
+        mut self,
+        fd: u32,
+        f: impl FnMut(HookEnv, Req) -> Resp + Send + Sync + 'a,
+    ) -> Self {
+        self.context_builder.typed_hook(fd, f);
+        self
+    }
+
     /// Avoid registering the default hooks in the runtime.
     ///
     /// It is not necessary to call this to override hooks --- instead, simply
@@ -61,10 +106,49 @@ impl<'a> Execute<'a> {
         self
     }
 
+    /// Pull the input items that come after the ones in `stdin` from `provider`, once the
+    /// program has read all of the input so far.
+    pub fn with_input_provider(mut self, provider: impl InputProvider + 'a) -> Self {
+        self.context_builder.input_provider(provider);
+        self
+    }
+
+    /// Write the program's stdout to `sink` instead of printing it.
+    pub fn stdout(mut self, sink: impl Write + Send + 'a) -> Self {
+        self.context_builder.stdout(sink);
+        self
+    }
+
+    /// Write the program's stderr to `sink` instead of printing it.
+    pub fn stderr(mut self, sink: impl Write + Send + 'a) -> Self {
+        self.context_builder.stderr(sink);
+        self
+    }
+
+    /// Also keep what the program writes to stdout and stderr in the `stdout` and `stderr` fields
+    /// of the report.
+    ///
+    /// The output is buffered in memory until the execution ends, so prefer [Self::stdout] and
+    /// [Self::stderr] for programs that write a lot.
+    pub fn capture_output(mut self) -> Self {
+        self.context_builder.capture_output();
+        self
+    }
+
+    /// Handle the syscall `code` on the host with `syscall`.
+    ///
+    /// `code` must be one of the reserved codes [SyscallCode::HOST_0] to [SyscallCode::HOST_3].
+    /// A program that invokes it can be executed, but proving it fails with
+    /// [ExecutionError::UnprovableSyscall](crate::ExecutionError::UnprovableSyscall).
+    pub fn with_syscall(mut self, code: SyscallCode, syscall: Arc<dyn Syscall>) -> Self {
+        self.context_builder.syscall(code, syscall);
+        self
+    }
+
     /// Set the maximum number of cpu cycles to use for execution.
     ///
     /// If the cycle limit is exceeded, execution will return
-    /// [sp1_core_machine::runtime::ExecutionError::ExceededCycleLimit].
+    /// [ExecutionError::ExceededCycleLimit](crate::ExecutionError::ExceededCycleLimit).
     pub fn max_cycles(mut self, max_cycles: u64) -> Self {
         self.context_builder.max_cycles(max_cycles);
         self
@@ -82,6 +166,7 @@ pub struct Prove<'a> {
     core_opts: SP1CoreOpts,
     recursion_opts: SP1CoreOpts,
     timeout: Option<Duration>,
+    cancellation_token: Option<CancellationToken>,
 }
 
 impl<'a> Prove<'a> {
@@ -103,6 +188,7 @@ impl<'a> Prove<'a> {
             core_opts: SP1CoreOpts::default(),
             recursion_opts: SP1CoreOpts::recursion(),
             timeout: None,
+            cancellation_token: None,
         }
     }
 
@@ -117,9 +203,13 @@ impl<'a> Prove<'a> {
             core_opts,
             recursion_opts,
             timeout,
+            cancellation_token,
         } = self;
         let opts = SP1ProverOpts { core_opts, recursion_opts };
         let proof_opts = ProofOpts { sp1_prover_opts: opts, timeout };
+        if let Some(token) = cancellation_token {
+            context_builder.cancellation_token(token);
+        }
         let context = context_builder.build();
 
         // Dump the program and stdin to files for debugging if `SP1_DUMP` is set.
@@ -174,6 +264,30 @@ impl<'a> Prove<'a> {
         self
     }
 
+    /// Add a runtime hook whose result is computed by a future, like a query to a database.
+    ///
+    /// The future is polled to completion on the thread executing the program.
+    pub fn with_async_hook<Fut: Future<Output = Vec<Vec<u8>>> + 'a>(
+        mut self,
+        fd: u32,
+        f: impl FnMut(HookEnv, &[u8]) -> Fut + Send + Sync + 'a,
+    ) -> Self {
+        self.context_builder.async_hook(fd, f);
+        self
+    }
+
+    /// Add a runtime hook from a deserializable request to a serializable response.
+    ///
+    /// The hook may be called from within SP1 with [`sp1_zkvm::io::hook`], using the same types.
+    pub fn with_typed_hook<Req: DeserializeOwned + 'a, Resp: Serialize + 'a>(
+        mut self,
+        fd: u32,
+        f: impl FnMut(HookEnv, Req) -> Resp + Send + Sync + 'a,
+    ) -> Self {
+        self.context_builder.typed_hook(fd, f);
+        self
+    }
+
     /// Avoid registering the default hooks in the runtime.
     ///
     /// It is not necessary to call this to override hooks --- instead, simply
@@ -183,6 +297,13 @@ impl<'a> Prove<'a> {
         self
     }
 
+    /// Pull the input items that come after the ones in `stdin` from `provider`, once the
+    /// program has read all of the input so far.
+    pub fn with_input_provider(mut self, provider: impl InputProvider + 'a) -> Self {
+        self.context_builder.input_provider(provider);
+        self
+    }
+
     /// Set the shard size for proving.
     pub fn shard_size(mut self, value: usize) -> Self {
         self.core_opts.shard_size = value;
@@ -195,6 +316,24 @@ impl<'a> Prove<'a> {
         self
     }
 
+    /// Pick the shard size, shard batch size, number of trace generation workers and channel
+    /// capacities so that the estimated peak memory usage of the prover fits in `bytes`.
+    ///
+    /// This overrides any shard size or shard batch size set before.
+    pub fn memory_budget(mut self, bytes: u64) -> Self {
+        let SP1ProverOpts { core_opts, recursion_opts } = SP1ProverOpts::with_memory_budget(bytes);
+        for (opts, budget_opts) in
+            [(&mut self.core_opts, core_opts), (&mut self.recursion_opts, recursion_opts)]
+        {
+            opts.shard_size = budget_opts.shard_size;
+            opts.shard_batch_size = budget_opts.shard_batch_size;
+            opts.trace_gen_workers = budget_opts.trace_gen_workers;
+            opts.records_and_traces_channel_capacity =
+                budget_opts.records_and_traces_channel_capacity;
+        }
+        self
+    }
+
     /// Set whether we should reconstruct commitments while proving.
     pub fn reconstruct_commitments(mut self, value: bool) -> Self {
         self.core_opts.reconstruct_commitments = value;
@@ -204,7 +343,7 @@ impl<'a> Prove<'a> {
     /// Set the maximum number of cpu cycles to use for execution.
     ///
     /// If the cycle limit is exceeded, execution will return
-    /// [sp1_core_machine::runtime::ExecutionError::ExceededCycleLimit].
+    /// [ExecutionError::ExceededCycleLimit](crate::ExecutionError::ExceededCycleLimit).
     pub fn cycle_limit(mut self, cycle_limit: u64) -> Self {
         self.context_builder.max_cycles(cycle_limit);
         self
@@ -212,9 +351,172 @@ impl<'a> Prove<'a> {
 
     /// Set the timeout for the proof's generation.
     ///
-    /// This parameter is only used when the prover is run in network mode.
+    /// In network mode the timeout is sent with the proof request. The local prover stops at the
+    /// next shard, shard batch or recursion layer once the timeout has elapsed and returns a
+    /// [crate::ProvingInterrupted::TimedOut] error describing the work done so far.
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = Some(timeout);
         self
     }
+
+    /// Set a listener that is called with every [ProverEvent] emitted while proving.
+    ///
+    /// The listener is called from the prover's worker threads, so it should return quickly.
+    pub fn on_progress(mut self, f: impl Fn(ProverEvent) + Send + Sync + 'a) -> Self {
+        self.context_builder.on_progress(f);
+        self
+    }
+
+    /// Set the token used to cancel the proof's generation.
+    ///
+    /// Once the token is cancelled, [Self::run] stops at the next shard, shard batch or recursion
+    /// layer and returns a [crate::ProvingInterrupted::Cancelled] error describing the work done
+    /// so far.
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Start proving on a thread of `scope` and return a [ProveHandle] to it.
+    ///
+    /// The handle receives the proof's progress events, can cancel it, and yields the result
+    /// of [Self::run] once the proof is generated, either by polling [ProveHandle::try_result],
+    /// blocking on [ProveHandle::wait], or awaiting the handle. The handle cancels the proof
+    /// through the token set with [Self::cancellation_token], or a new one if none was set.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    ///
+    /// let proof = std::thread::scope(|s| {
+    ///     let handle = client.prove(&pk, stdin).compressed().spawn(s);
+    ///     for event in handle.events() {
+    ///         println!("{:?}", event);
+    ///     }
+    ///     handle.wait()
+    /// });
+    /// ```
+    pub fn spawn<'scope>(self, scope: &'scope Scope<'scope, '_>) -> ProveHandle
+    where
+        'a: 'scope,
+    {
+        let (events_tx, events_rx) = channel();
+        let cancellation_token = self.cancellation_token.clone().unwrap_or_default();
+        let prove = self
+            .on_progress(move |event| {
+                // The handle may have been dropped, in which case nobody is listening.
+                let _ = events_tx.send(event);
+            })
+            .cancellation_token(cancellation_token.clone());
+
+        let completion = Arc::new(Completion::default());
+        let thread_completion = completion.clone();
+        scope.spawn(move || {
+            // Report a panic as an error, so that waiting on the handle doesn't block forever.
+            let result = panic::catch_unwind(AssertUnwindSafe(|| prove.run()))
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("the prover panicked")));
+            thread_completion.complete(result);
+        });
+        ProveHandle { events: events_rx, cancellation_token, completion }
+    }
+}
+
+/// The result of a proof spawned with [Prove::spawn], shared with its [ProveHandle].
+#[derive(Default)]
+struct Completion {
+    state: Mutex<CompletionState>,
+    done: Condvar,
+}
+
+#[derive(Default)]
+struct CompletionState {
+    result: Option<Result<SP1ProofWithPublicValues>>,
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+impl Completion {
+    fn complete(&self, result: Result<SP1ProofWithPublicValues>) {
+        let mut state = self.state.lock().unwrap();
+        state.result = Some(result);
+        state.finished = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.done.notify_all();
+    }
+}
+
+/// A handle to a proof being generated on another thread, created with [Prove::spawn].
+///
+/// The handle is also a [Future] resolving to the result of the proof.
+pub struct ProveHandle {
+    events: Receiver<ProverEvent>,
+    cancellation_token: CancellationToken,
+    completion: Arc<Completion>,
+}
+
+impl ProveHandle {
+    /// Iterate over the progress events, blocking until the next one is available.
+    ///
+    /// The iterator ends once the proof has been generated, has failed, or was cancelled.
+    pub fn events(&self) -> impl Iterator<Item = ProverEvent> + '_ {
+        self.events.iter()
+    }
+
+    /// Get the next progress event without blocking, if there is one.
+    ///
+    /// Returns `Err(TryRecvError::Disconnected)` once no more events will be emitted.
+    pub fn try_next_event(&self) -> std::result::Result<ProverEvent, TryRecvError> {
+        self.events.try_recv()
+    }
+
+    /// Cancel the proof's generation.
+    pub fn cancel(&self) {
+        self.cancellation_token.cancel();
+    }
+
+    /// Get a clone of the token used to cancel the proof's generation.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation_token.clone()
+    }
+
+    /// Whether the proof has finished, successfully or not.
+    pub fn is_finished(&self) -> bool {
+        self.completion.state.lock().unwrap().finished
+    }
+
+    /// Take the result of the proof without blocking, if it has finished.
+    ///
+    /// Returns `None` while the proof is still being generated, and after the result was taken.
+    pub fn try_result(&mut self) -> Option<Result<SP1ProofWithPublicValues>> {
+        self.completion.state.lock().unwrap().result.take()
+    }
+
+    /// Block until the proof has finished and return its result.
+    pub fn wait(self) -> Result<SP1ProofWithPublicValues> {
+        let state = self.completion.state.lock().unwrap();
+        let mut state = self.completion.done.wait_while(state, |state| !state.finished).unwrap();
+        state.result.take().expect("the result of the proof was already taken")
+    }
+}
+
+impl Future for ProveHandle {
+    type Output = Result<SP1ProofWithPublicValues>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.completion.state.lock().unwrap();
+        if state.finished {
+            Poll::Ready(state.result.take().expect("the result of the proof was already taken"))
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
 }
@@ -38,9 +38,16 @@ use {std::future::Future, tokio::task::block_in_place};
 
 pub use provers::{CpuProver, MockProver, Prover};
 
-pub use sp1_core_executor::{ExecutionReport, HookEnv, SP1Context, SP1ContextBuilder};
-pub use sp1_core_machine::{io::SP1Stdin, riscv::cost::CostEstimator, SP1_CIRCUIT_VERSION};
-pub use sp1_primitives::io::SP1PublicValues;
+pub use sp1_core_executor::{
+    syscalls::{Syscall, SyscallCode, SyscallContext},
+    BacktraceFrame, CancellationToken, ExecutionError, ExecutionReport, GuestPanic, HaltReport,
+    HookEnv, InputProvider, ProverEvent, ProvingInterrupted, ProvingStats, SP1Context,
+    SP1ContextBuilder, SharedBuffer,
+};
+pub use sp1_core_machine::{
+    io::SP1Stdin, riscv::cost::CostEstimator, utils::MemoryBudget, SP1_CIRCUIT_VERSION,
+};
+pub use sp1_primitives::io::{PublicValueChannel, SP1PublicValues};
 pub use sp1_prover::{
     CoreSC, HashableKey, InnerSC, OuterSC, PlonkBn254Proof, SP1Prover, SP1ProvingKey,
     SP1VerifyingKey,
@@ -191,6 +198,44 @@ impl ProverClient {
         action::Execute::new(self.prover.as_ref(), elf, stdin)
     }
 
+    /// Execute the given program once for each of the given inputs, in parallel, and return the
+    /// public values and execution report of each run in the order of the inputs.
+    ///
+    /// The runtimes are reset and reused across the runs, which makes this cheaper than calling
+    /// [Self::execute] in a loop when the program is run on many inputs.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// // Load the program.
+    /// let elf = include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    ///
+    /// // Initialize the prover client.
+    /// let client = ProverClient::new();
+    ///
+    /// // Setup the inputs.
+    /// let stdins = (0..16usize)
+    ///     .map(|n| {
+    ///         let mut stdin = SP1Stdin::new();
+    ///         stdin.write(&n);
+    ///         stdin
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// // Execute the program on each of the inputs.
+    /// for result in client.execute_batch(elf, &stdins) {
+    ///     let (public_values, report) = result.unwrap();
+    /// }
+    /// ```
+    pub fn execute_batch(
+        &self,
+        elf: &[u8],
+        stdins: &[SP1Stdin],
+    ) -> Vec<Result<(SP1PublicValues, ExecutionReport), ExecutionError>> {
+        self.prover.sp1_prover().execute_batch(elf, stdins, SP1Context::default)
+    }
+
     /// Prepare to prove the execution of the given program with the given input in the default
     /// mode. The returned [action::Prove] may be configured via its methods before running.
     /// For example, calling [action::Prove::compress] sets the mode to compressed mode.
@@ -299,10 +344,14 @@ pub fn block_on<T>(fut: impl Future<Output = T>) -> T {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
 
     use sp1_primitives::io::SP1PublicValues;
 
-    use crate::{utils, CostEstimator, ProverClient, SP1Stdin};
+    use crate::{
+        utils, CancellationToken, CostEstimator, ExecutionError, ProverClient, ProvingInterrupted,
+        SP1Stdin,
+    };
 
     #[test]
     fn test_execute() {
@@ -327,6 +376,35 @@ mod tests {
         client.execute(elf, stdin).run().unwrap();
     }
 
+    #[test]
+    fn test_execute_panic_report() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf = include_bytes!("../../../tests/panic/elf/riscv32im-succinct-zkvm-elf");
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let error = client.execute(elf, stdin).run().unwrap_err();
+        let Some(ExecutionError::HaltWithReport(report)) = error.downcast_ref::<ExecutionError>()
+        else {
+            panic!("expected a halt report, got {error}");
+        };
+        assert_ne!(report.exit_code, 0);
+        assert!(!report.backtrace.is_empty());
+    }
+
+    #[test]
+    fn test_execute_missing_input() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let error = client.execute(elf, SP1Stdin::new()).run().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::InputExhausted { read: 0, .. })
+        ));
+    }
+
     #[should_panic]
     #[test]
     fn test_cycle_limit_fail() {
@@ -359,6 +437,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_e2e_cancelled() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, _) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        // A cancelled proof should stop before generating any checkpoints.
+        let token = CancellationToken::new();
+        token.cancel();
+        let (err, events) = std::thread::scope(|s| {
+            let handle = client.prove(&pk, stdin).cancellation_token(token).spawn(s);
+            let events = handle.events().count();
+            (handle.wait().unwrap_err(), events)
+        });
+        assert!(matches!(
+            err.downcast_ref::<ProvingInterrupted>(),
+            Some(ProvingInterrupted::Cancelled(_))
+        ));
+        assert_eq!(events, 0);
+    }
+
+    #[test]
+    fn test_e2e_timeout() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, _) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        // A proof whose timeout has already elapsed should stop with the work done so far.
+        let err = client.prove(&pk, stdin).timeout(Duration::ZERO).run().unwrap_err();
+        let Some(ProvingInterrupted::TimedOut(stats)) = err.downcast_ref::<ProvingInterrupted>()
+        else {
+            panic!("expected the proof to time out, got {err}");
+        };
+        assert_eq!(stats.shards_proven, 0);
+    }
+
     #[test]
     fn test_e2e_compressed() {
         utils::setup_logger();
@@ -163,7 +163,15 @@ fn warn_if_not_default(opts: &SP1ProverOpts, context: &SP1Context) {
         tracing::warn!("custom SP1ProverOpts are currently unsupported by the network prover");
     }
     // Exhaustive match is done to ensure we update the warnings if the types change.
-    let SP1Context { hook_registry, subproof_verifier, .. } = context;
+    let SP1Context {
+        hook_registry,
+        subproof_verifier,
+        syscalls,
+        input_provider,
+        stdout,
+        stderr,
+        ..
+    } = context;
     if hook_registry.is_some() {
         tracing::warn!("non-default context.hook_registry will be ignored: {:?}", hook_registry);
         tracing::warn!("custom runtime hooks are currently unsupported by the network prover");
@@ -173,6 +181,18 @@ fn warn_if_not_default(opts: &SP1ProverOpts, context: &SP1Context) {
         tracing::warn!("non-default context.subproof_verifier will be ignored");
         tracing::warn!("custom subproof verifiers are currently unsupported by the network prover");
     }
+    if !syscalls.is_empty() {
+        tracing::warn!("non-default context.syscalls will be ignored");
+        tracing::warn!("syscalls registered on the host can be executed but not proven");
+    }
+    if input_provider.is_some() {
+        tracing::warn!("non-default context.input_provider will be ignored");
+        tracing::warn!("only the input in stdin is sent to the network prover");
+    }
+    if stdout.is_some() || stderr.is_some() {
+        tracing::warn!("non-default context.stdout and context.stderr will be ignored");
+        tracing::warn!("the network prover does not return the program's output");
+    }
 }
 
 impl From<SP1ProofKind> for ProofMode {
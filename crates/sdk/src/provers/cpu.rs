@@ -1,7 +1,9 @@
+use std::time::Instant;
+
 use anyhow::Result;
-use sp1_core_executor::SP1Context;
-use sp1_core_machine::io::SP1Stdin;
-use sp1_prover::{components::DefaultProverComponents, SP1Prover};
+use sp1_core_executor::{ProverEvent, SP1Context};
+use sp1_core_machine::{io::SP1Stdin, utils::SP1CoreProverError};
+use sp1_prover::{components::DefaultProverComponents, SP1Prover, SP1RecursionProverError};
 
 use crate::install::try_install_circuit_artifacts;
 use crate::{
@@ -9,7 +11,7 @@ use crate::{
     SP1VerifyingKey,
 };
 
-use super::ProverType;
+use super::{check_public_values_kind, ProverType};
 
 /// An implementation of [crate::ProverClient] that can generate end-to-end proofs locally.
 pub struct CpuProver {
@@ -47,12 +49,26 @@ impl Prover<DefaultProverComponents> for CpuProver {
         pk: &SP1ProvingKey,
         stdin: SP1Stdin,
         opts: ProofOpts,
-        context: SP1Context<'a>,
+        mut context: SP1Context<'a>,
         kind: SP1ProofKind,
     ) -> Result<SP1ProofWithPublicValues> {
-        // Generate the core proof.
-        let proof: sp1_prover::SP1ProofWithMetadata<sp1_prover::SP1CoreProofData> =
-            self.prover.prove_core(pk, &stdin, opts.sp1_prover_opts, context)?;
+        // Stop proving once the timeout has elapsed.
+        if let Some(timeout) = opts.timeout {
+            context.observer.deadline = Some(Instant::now() + timeout);
+        }
+        let observer = context.observer();
+        observer.start();
+
+        // Generate the core proof. Interruptions are surfaced as a bare `ProvingInterrupted` so
+        // that callers can downcast to it regardless of the stage that was interrupted.
+        let proof: sp1_prover::SP1ProofWithMetadata<sp1_prover::SP1CoreProofData> = self
+            .prover
+            .prove_core(pk, &stdin, opts.sp1_prover_opts, context)
+            .map_err(|error| match error {
+                SP1CoreProverError::Interrupted(interrupted) => interrupted.into(),
+                error => anyhow::Error::from(error),
+            })?;
+        check_public_values_kind(&proof.public_values, kind)?;
         if kind == SP1ProofKind::Core {
             return Ok(SP1ProofWithPublicValues {
                 proof: SP1Proof::Core(proof.proof.0),
@@ -67,8 +83,13 @@ impl Prover<DefaultProverComponents> for CpuProver {
         let public_values = proof.public_values.clone();
 
         // Generate the compressed proof.
-        let reduce_proof =
-            self.prover.compress(&pk.vk, proof, deferred_proofs, opts.sp1_prover_opts)?;
+        let reduce_proof = self
+            .prover
+            .compress_with_observer(&pk.vk, proof, deferred_proofs, opts.sp1_prover_opts, &observer)
+            .map_err(|error| match error {
+                SP1RecursionProverError::Interrupted(interrupted) => interrupted.into(),
+                error => anyhow::Error::from(error),
+            })?;
         if kind == SP1ProofKind::Compressed {
             return Ok(SP1ProofWithPublicValues {
                 proof: SP1Proof::Compressed(Box::new(reduce_proof)),
@@ -79,9 +100,13 @@ impl Prover<DefaultProverComponents> for CpuProver {
         }
 
         // Generate the shrink proof.
+        observer.check()?;
+        observer.emit(ProverEvent::ShrinkStarted);
         let compress_proof = self.prover.shrink(reduce_proof, opts.sp1_prover_opts)?;
 
         // Genenerate the wrap proof.
+        observer.check()?;
+        observer.emit(ProverEvent::WrapStarted);
         let outer_proof = self.prover.wrap_bn254(compress_proof, opts.sp1_prover_opts)?;
 
         if kind == SP1ProofKind::Plonk {
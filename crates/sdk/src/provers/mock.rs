@@ -18,7 +18,7 @@ use sp1_prover::{
     Groth16Bn254Proof, HashableKey, PlonkBn254Proof, SP1Prover,
 };
 
-use super::{ProofOpts, ProverType};
+use super::{check_public_values_kind, ProofOpts, ProverType};
 
 /// An implementation of [crate::ProverClient] that can generate mock proofs.
 pub struct MockProver {
@@ -110,6 +110,7 @@ impl Prover<DefaultProverComponents> for MockProver {
             }
             SP1ProofKind::Plonk => {
                 let (public_values, _) = self.prover.execute(&pk.elf, &stdin, context)?;
+                check_public_values_kind(&public_values, kind)?;
                 Ok(SP1ProofWithPublicValues {
                     proof: SP1Proof::Plonk(PlonkBn254Proof {
                         public_inputs: [
@@ -127,6 +128,7 @@ impl Prover<DefaultProverComponents> for MockProver {
             }
             SP1ProofKind::Groth16 => {
                 let (public_values, _) = self.prover.execute(&pk.elf, &stdin, context)?;
+                check_public_values_kind(&public_values, kind)?;
                 Ok(SP1ProofWithPublicValues {
                     proof: SP1Proof::Groth16(Groth16Bn254Proof {
                         public_inputs: [
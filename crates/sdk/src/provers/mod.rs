@@ -16,6 +16,7 @@ use std::time::Duration;
 use anyhow::Result;
 use sp1_core_executor::SP1Context;
 use sp1_core_machine::{io::SP1Stdin, SP1_CIRCUIT_VERSION};
+use sp1_primitives::io::SP1PublicValues;
 use sp1_prover::{
     components::SP1ProverComponents, CoreSC, InnerSC, SP1CoreProofData, SP1Prover, SP1ProvingKey,
     SP1VerifyingKey,
@@ -61,6 +62,20 @@ pub enum SP1VerificationError {
     Groth16(anyhow::Error),
 }
 
+/// Check that a proof of `kind` can commit to `public_values`.
+///
+/// The Plonk and Groth16 verifiers hash the public values as a single stream, so they can't check
+/// the Merkle root a program with named channels commits to.
+pub(crate) fn check_public_values_kind(
+    public_values: &SP1PublicValues,
+    kind: SP1ProofKind,
+) -> Result<()> {
+    if matches!(kind, SP1ProofKind::Plonk | SP1ProofKind::Groth16) && public_values.has_channels() {
+        anyhow::bail!("{kind:?} proofs don't support named public value channels");
+    }
+    Ok(())
+}
+
 /// An implementation of [crate::ProverClient].
 pub trait Prover<C: SP1ProverComponents>: Send + Sync {
     fn id(&self) -> ProverType;
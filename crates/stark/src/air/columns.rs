@@ -0,0 +1,184 @@
+use std::io::{self, Write};
+
+use generic_array::{ArrayLength, GenericArray};
+use p3_field::PrimeField32;
+use p3_matrix::{dense::RowMajorMatrix, Matrix};
+use serde::{Deserialize, Serialize};
+
+/// A single trace column, used in place of the field to describe the layout of a column struct.
+///
+/// For example, the layout of the CPU chip is given by `CpuCols::<Column>::layout()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Column;
+
+/// The columns occupied by a field of a column struct.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnDescriptor {
+    /// The path of the field from the root of the row, e.g. `add_operation.value[0]`.
+    pub path: String,
+    /// The index of the first column of the field.
+    pub offset: usize,
+    /// The number of columns of the field.
+    pub width: usize,
+    /// The fields nested in this field, empty if it is a single column.
+    pub children: Vec<ColumnDescriptor>,
+}
+
+impl ColumnDescriptor {
+    /// A descriptor made of the given fields, which must be contiguous and start at `offset`.
+    #[must_use]
+    pub fn with_children(path: &str, offset: usize, children: Vec<ColumnDescriptor>) -> Self {
+        let width = children.iter().map(|child| child.width).sum();
+        Self { path: path.to_string(), offset, width, children }
+    }
+
+    /// Append a field of type `T` named `field` after the existing fields.
+    pub fn push_field<T: ColumnLayout>(&mut self, field: &str) {
+        let child = T::layout_at(&column_path(&self.path, field), self.offset + self.width);
+        self.width += child.width;
+        self.children.push(child);
+    }
+
+    /// The single columns of the field, in the order they appear in a row.
+    #[must_use]
+    pub fn leaves(&self) -> Vec<&ColumnDescriptor> {
+        if self.children.is_empty() {
+            vec![self]
+        } else {
+            self.children.iter().flat_map(ColumnDescriptor::leaves).collect()
+        }
+    }
+
+    /// The names of the single columns of the field, in the order they appear in a row.
+    #[must_use]
+    pub fn column_names(&self) -> Vec<String> {
+        self.leaves().into_iter().map(|leaf| leaf.path.clone()).collect()
+    }
+
+    /// Find the field with the given path.
+    #[must_use]
+    pub fn find(&self, path: &str) -> Option<&ColumnDescriptor> {
+        if self.path == path {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(path))
+    }
+}
+
+/// Describes how the fields of a column struct are laid out in a trace row.
+///
+/// This is implemented by `#[derive(AlignedBorrow)]` for every column struct whose fields all have
+/// a layout.
+pub trait ColumnLayout {
+    /// The layout of the struct, for a field at `path` whose first column is `offset`.
+    fn layout_at(path: &str, offset: usize) -> ColumnDescriptor;
+
+    /// The layout of the struct, as the root of a row.
+    #[must_use]
+    fn layout() -> ColumnDescriptor {
+        Self::layout_at("", 0)
+    }
+
+    /// The names of the columns, in the order they appear in a trace row.
+    #[must_use]
+    fn column_names() -> Vec<String> {
+        Self::layout().column_names()
+    }
+}
+
+/// Extend a column path with the name of a field.
+#[must_use]
+pub fn column_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{path}.{field}")
+    }
+}
+
+/// The layout of `len` consecutive elements of type `T`, named `path[i]`.
+fn array_layout<T: ColumnLayout>(path: &str, offset: usize, len: usize) -> ColumnDescriptor {
+    let mut layout = ColumnDescriptor::with_children(path, offset, Vec::new());
+    for i in 0..len {
+        let child = T::layout_at(&format!("{path}[{i}]"), offset + layout.width);
+        layout.width += child.width;
+        layout.children.push(child);
+    }
+    layout
+}
+
+impl ColumnLayout for Column {
+    fn layout_at(path: &str, offset: usize) -> ColumnDescriptor {
+        ColumnDescriptor { path: path.to_string(), offset, width: 1, children: Vec::new() }
+    }
+}
+
+impl<T: ColumnLayout, const N: usize> ColumnLayout for [T; N] {
+    fn layout_at(path: &str, offset: usize) -> ColumnDescriptor {
+        array_layout::<T>(path, offset, N)
+    }
+}
+
+impl<T: ColumnLayout, N: ArrayLength> ColumnLayout for GenericArray<T, N> {
+    fn layout_at(path: &str, offset: usize) -> ColumnDescriptor {
+        array_layout::<T>(path, offset, N::USIZE)
+    }
+}
+
+impl<A: ColumnLayout, B: ColumnLayout> ColumnLayout for (A, B) {
+    fn layout_at(path: &str, offset: usize) -> ColumnDescriptor {
+        let mut layout = ColumnDescriptor::with_children(path, offset, Vec::new());
+        layout.push_field::<A>("0");
+        layout.push_field::<B>("1");
+        layout
+    }
+}
+
+/// Write a trace as CSV, with a header row of column names.
+///
+/// Columns without a name in `column_names` are named `main[i]`.
+pub fn write_trace_csv<F: PrimeField32>(
+    mut writer: impl Write,
+    column_names: &[String],
+    trace: &RowMajorMatrix<F>,
+) -> io::Result<()> {
+    let header = (0..trace.width())
+        .map(|i| column_names.get(i).cloned().unwrap_or_else(|| format!("main[{i}]")))
+        .collect::<Vec<_>>();
+    writeln!(writer, "{}", header.join(","))?;
+    for i in 0..trace.height() {
+        let row = trace.row_slice(i);
+        let row = row.iter().map(|value| value.as_canonical_u32().to_string()).collect::<Vec<_>>();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use generic_array::typenum::U2;
+
+    use super::*;
+    use crate::Word;
+
+    #[test]
+    fn word_column_names() {
+        assert_eq!(Word::<Column>::column_names(), vec!["[0]", "[1]", "[2]", "[3]"]);
+        assert_eq!(
+            <[Word<Column>; 2]>::column_names()[4..6],
+            ["[1][0]".to_string(), "[1][1]".to_string()]
+        );
+    }
+
+    #[test]
+    fn generic_array_layout() {
+        let layout = GenericArray::<(Column, Word<Column>), U2>::layout_at("words", 3);
+        assert_eq!(layout.width, 10);
+        assert_eq!(layout.leaves().len(), 10);
+
+        let second = layout.find("words[1].1").unwrap();
+        assert_eq!(second.offset, 9);
+        assert_eq!(second.width, 4);
+        assert_eq!(second.children[2].path, "words[1].1[2]");
+    }
+}
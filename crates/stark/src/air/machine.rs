@@ -6,7 +6,7 @@ use crate::MachineRecord;
 
 pub use sp1_derive::MachineAir;
 
-use super::InteractionScope;
+use super::{ColumnDescriptor, InteractionScope};
 
 /// An AIR that is part of a multi table AIR arithmetization.
 pub trait MachineAir<F: Field>: BaseAir<F> + 'static + Send + Sync {
@@ -44,6 +44,24 @@ pub trait MachineAir<F: Field>: BaseAir<F> + 'static + Send + Sync {
         None
     }
 
+    /// The layout of the main trace columns, if the chip describes it.
+    ///
+    /// Chips whose columns are an `AlignedBorrow` struct can return
+    /// `Some(ChipCols::<Column>::layout())`.
+    fn column_layout(&self) -> Option<ColumnDescriptor> {
+        None
+    }
+
+    /// The names of the main trace columns, used by debugging tools to refer to them.
+    ///
+    /// The names come from [`MachineAir::column_layout`], the default only numbers the columns.
+    fn column_names(&self) -> Vec<String> {
+        match self.column_layout() {
+            Some(layout) => layout.column_names(),
+            None => (0..self.width()).map(|i| format!("main[{i}]")).collect(),
+        }
+    }
+
     /// Specifies whether it's trace should be part of either the global or local commit.
     fn commit_scope(&self) -> InteractionScope {
         InteractionScope::Local
@@ -1,6 +1,7 @@
 //! Building blocks for defining AIRs.
 
 mod builder;
+mod columns;
 mod extension;
 mod interaction;
 mod machine;
@@ -9,6 +10,7 @@ mod public_values;
 mod sub_builder;
 
 pub use builder::*;
+pub use columns::*;
 pub use extension::*;
 pub use interaction::*;
 pub use machine::*;
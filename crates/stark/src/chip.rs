@@ -43,6 +43,11 @@ impl<F: Field, A> Chip<F, A> {
         self.log_quotient_degree
     }
 
+    /// The underlying air.
+    pub const fn air(&self) -> &A {
+        &self.air
+    }
+
     /// Consumes the chip and returns the underlying air.
     pub fn into_inner(self) -> A {
         self.air
@@ -210,6 +215,14 @@ where
         self.air.included(shard)
     }
 
+    fn column_layout(&self) -> Option<crate::air::ColumnDescriptor> {
+        self.air.column_layout()
+    }
+
+    fn column_names(&self) -> Vec<String> {
+        self.air.column_names()
+    }
+
     fn commit_scope(&self) -> crate::air::InteractionScope {
         self.air.commit_scope()
     }
@@ -1,7 +1,9 @@
 use std::{
+    backtrace::Backtrace,
     borrow::Borrow,
-    panic::{self, AssertUnwindSafe},
-    process::exit,
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    panic::{self, AssertUnwindSafe, Location},
 };
 
 use p3_air::{
@@ -16,12 +18,124 @@ use p3_matrix::{
 };
 
 use super::{MachineChip, StarkGenericConfig, Val};
-use crate::air::{EmptyMessageBuilder, MachineAir, MultiTableAirBuilder};
+use crate::{
+    air::{EmptyMessageBuilder, MachineAir, MultiTableAirBuilder},
+    InteractionReport,
+};
+
+/// The offset added to a column to check whether a failing constraint depends on it.
+///
+/// It is far from zero and one, so perturbing a boolean column never yields another boolean.
+const PERTURBATION: u32 = 0x2545_F491;
+
+/// The number of violations of each chip shown when a [`ConstraintReport`] is displayed.
+const MAX_DISPLAYED_VIOLATIONS: usize = 16;
+
+/// A constraint that does not hold on a row of a chip's trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    /// The name of the chip.
+    pub chip: String,
+    /// The index of the shard among the records being debugged.
+    pub shard: usize,
+    /// The row of the main trace.
+    pub row: usize,
+    /// The index of the constraint, in the order in which the chip asserts its constraints.
+    pub constraint: usize,
+    /// Where the constraint is asserted, or the panic message if evaluating the row panicked.
+    pub location: String,
+    /// The main trace columns the constraint depends on. Columns of the next row are prefixed
+    /// with `next.`.
+    pub columns: Vec<String>,
+}
+
+impl Display for ConstraintViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "shard {} row {}: constraint {} at {}",
+            self.shard, self.row, self.constraint, self.location
+        )?;
+        if !self.columns.is_empty() {
+            write!(f, " depends on [{}]", self.columns.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The constraints that do not hold on the traces of a machine.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConstraintReport {
+    /// Every violated constraint, ordered by shard, chip and row.
+    pub violations: Vec<ConstraintViolation>,
+    /// The messages that don't balance, if a cumulative sum of the permutation argument is not
+    /// zero.
+    pub interactions: InteractionReport,
+}
+
+impl ConstraintReport {
+    /// Whether all the constraints hold and all the interactions balance.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty() && self.interactions.is_balanced()
+    }
+
+    /// The names of the chips with at least one violated constraint.
+    #[must_use]
+    pub fn failing_chips(&self) -> BTreeSet<&str> {
+        self.violations.iter().map(|v| v.chip.as_str()).collect()
+    }
+
+    /// The `(shard, row)` pairs of the given chip with at least one violated constraint.
+    #[must_use]
+    pub fn failing_rows(&self, chip: &str) -> BTreeSet<(usize, usize)> {
+        self.violations.iter().filter(|v| v.chip == chip).map(|v| (v.shard, v.row)).collect()
+    }
+
+    /// The violations of the given chip.
+    pub fn violations_of<'a>(
+        &'a self,
+        chip: &'a str,
+    ) -> impl Iterator<Item = &'a ConstraintViolation> + 'a {
+        self.violations.iter().filter(move |v| v.chip == chip)
+    }
+}
+
+impl Display for ConstraintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_ok() {
+            return write!(f, "all constraints are satisfied");
+        }
+        if !self.interactions.is_balanced() {
+            write!(f, "{}", self.interactions)?;
+        }
+        for chip in self.failing_chips() {
+            let violations = self.violations_of(chip).collect::<Vec<_>>();
+            writeln!(
+                f,
+                "chip {chip}: {} violations on {} rows",
+                violations.len(),
+                self.failing_rows(chip).len()
+            )?;
+            for violation in violations.iter().take(MAX_DISPLAYED_VIOLATIONS) {
+                writeln!(f, "  {violation}")?;
+            }
+            if violations.len() > MAX_DISPLAYED_VIOLATIONS {
+                writeln!(f, "  ... and {} more", violations.len() - MAX_DISPLAYED_VIOLATIONS)?;
+            }
+        }
+        Ok(())
+    }
+}
 
 /// Checks that the constraints of the given AIR are satisfied, including the permutation trace.
 ///
+/// Every row is evaluated and every violated constraint is returned, along with the columns it
+/// depends on. The shard of the returned violations is zero.
+///
 /// Note that this does not actually verify the proof.
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+#[must_use]
 pub fn debug_constraints<SC, A>(
     chip: &MachineChip<SC, A>,
     preprocessed: Option<&RowMajorMatrix<Val<SC>>>,
@@ -30,7 +144,8 @@ pub fn debug_constraints<SC, A>(
     perm_challenges: &[SC::Challenge],
     public_values: &[Val<SC>],
     cumulative_sums: &[SC::Challenge],
-) where
+) -> Vec<ConstraintViolation>
+where
     SC: StarkGenericConfig,
     Val<SC>: PrimeField32,
     A: MachineAir<Val<SC>> + for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
@@ -38,11 +153,16 @@ pub fn debug_constraints<SC, A>(
     assert_eq!(main.height(), perm.height());
     let height = main.height();
     if height == 0 {
-        return;
+        return Vec::new();
     }
 
-    // Check that constraints are satisfied.
-    (0..height).for_each(|i| {
+    let name = chip.name();
+    let column_names = chip.column_names();
+    // The columns each failing constraint depends on, computed the first time it fails.
+    let mut dependencies: HashMap<usize, Vec<String>> = HashMap::new();
+    let mut violations = Vec::new();
+
+    for i in 0..height {
         let i_next = (i + 1) % height;
 
         let main_local = main.row_slice(i);
@@ -68,43 +188,123 @@ pub fn debug_constraints<SC, A>(
         let perm_next = perm.row_slice(i_next);
         let perm_next = &(*perm_next);
 
-        let mut builder = DebugConstraintBuilder {
-            preprocessed: VerticalPair::new(
-                RowMajorMatrixView::new_row(&preprocessed_local),
-                RowMajorMatrixView::new_row(&preprocessed_next),
-            ),
-            main: VerticalPair::new(
-                RowMajorMatrixView::new_row(main_local),
-                RowMajorMatrixView::new_row(main_next),
-            ),
-            perm: VerticalPair::new(
-                RowMajorMatrixView::new_row(perm_local),
-                RowMajorMatrixView::new_row(perm_next),
-            ),
-            perm_challenges,
-            cumulative_sums,
-            is_first_row: Val::<SC>::zero(),
-            is_last_row: Val::<SC>::zero(),
-            is_transition: Val::<SC>::one(),
-            public_values,
+        // Evaluate the constraints on the row, with the given main trace rows.
+        let eval = |main_local: &[Val<SC>], main_next: &[Val<SC>], record_failures: bool| {
+            let mut builder = DebugConstraintBuilder {
+                preprocessed: VerticalPair::new(
+                    RowMajorMatrixView::new_row(&preprocessed_local),
+                    RowMajorMatrixView::new_row(&preprocessed_next),
+                ),
+                main: VerticalPair::new(
+                    RowMajorMatrixView::new_row(main_local),
+                    RowMajorMatrixView::new_row(main_next),
+                ),
+                perm: VerticalPair::new(
+                    RowMajorMatrixView::new_row(perm_local),
+                    RowMajorMatrixView::new_row(perm_next),
+                ),
+                perm_challenges,
+                cumulative_sums,
+                is_first_row: Val::<SC>::zero(),
+                is_last_row: Val::<SC>::zero(),
+                is_transition: Val::<SC>::one(),
+                public_values,
+                constraints: Vec::new(),
+                failures: Vec::new(),
+                record_failures,
+            };
+            if i == 0 {
+                builder.is_first_row = Val::<SC>::one();
+            }
+            if i == height - 1 {
+                builder.is_last_row = Val::<SC>::one();
+                builder.is_transition = Val::<SC>::zero();
+            }
+            let result = catch_unwind_silent(AssertUnwindSafe(|| {
+                chip.eval(&mut builder);
+            }));
+            let panic = result.err().map(|payload| panic_message(payload.as_ref()));
+            (builder.constraints, builder.failures, panic)
         };
-        if i == 0 {
-            builder.is_first_row = Val::<SC>::one();
+
+        let (values, failures, panic) = eval(main_local, main_next, true);
+
+        // Find the columns of the constraints that fail for the first time.
+        let new_failures = failures
+            .iter()
+            .map(|(index, _)| *index)
+            .filter(|index| !dependencies.contains_key(index))
+            .collect::<Vec<_>>();
+        if !new_failures.is_empty() {
+            dependencies.extend(constraint_dependencies(
+                &new_failures,
+                &values,
+                main_local,
+                main_next,
+                &column_names,
+                |local, next| eval(local, next, false).0,
+            ));
+        }
+
+        for (constraint, location) in failures {
+            violations.push(ConstraintViolation {
+                chip: name.clone(),
+                shard: 0,
+                row: i,
+                constraint,
+                location,
+                columns: dependencies[&constraint].clone(),
+            });
         }
-        if i == height - 1 {
-            builder.is_last_row = Val::<SC>::one();
-            builder.is_transition = Val::<SC>::zero();
+        if let Some(message) = panic {
+            violations.push(ConstraintViolation {
+                chip: name.clone(),
+                shard: 0,
+                row: i,
+                constraint: values.len(),
+                location: format!("panicked: {message}"),
+                columns: Vec::new(),
+            });
         }
-        let result = catch_unwind_silent(AssertUnwindSafe(|| {
-            chip.eval(&mut builder);
-        }));
-        if result.is_err() {
-            eprintln!("local: {main_local:?}");
-            eprintln!("next:  {main_next:?}");
-            eprintln!("failed at row {} of chip {}", i, chip.name());
-            exit(1);
+    }
+
+    violations
+}
+
+/// Finds the main trace columns each of the given constraints depends on, by perturbing one
+/// column at a time and checking whether the value of the constraint changes.
+fn constraint_dependencies<F: PrimeField32, EF: ExtensionField<F>>(
+    constraints: &[usize],
+    values: &[EF],
+    main_local: &[F],
+    main_next: &[F],
+    column_names: &[String],
+    mut eval: impl FnMut(&[F], &[F]) -> Vec<EF>,
+) -> HashMap<usize, Vec<String>> {
+    let mut dependencies: HashMap<usize, Vec<String>> =
+        constraints.iter().map(|&constraint| (constraint, Vec::new())).collect();
+    let column_name =
+        |i: usize| column_names.get(i).cloned().unwrap_or_else(|| format!("main[{i}]"));
+
+    for i in 0..main_local.len() + main_next.len() {
+        let mut local = main_local.to_vec();
+        let mut next = main_next.to_vec();
+        let name = if i < local.len() {
+            local[i] += F::from_canonical_u32(PERTURBATION);
+            column_name(i)
+        } else {
+            next[i - local.len()] += F::from_canonical_u32(PERTURBATION);
+            format!("next.{}", column_name(i - local.len()))
+        };
+        let perturbed = eval(&local, &next);
+        for constraint in constraints {
+            if perturbed.get(*constraint) != values.get(*constraint) {
+                dependencies.get_mut(constraint).unwrap().push(name.clone());
+            }
         }
-    });
+    }
+
+    dependencies
 }
 
 fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(f: F) -> std::thread::Result<R> {
@@ -115,6 +315,28 @@ fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(f: F) -> std::th
     result
 }
 
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// The location of the constraint being asserted: the first frame of the backtrace outside of the
+/// builders, or `caller` if the backtrace has no debug info.
+fn constraint_location(caller: &Location<'_>) -> String {
+    const SKIPPED: [&str; 4] = ["/rustc/", "/air/src/", "stark/src/air/builder.rs", file!()];
+    let backtrace = Backtrace::force_capture().to_string();
+    backtrace
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("at "))
+        .find(|path| !SKIPPED.iter().any(|skipped| path.contains(skipped)))
+        .map_or_else(|| caller.to_string(), ToString::to_string)
+}
+
 /// Checks that all the interactions between the chips has been satisfied.
 ///
 /// Note that this does not actually verify the proof.
@@ -134,6 +356,9 @@ pub struct DebugConstraintBuilder<'a, F: Field, EF: ExtensionField<F>> {
     pub(crate) is_last_row: F,
     pub(crate) is_transition: F,
     pub(crate) public_values: &'a [F],
+    pub(crate) constraints: Vec<EF>,
+    pub(crate) failures: Vec<(usize, String)>,
+    pub(crate) record_failures: bool,
 }
 
 impl<'a, F, EF> ExtensionBuilder for DebugConstraintBuilder<'a, F, EF>
@@ -145,11 +370,12 @@ where
     type VarEF = EF;
     type ExprEF = EF;
 
+    #[track_caller]
     fn assert_zero_ext<I>(&mut self, x: I)
     where
         I: Into<Self::ExprEF>,
     {
-        assert_eq!(x.into(), EF::zero(), "constraints must evaluate to zero");
+        self.record_constraint(x.into());
     }
 }
 
@@ -186,14 +412,14 @@ where
     F: Field,
     EF: ExtensionField<F>,
 {
-    #[allow(clippy::unused_self)]
+    /// Record the value of a constraint, which holds if the value is zero.
+    #[track_caller]
     #[inline]
-    fn debug_constraint(&self, x: F, y: F) {
-        if x != y {
-            let backtrace = std::backtrace::Backtrace::force_capture();
-            eprintln!("constraint failed: {x:?} != {y:?}\n{backtrace}");
-            panic!();
+    fn record_constraint(&mut self, value: EF) {
+        if self.record_failures && !value.is_zero() {
+            self.failures.push((self.constraints.len(), constraint_location(Location::caller())));
         }
+        self.constraints.push(value);
     }
 }
 
@@ -227,26 +453,26 @@ where
         self.main
     }
 
+    #[track_caller]
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
-        self.debug_constraint(x.into(), F::zero());
+        self.record_constraint(EF::from_base(x.into()));
     }
 
+    #[track_caller]
     fn assert_one<I: Into<Self::Expr>>(&mut self, x: I) {
-        self.debug_constraint(x.into(), F::one());
+        self.record_constraint(EF::from_base(x.into() - F::one()));
     }
 
+    #[track_caller]
     fn assert_eq<I1: Into<Self::Expr>, I2: Into<Self::Expr>>(&mut self, x: I1, y: I2) {
-        self.debug_constraint(x.into(), y.into());
+        self.record_constraint(EF::from_base(x.into() - y.into()));
     }
 
     /// Assert that `x` is a boolean, i.e. either 0 or 1.
+    #[track_caller]
     fn assert_bool<I: Into<Self::Expr>>(&mut self, x: I) {
         let x = x.into();
-        if x != F::zero() && x != F::one() {
-            let backtrace = std::backtrace::Backtrace::force_capture();
-            eprintln!("constraint failed: {x:?} is not a bool\n{backtrace}");
-            panic!();
-        }
+        self.record_constraint(EF::from_base(x * (x - F::one())));
     }
 }
 
@@ -20,6 +20,9 @@
 #![allow(clippy::if_not_else)]
 #![warn(missing_docs)]
 
+// Lets `#[derive(AlignedBorrow)]` refer to this crate as `sp1_stark` from within it.
+extern crate self as sp1_stark;
+
 pub mod air;
 mod bb31_poseidon2;
 mod chip;
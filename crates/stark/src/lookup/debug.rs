@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt::Display};
 
 use p3_baby_bear::BabyBear;
 use p3_field::{AbstractField, Field, PrimeField32, PrimeField64};
@@ -10,6 +10,10 @@ use crate::{
     MachineChip, StarkGenericConfig, StarkMachine, StarkProvingKey, Val,
 };
 
+/// The number of unbalanced messages of each kind shown when an [`InteractionReport`] is
+/// displayed.
+const MAX_DISPLAYED_IMBALANCES: usize = 16;
+
 /// The data for an interaction.
 #[derive(Debug)]
 pub struct InteractionData<F: Field> {
@@ -17,6 +21,10 @@ pub struct InteractionData<F: Field> {
     pub chip_name: String,
     /// The kind of interaction.
     pub kind: InteractionKind,
+    /// The scope of the interaction.
+    pub scope: InteractionScope,
+    /// The values of the interaction's message.
+    pub values: Vec<F>,
     /// The row of the interaction.
     pub row: usize,
     /// The interaction number.
@@ -105,11 +113,13 @@ pub fn debug_interactions<SC: StarkGenericConfig, A: MachineAir<Val<SC>>>(
                     "{} {} {}",
                     &interaction.scope.to_string(),
                     &interaction.kind.to_string(),
-                    vec_to_string(values)
+                    vec_to_string(values.clone())
                 );
                 key_to_vec_data.entry(key.clone()).or_insert_with(Vec::new).push(InteractionData {
                     chip_name: chip.name(),
                     kind: interaction.kind,
+                    scope: interaction.scope,
+                    values,
                     row,
                     interaction_number: m,
                     is_send,
@@ -128,16 +138,147 @@ pub fn debug_interactions<SC: StarkGenericConfig, A: MachineAir<Val<SC>>>(
     (key_to_vec_data, key_to_count)
 }
 
-/// Calculate the number of times we send and receive each event of the given interaction type,
-/// and print out the ones for which the set of sends and receives don't match.
+/// A row of a chip that sends or receives an unbalanced message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InteractionSite {
+    /// The name of the chip.
+    pub chip: String,
+    /// The index of the shard among the records being debugged.
+    pub shard: usize,
+    /// The row of the chip's main trace.
+    pub row: usize,
+    /// The index of the interaction among the chip's sends followed by its receives.
+    pub interaction: usize,
+    /// The multiplicity of the interaction on the row.
+    pub multiplicity: i32,
+}
+
+impl Display for InteractionSite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} shard {} row {} (interaction {}, multiplicity {})",
+            self.chip, self.shard, self.row, self.interaction, self.multiplicity
+        )
+    }
+}
+
+/// A message whose sends and receives don't balance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InteractionImbalance {
+    /// The kind of the interactions.
+    pub kind: InteractionKind,
+    /// The scope of the interactions.
+    pub scope: InteractionScope,
+    /// The values of the message, as canonical integers.
+    pub values: Vec<u32>,
+    /// The number of times the message is sent minus the number of times it is received.
+    pub balance: i32,
+    /// The rows that send the message.
+    pub senders: Vec<InteractionSite>,
+    /// The rows that receive the message.
+    pub receivers: Vec<InteractionSite>,
+    /// The event the message originates from, if it could be found.
+    pub origin: Option<String>,
+}
+
+impl InteractionImbalance {
+    /// The rows that send or receive the message.
+    pub fn sites(&self) -> impl Iterator<Item = &InteractionSite> {
+        self.senders.iter().chain(self.receivers.iter())
+    }
+}
+
+impl Display for InteractionImbalance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let values = self.values.iter().map(ToString::to_string).collect::<Vec<_>>();
+        write!(f, "({}) balance {:+}", values.join(", "), self.balance)?;
+        if let Some(origin) = &self.origin {
+            write!(f, " from {origin}")?;
+        }
+        for sender in &self.senders {
+            write!(f, "\n    sent by {sender}")?;
+        }
+        for receiver in &self.receivers {
+            write!(f, "\n    received by {receiver}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The messages whose sends and receives don't balance across the chips of a machine.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InteractionReport {
+    /// Every unbalanced message, ordered by scope, kind and values.
+    pub imbalances: Vec<InteractionImbalance>,
+}
+
+impl InteractionReport {
+    /// Whether every message is received as many times as it is sent.
+    #[must_use]
+    pub fn is_balanced(&self) -> bool {
+        self.imbalances.is_empty()
+    }
+
+    /// The unbalanced messages grouped by scope and kind.
+    #[must_use]
+    pub fn grouped(
+        &self,
+    ) -> BTreeMap<(InteractionScope, InteractionKind), Vec<&InteractionImbalance>> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        for imbalance in &self.imbalances {
+            groups.entry((imbalance.scope, imbalance.kind)).or_default().push(imbalance);
+        }
+        groups
+    }
+
+    /// The unbalanced messages of the given kind and scope.
+    pub fn imbalances_of(
+        &self,
+        kind: InteractionKind,
+        scope: InteractionScope,
+    ) -> impl Iterator<Item = &InteractionImbalance> {
+        self.imbalances.iter().filter(move |i| i.kind == kind && i.scope == scope)
+    }
+
+    /// Attach the originating event to each unbalanced message, as named by `describe`.
+    pub fn describe_origins(&mut self, describe: impl Fn(&InteractionImbalance) -> Option<String>) {
+        for imbalance in &mut self.imbalances {
+            imbalance.origin = describe(imbalance);
+        }
+    }
+}
+
+impl Display for InteractionReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_balanced() {
+            return write!(f, "all interactions are balanced");
+        }
+        for ((scope, kind), imbalances) in self.grouped() {
+            writeln!(f, "{scope} {kind}: {} unbalanced messages", imbalances.len())?;
+            for imbalance in imbalances.iter().take(MAX_DISPLAYED_IMBALANCES) {
+                writeln!(f, "  {imbalance}")?;
+            }
+            if imbalances.len() > MAX_DISPLAYED_IMBALANCES {
+                writeln!(f, "  ... and {} more", imbalances.len() - MAX_DISPLAYED_IMBALANCES)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Find the messages of the given interaction kinds that are not received as many times as they
+/// are sent across the chips of the machine, and the rows that send and receive them.
+///
+/// For the local scope, exactly one shard must be given.
 #[allow(clippy::needless_pass_by_value)]
-pub fn debug_interactions_with_all_chips<SC, A>(
+pub fn interaction_report<SC, A>(
     machine: &StarkMachine<SC, A>,
     pkey: &StarkProvingKey<SC>,
     shards: &[A::Record],
     interaction_kinds: Vec<InteractionKind>,
     scope: InteractionScope,
-) -> bool
+) -> InteractionReport
 where
     SC: StarkGenericConfig,
     SC::Val: PrimeField32,
@@ -147,69 +288,79 @@ where
         assert!(shards.len() == 1);
     }
 
-    let mut final_map = BTreeMap::new();
-    let mut total = SC::Val::zero();
-
-    let chips = machine.chips();
-    for chip in chips.iter() {
-        let mut total_events = 0;
-        for shard in shards {
-            let (_, count) =
+    let mut messages = BTreeMap::<_, (SC::Val, Vec<InteractionSite>, Vec<InteractionSite>)>::new();
+    for chip in machine.chips() {
+        for (shard_index, shard) in shards.iter().enumerate() {
+            let (data, _) =
                 debug_interactions::<SC, A>(chip, pkey, shard, interaction_kinds.clone(), scope);
-            total_events += count.len();
-            for (key, value) in count.iter() {
-                let entry =
-                    final_map.entry(key.clone()).or_insert((SC::Val::zero(), BTreeMap::new()));
-                entry.0 += *value;
-                total += *value;
-                *entry.1.entry(chip.name()).or_insert(SC::Val::zero()) += *value;
+            for interaction in data.into_values().flatten() {
+                let values = interaction
+                    .values
+                    .iter()
+                    .map(PrimeField32::as_canonical_u32)
+                    .collect::<Vec<_>>();
+                let (balance, senders, receivers) = messages
+                    .entry((interaction.scope, interaction.kind, values))
+                    .or_insert_with(|| (SC::Val::zero(), Vec::new(), Vec::new()));
+                let site = InteractionSite {
+                    chip: interaction.chip_name,
+                    shard: shard_index,
+                    row: interaction.row,
+                    interaction: interaction.interaction_number,
+                    multiplicity: field_to_int(interaction.multiplicity),
+                };
+                if interaction.is_send {
+                    *balance += interaction.multiplicity;
+                    senders.push(site);
+                } else {
+                    *balance -= interaction.multiplicity;
+                    receivers.push(site);
+                }
             }
         }
-        tracing::info!("{} chip has {} distinct events", chip.name(), total_events);
     }
 
-    tracing::info!("Final counts below.");
-    tracing::info!("==================");
-
-    let mut any_nonzero = false;
-    for (key, (value, chip_values)) in final_map.clone() {
-        if !Val::<SC>::is_zero(&value) {
-            tracing::info!(
-                "Interaction key: {} Send-Receive Discrepancy: {}",
-                key,
-                field_to_int(value)
-            );
-            any_nonzero = true;
-            for (chip, chip_value) in chip_values {
-                tracing::info!(
-                    " {} chip's send-receive discrepancy for this key is {}",
-                    chip,
-                    field_to_int(chip_value)
-                );
-            }
-        }
-    }
+    let imbalances = messages
+        .into_iter()
+        .filter(|(_, (balance, _, _))| !balance.is_zero())
+        .map(|((scope, kind, values), (balance, senders, receivers))| InteractionImbalance {
+            kind,
+            scope,
+            values,
+            balance: field_to_int(balance),
+            senders,
+            receivers,
+            origin: None,
+        })
+        .collect();
+    InteractionReport { imbalances }
+}
 
-    tracing::info!("==================");
-    if !any_nonzero {
+/// Calculate the number of times we send and receive each event of the given interaction type,
+/// and log the ones for which the set of sends and receives don't match.
+///
+/// Returns whether every message is balanced. Use [`interaction_report`] to inspect the unbalanced
+/// messages instead of logging them.
+#[allow(clippy::needless_pass_by_value)]
+pub fn debug_interactions_with_all_chips<SC, A>(
+    machine: &StarkMachine<SC, A>,
+    pkey: &StarkProvingKey<SC>,
+    shards: &[A::Record],
+    interaction_kinds: Vec<InteractionKind>,
+    scope: InteractionScope,
+) -> bool
+where
+    SC: StarkGenericConfig,
+    SC::Val: PrimeField32,
+    A: MachineAir<SC::Val>,
+{
+    let report = interaction_report(machine, pkey, shards, interaction_kinds, scope);
+    if report.is_balanced() {
         tracing::info!("All chips have the same number of sends and receives.");
     } else {
-        tracing::info!("Positive values mean sent more than received.");
-        tracing::info!("Negative values mean received more than sent.");
-        if total != SC::Val::zero() {
-            tracing::info!("Total send-receive discrepancy: {}", field_to_int(total));
-            if field_to_int(total) > 0 {
-                tracing::info!("you're sending more than you are receiving");
-            } else {
-                tracing::info!("you're receiving more than you are sending");
-            }
-        } else {
-            tracing::info!(
-                "the total number of sends and receives match, but the keys don't match"
-            );
-            tracing::info!("check the arguments");
-        }
+        tracing::info!("Unbalanced interactions:\n{report}");
+        tracing::info!("Positive balances mean sent more than received.");
+        tracing::info!("Negative balances mean received more than sent.");
     }
-
-    !any_nonzero
+    report.is_balanced()
 }
@@ -7,15 +7,25 @@ use p3_field::{AbstractExtensionField, AbstractField, Field, PrimeField32};
 use p3_matrix::{dense::RowMajorMatrix, Dimensions, Matrix};
 use p3_maybe_rayon::prelude::*;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::{array, cmp::Reverse, env, fmt::Debug, time::Instant};
+use std::{
+    array,
+    cmp::Reverse,
+    env,
+    fmt::Debug,
+    fs::{self, File},
+    io::{self, BufWriter},
+    path::Path,
+    time::Instant,
+};
 use tracing::instrument;
 
 use super::{debug_constraints, Dom};
 use crate::{
-    air::{InteractionScope, MachineAir, MachineProgram},
-    lookup::{debug_interactions_with_all_chips, InteractionKind},
+    air::{write_trace_csv, InteractionScope, MachineAir, MachineProgram},
+    lookup::{interaction_report, InteractionKind},
     record::MachineRecord,
-    DebugConstraintBuilder, ShardProof, VerifierConstraintFolder,
+    ConstraintReport, ConstraintViolation, DebugConstraintBuilder, ShardProof,
+    VerifierConstraintFolder,
 };
 
 use super::{
@@ -359,14 +369,30 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         })
     }
 
-    /// Debugs the constraints of the given records.
+    /// Writes the main trace of every chip included in the shard as a CSV file named after the
+    /// chip, with a header row of column names.
+    pub fn write_trace_csvs(&self, shard: &A::Record, dir: &Path) -> io::Result<()>
+    where
+        SC::Val: PrimeField32,
+    {
+        fs::create_dir_all(dir)?;
+        for chip in self.shard_chips(shard) {
+            let trace = chip.generate_trace(shard, &mut A::Record::default());
+            let file = File::create(dir.join(format!("{}.csv", chip.name())))?;
+            write_trace_csv(BufWriter::new(file), &chip.column_names(), &trace)?;
+        }
+        Ok(())
+    }
+
+    /// Debugs the constraints of the given records, returning every violated constraint.
     #[instrument("debug constraints", level = "debug", skip_all)]
     pub fn debug_constraints(
         &self,
         pk: &StarkProvingKey<SC>,
         records: Vec<A::Record>,
         challenger: &mut SC::Challenger,
-    ) where
+    ) -> ConstraintReport
+    where
         SC::Val: PrimeField32,
         A: for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
     {
@@ -383,8 +409,9 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
             permutation_challenges.push(challenger.sample_ext_element());
         }
 
+        let mut report = ConstraintReport::default();
         let mut global_cumulative_sum = SC::Challenge::zero();
-        for shard in records.iter() {
+        for (shard_index, shard) in records.iter().enumerate() {
             // Filter the chips based on what is used.
             let chips = self.shard_chips(shard).collect::<Vec<_>>();
 
@@ -424,16 +451,23 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                 cumulative_sums.iter().map(|sum| sum[1]).sum::<SC::Challenge>();
             if !local_cumulative_sum.is_zero() {
                 tracing::warn!("Local cumulative sum is not zero");
-                tracing::debug_span!("debug local interactions").in_scope(|| {
-                    debug_interactions_with_all_chips::<SC, A>(
-                        self,
-                        pk,
-                        &[shard.clone()],
-                        InteractionKind::all_kinds(),
-                        InteractionScope::Local,
-                    )
-                });
-                panic!("Local cumulative sum is not zero");
+                let interactions = interaction_report::<SC, A>(
+                    self,
+                    pk,
+                    &[shard.clone()],
+                    InteractionKind::all_kinds(),
+                    InteractionScope::Local,
+                );
+                report.interactions.imbalances.extend(interactions.imbalances.into_iter().map(
+                    |mut imbalance| {
+                        for site in
+                            imbalance.senders.iter_mut().chain(imbalance.receivers.iter_mut())
+                        {
+                            site.shard = shard_index;
+                        }
+                        imbalance
+                    },
+                ));
             }
 
             // Compute some statistics.
@@ -459,7 +493,7 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                     for i in 0..chips.len() {
                         let preprocessed_trace =
                             pk.chip_ordering.get(&chips[i].name()).map(|index| &pk.traces[*index]);
-                        debug_constraints::<SC, A>(
+                        let violations = debug_constraints::<SC, A>(
                             chips[i],
                             preprocessed_trace,
                             &traces[i].0,
@@ -468,27 +502,34 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                             &shard.public_values(),
                             &cumulative_sums[i],
                         );
+                        report.violations.extend(violations.into_iter().map(|violation| {
+                            ConstraintViolation { shard: shard_index, ..violation }
+                        }));
                     }
                 });
             }
         }
 
-        tracing::info!("Constraints verified successfully");
-
-        // If the global cumulative sum is not zero, debug the interactions.
+        // If the global cumulative sum is not zero, report the unbalanced global interactions.
         if !global_cumulative_sum.is_zero() {
             tracing::warn!("Global cumulative sum is not zero");
-            tracing::debug_span!("debug global interactions").in_scope(|| {
-                debug_interactions_with_all_chips::<SC, A>(
-                    self,
-                    pk,
-                    &records,
-                    InteractionKind::all_kinds(),
-                    InteractionScope::Global,
-                )
-            });
-            panic!("Global cumulative sum is not zero");
+            let interactions = interaction_report::<SC, A>(
+                self,
+                pk,
+                &records,
+                InteractionKind::all_kinds(),
+                InteractionScope::Global,
+            );
+            report.interactions.imbalances.extend(interactions.imbalances);
         }
+
+        if report.is_ok() {
+            tracing::info!("Constraints verified successfully");
+        } else {
+            tracing::error!("Constraints failed:\n{report}");
+        }
+
+        report
     }
 }
 
@@ -1,14 +1,17 @@
-use std::env;
+use std::{env, fmt::Display, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
 
+const MIN_SHARD_SIZE: usize = 1 << 16;
 const MAX_SHARD_SIZE: usize = 1 << 21;
 const RECURSION_MAX_SHARD_SIZE: usize = 1 << 22;
 const MAX_SHARD_BATCH_SIZE: usize = 8;
 const DEFAULT_TRACE_GEN_WORKERS: usize = 1;
 const DEFAULT_CHECKPOINTS_CHANNEL_CAPACITY: usize = 128;
 const DEFAULT_RECORDS_AND_TRACES_CHANNEL_CAPACITY: usize = 1;
+const MAX_TRACE_GEN_WORKERS: usize = 4;
+const MAX_RECORDS_AND_TRACES_CHANNEL_CAPACITY: usize = 4;
 
 /// The threshold for splitting deferred events.
 pub const MAX_DEFERRED_SPLIT_THRESHOLD: usize = 1 << 18;
@@ -28,6 +31,126 @@ impl Default for SP1ProverOpts {
     }
 }
 
+impl SP1ProverOpts {
+    /// Get options whose estimated peak memory usage under `model` fits in `budget` bytes.
+    ///
+    /// Larger shards are preferred over larger batches, and larger batches over more trace
+    /// generation workers and deeper channels, since larger shards leave fewer proofs for the
+    /// recursion prover to aggregate. If no options fit, the smallest ones are used.
+    #[must_use]
+    pub fn with_memory_budget_for(budget: u64, model: &MemoryModel) -> Self {
+        let mut opts = Self::default();
+        let fits = |core_opts: &SP1CoreOpts| core_opts.estimate_memory(model).peak_bytes <= budget;
+
+        // Pick the shard size and batch size with a single worker and the shallowest channel.
+        let mut core_opts = opts.core_opts;
+        core_opts.trace_gen_workers = 1;
+        core_opts.records_and_traces_channel_capacity = 1;
+        let candidates = (MIN_SHARD_SIZE.ilog2()..=MAX_SHARD_SIZE.ilog2()).rev().flat_map(|log| {
+            (0..=MAX_SHARD_BATCH_SIZE.ilog2())
+                .rev()
+                .map(move |log_batch| (1 << log, 1 << log_batch))
+        });
+        let (shard_size, shard_batch_size) = candidates
+            .clone()
+            .find(|&(shard_size, shard_batch_size)| {
+                fits(&SP1CoreOpts { shard_size, shard_batch_size, ..core_opts })
+            })
+            .unwrap_or_else(|| candidates.last().unwrap());
+        core_opts.shard_size = shard_size;
+        core_opts.shard_batch_size = shard_batch_size;
+
+        // Spend the remaining budget on more workers, then on deeper channels.
+        while core_opts.trace_gen_workers < MAX_TRACE_GEN_WORKERS
+            && fits(&SP1CoreOpts {
+                trace_gen_workers: core_opts.trace_gen_workers + 1,
+                ..core_opts
+            })
+        {
+            core_opts.trace_gen_workers += 1;
+        }
+        while core_opts.records_and_traces_channel_capacity
+            < MAX_RECORDS_AND_TRACES_CHANNEL_CAPACITY
+            && fits(&SP1CoreOpts {
+                records_and_traces_channel_capacity: core_opts.records_and_traces_channel_capacity
+                    + 1,
+                ..core_opts
+            })
+        {
+            core_opts.records_and_traces_channel_capacity += 1;
+        }
+        opts.core_opts = core_opts;
+
+        // The recursion prover has a fixed shard size, but shares the concurrency settings.
+        opts.recursion_opts.shard_batch_size = core_opts.shard_batch_size;
+        opts.recursion_opts.trace_gen_workers = core_opts.trace_gen_workers;
+        opts.recursion_opts.records_and_traces_channel_capacity =
+            core_opts.records_and_traces_channel_capacity;
+
+        let estimate = core_opts.estimate_memory(model);
+        tracing::info!(
+            "memory budget {} MiB: shard_size={}, shard_batch_size={}, trace_gen_workers={}, \
+             records_and_traces_channel_capacity={}, {}",
+            budget >> 20,
+            core_opts.shard_size,
+            core_opts.shard_batch_size,
+            core_opts.trace_gen_workers,
+            core_opts.records_and_traces_channel_capacity,
+            estimate,
+        );
+        if estimate.peak_bytes > budget {
+            tracing::warn!("no prover options fit the memory budget of {} MiB", budget >> 20);
+        }
+
+        opts
+    }
+}
+
+/// A model of the memory used to prove a shard, used to pick options that fit a memory budget.
+///
+/// The cells of a row count everything the prover holds for it while proving a shard: the main
+/// and permutation traces, the quotient chunks, their low-degree extensions, and the Merkle trees
+/// committing to them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryModel {
+    /// The number of cells per cycle of a core shard.
+    pub cells_per_cycle: u64,
+    /// The number of cells per row of a precompile shard, which has [`SplitOpts::deferred`] rows.
+    pub cells_per_deferred_row: u64,
+    /// The number of cells per event of a shard initializing and finalizing memory, which has
+    /// [`SplitOpts::memory`] events.
+    pub cells_per_memory_event: u64,
+    /// The number of cells in every shard, independent of the number of cycles.
+    pub fixed_cells_per_shard: u64,
+    /// The number of bytes of a cell.
+    pub bytes_per_cell: u64,
+    /// The number of bytes of the execution record per cycle of a shard.
+    pub record_bytes_per_cycle: u64,
+}
+
+/// An estimate of the peak memory used by the core prover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryEstimate {
+    /// The number of bytes used by the record and traces of a single shard.
+    pub bytes_per_shard: u64,
+    /// The maximum number of shards held in memory at once.
+    pub shards_in_flight: u64,
+    /// The estimated peak number of bytes used.
+    pub peak_bytes: u64,
+}
+
+impl Display for MemoryEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "estimated peak memory {} MiB ({} MiB per shard, {} shards in flight)",
+            self.peak_bytes >> 20,
+            self.bytes_per_shard >> 20,
+            self.shards_in_flight
+        )
+    }
+}
+
 /// Options for the core prover.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SP1CoreOpts {
@@ -45,6 +168,8 @@ pub struct SP1CoreOpts {
     pub checkpoints_channel_capacity: usize,
     /// The capacity of the channel for records and traces.
     pub records_and_traces_channel_capacity: usize,
+    /// Options for dumping shard records and traces to disk.
+    pub trace_dump: TraceDumpOpts,
 }
 
 /// Calculate the default shard size using an empirically determined formula.
@@ -114,6 +239,7 @@ impl Default for SP1CoreOpts {
                     |_| DEFAULT_RECORDS_AND_TRACES_CHANNEL_CAPACITY,
                     |s| s.parse::<usize>().unwrap_or(DEFAULT_RECORDS_AND_TRACES_CHANNEL_CAPACITY),
                 ),
+            trace_dump: TraceDumpOpts::default(),
         }
     }
 }
@@ -129,6 +255,76 @@ impl SP1CoreOpts {
         opts.shard_size = RECURSION_MAX_SHARD_SIZE;
         opts
     }
+
+    /// Estimate the peak memory used by the core prover with these options.
+    ///
+    /// Every trace generation worker holds a batch of shards while generating its traces, the
+    /// records and traces channel buffers more batches, and the prover holds one batch while
+    /// committing to it.
+    #[must_use]
+    pub fn estimate_memory(&self, model: &MemoryModel) -> MemoryEstimate {
+        // A shard is either a core shard, a precompile shard or a memory shard, so the largest
+        // of them bounds the cells of every shard.
+        let cells = (self.shard_size as u64 * model.cells_per_cycle)
+            .max(self.split_opts.deferred as u64 * model.cells_per_deferred_row)
+            .max(self.split_opts.memory as u64 * model.cells_per_memory_event)
+            + model.fixed_cells_per_shard;
+        let bytes_per_shard =
+            cells * model.bytes_per_cell + self.shard_size as u64 * model.record_bytes_per_cycle;
+        let shards_in_flight = (self.shard_batch_size
+            * (self.trace_gen_workers + self.records_and_traces_channel_capacity + 1))
+            as u64;
+        MemoryEstimate {
+            bytes_per_shard,
+            shards_in_flight,
+            peak_bytes: bytes_per_shard.saturating_mul(shards_in_flight),
+        }
+    }
+}
+
+/// Options for dumping the record and traces of shards while proving, so that a single chip of a
+/// failing shard can be replayed offline.
+///
+/// The options are `Copy` like the rest of [`SP1CoreOpts`], so the dump directory and the chips to
+/// dump are read from the `TRACE_DUMP_DIR` and `TRACE_DUMP_CHIPS` environment variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceDumpOpts {
+    /// Whether to dump shards.
+    pub enabled: bool,
+    /// Only dump the shard with this number, or every shard if `None`.
+    pub shard: Option<u32>,
+}
+
+impl Default for TraceDumpOpts {
+    fn default() -> Self {
+        Self {
+            enabled: env::var("TRACE_DUMP_DIR").is_ok(),
+            shard: env::var("TRACE_DUMP_SHARD").ok().and_then(|s| s.parse::<u32>().ok()),
+        }
+    }
+}
+
+impl TraceDumpOpts {
+    /// Whether the shard with the given number should be dumped.
+    #[must_use]
+    pub fn includes(&self, shard: u32) -> bool {
+        self.enabled && self.shard.map_or(true, |s| s == shard)
+    }
+
+    /// The directory shards are dumped to, `TRACE_DUMP_DIR` or `trace-dump` by default.
+    #[must_use]
+    pub fn dir() -> PathBuf {
+        env::var("TRACE_DUMP_DIR").map_or_else(|_| PathBuf::from("trace-dump"), PathBuf::from)
+    }
+
+    /// The names of the chips whose traces are dumped, from the comma-separated `TRACE_DUMP_CHIPS`,
+    /// or `None` to dump every chip included in the shard.
+    #[must_use]
+    pub fn chips() -> Option<Vec<String>> {
+        env::var("TRACE_DUMP_CHIPS")
+            .ok()
+            .map(|chips| chips.split(',').map(|chip| chip.trim().to_string()).collect())
+    }
 }
 
 /// Options for splitting deferred events.
@@ -159,3 +355,43 @@ impl SplitOpts {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: MemoryModel = MemoryModel {
+        cells_per_cycle: 768,
+        cells_per_deferred_row: 512,
+        cells_per_memory_event: 128,
+        fixed_cells_per_shard: 1 << 22,
+        bytes_per_cell: 4,
+        record_bytes_per_cycle: 256,
+    };
+
+    #[test]
+    fn memory_budget_fits() {
+        for budget_gib in [4u64, 16, 64, 256] {
+            let budget = budget_gib << 30;
+            let opts = SP1ProverOpts::with_memory_budget_for(budget, &MODEL);
+            assert!(opts.core_opts.estimate_memory(&MODEL).peak_bytes <= budget);
+        }
+    }
+
+    #[test]
+    fn memory_budget_prefers_larger_shards() {
+        let small = SP1ProverOpts::with_memory_budget_for(4 << 30, &MODEL).core_opts;
+        let large = SP1ProverOpts::with_memory_budget_for(256 << 30, &MODEL).core_opts;
+        assert!(small.shard_size <= large.shard_size);
+        assert_eq!(large.shard_size, MAX_SHARD_SIZE);
+    }
+
+    #[test]
+    fn memory_budget_too_small() {
+        let opts = SP1ProverOpts::with_memory_budget_for(0, &MODEL).core_opts;
+        assert_eq!(opts.shard_size, MIN_SHARD_SIZE);
+        assert_eq!(opts.shard_batch_size, 1);
+        assert_eq!(opts.trace_gen_workers, 1);
+        assert_eq!(opts.records_and_traces_channel_capacity, 1);
+    }
+}
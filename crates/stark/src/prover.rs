@@ -19,9 +19,9 @@ use super::{
 };
 use crate::{
     air::MachineAir, config::ZeroCommitment, lookup::InteractionBuilder, opts::SP1CoreOpts,
-    record::MachineRecord, Challenger, DebugConstraintBuilder, MachineChip, MachineProof,
-    PackedChallenge, PcsProverData, ProverConstraintFolder, ShardCommitment, ShardMainData,
-    ShardProof, StarkVerifyingKey,
+    record::MachineRecord, Challenger, ConstraintReport, DebugConstraintBuilder, MachineChip,
+    MachineProof, PackedChallenge, PcsProverData, ProverConstraintFolder, ShardCommitment,
+    ShardMainData, ShardProof, StarkVerifyingKey,
 };
 
 /// A merged prover data item from the global and local prover data.
@@ -155,17 +155,18 @@ pub trait MachineProver<SC: StarkGenericConfig, A: MachineAir<SC::Val>>:
         self.machine().shard_chips(record)
     }
 
-    /// Debug the constraints for the given inputs.
+    /// Debug the constraints for the given inputs, returning every violated constraint.
     fn debug_constraints(
         &self,
         pk: &StarkProvingKey<SC>,
         records: Vec<A::Record>,
         challenger: &mut SC::Challenger,
-    ) where
+    ) -> ConstraintReport
+    where
         SC::Val: PrimeField32,
         A: for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
     {
-        self.machine().debug_constraints(pk, records, challenger);
+        self.machine().debug_constraints(pk, records, challenger)
     }
 
     /// Merge the global and local chips' sorted traces.
@@ -0,0 +1,137 @@
+//! The named channels of public values and the Merkle tree committing to them.
+//!
+//! The guest computes the digest it commits to with the same functions as the host, so this crate
+//! only depends on `sha2`, and is re-exported as `sp1_primitives::channels`.
+
+use sha2::{Digest, Sha256};
+
+/// Split a write to `sp1_primitives::io::FD_PUBLIC_VALUE_CHANNELS` into the channel name and the
+/// bytes, or return `None` if it is malformed, names the default channel or the name is not UTF-8.
+pub fn decode_channel_write(frame: &[u8]) -> Option<(&str, &[u8])> {
+    let (len, rest) = frame.split_first_chunk::<4>()?;
+    let len = u32::from_le_bytes(*len) as usize;
+    if len == 0 || len > rest.len() {
+        return None;
+    }
+    let (name, data) = rest.split_at(len);
+    Some((core::str::from_utf8(name).ok()?, data))
+}
+
+/// Encode a write of `data` to the named channel, as [`decode_channel_write`] splits it.
+pub fn encode_channel_write(name: &str, data: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(4 + name.len() + data.len());
+    frame.extend_from_slice(&(name.len() as u32).to_le_bytes());
+    frame.extend_from_slice(name.as_bytes());
+    frame.extend_from_slice(data);
+    frame
+}
+
+/// The channel `sp1_zkvm::io::input_region` commits the SHA-256 digest of the input region with
+/// the given index to.
+pub fn input_region_channel(index: usize) -> String {
+    format!("input_region/{index}")
+}
+
+/// The leaf of a channel in the Merkle tree of the public values: the SHA-256 hash of `0x00`, the
+/// little-endian `u32` length of the name, the name, and the digest of the channel.
+pub fn channel_leaf(name: &str, digest: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0u8]);
+    hasher.update((name.len() as u32).to_le_bytes());
+    hasher.update(name.as_bytes());
+    hasher.update(digest);
+    hasher.finalize().into()
+}
+
+/// An inner node of the Merkle tree: the SHA-256 hash of `0x01` and its two children.
+fn channel_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([1u8]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// The root of the Merkle tree with the given leaves.
+///
+/// The leaves are paired up level by level, and the last node of a level with an odd number of
+/// nodes moves up to the next level as is.
+///
+/// # Panics
+///
+/// Panics if there are no leaves.
+pub fn channels_root(leaves: impl IntoIterator<Item = [u8; 32]>) -> [u8; 32] {
+    // The roots of the complete subtrees over the leaves so far, with their heights. Like the
+    // digits of a binary counter, adding a leaf merges the subtrees of the same height, so there
+    // are at most 33 of them.
+    let mut subtrees = [([0u8; 32], 0u32); 33];
+    let mut len = 0;
+    for leaf in leaves {
+        let mut subtree = (leaf, 0);
+        while len > 0 && subtrees[len - 1].1 == subtree.1 {
+            len -= 1;
+            subtree = (channel_node(&subtrees[len].0, &subtree.0), subtree.1 + 1);
+        }
+        subtrees[len] = subtree;
+        len += 1;
+    }
+    assert!(len > 0, "the tree has no leaves");
+
+    // The last node of an odd level moves up, so the subtrees are joined from the right.
+    let (mut root, _) = subtrees[len - 1];
+    for (subtree, _) in subtrees[..len - 1].iter().rev() {
+        root = channel_node(subtree, &root);
+    }
+    root
+}
+
+/// The digest of the public values a program commits to.
+///
+/// Without named channels, this is the digest of the public values stream. Otherwise it is the
+/// root of the Merkle tree whose leaves are the stream, under the empty name, followed by the
+/// channels in order of their names.
+pub fn public_values_digest<'a>(
+    stream_digest: [u8; 32],
+    channels: impl IntoIterator<Item = (&'a str, [u8; 32])>,
+) -> [u8; 32] {
+    let mut channels = channels.into_iter().peekable();
+    if channels.peek().is_none() {
+        return stream_digest;
+    }
+    channels_root(
+        core::iter::once(channel_leaf("", &stream_digest))
+            .chain(channels.map(|(name, digest)| channel_leaf(name, &digest))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_channel_write() {
+        assert_eq!(decode_channel_write(b"\x03\0\0\0abcdef"), Some(("abc", &b"def"[..])));
+        assert_eq!(decode_channel_write(b"\x03\0\0\0abc"), Some(("abc", &b""[..])));
+        assert_eq!(decode_channel_write(b"\x04\0\0\0abc"), None);
+        assert_eq!(decode_channel_write(b"\0\0\0\0abc"), None);
+        assert_eq!(decode_channel_write(b"\x01\0"), None);
+        assert_eq!(decode_channel_write(b"\x01\0\0\0\xffabc"), None);
+
+        let frame = encode_channel_write("abc", b"def");
+        assert_eq!(frame, b"\x03\0\0\0abcdef");
+        assert_eq!(decode_channel_write(&frame), Some(("abc", &b"def"[..])));
+    }
+
+    #[test]
+    fn test_channels_root() {
+        // The tree of 7 leaves pairs (0, 1), (2, 3) and (4, 5), and moves 6 up twice.
+        let leaves = (0..7u8).map(|i| [i; 32]).collect::<Vec<_>>();
+        let left = channel_node(
+            &channel_node(&leaves[0], &leaves[1]),
+            &channel_node(&leaves[2], &leaves[3]),
+        );
+        let right = channel_node(&channel_node(&leaves[4], &leaves[5]), &leaves[6]);
+        assert_eq!(channels_root(leaves.clone()), channel_node(&left, &right));
+        assert_eq!(channels_root([leaves[0]]), leaves[0]);
+    }
+}
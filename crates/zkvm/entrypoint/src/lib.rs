@@ -1,10 +1,13 @@
 extern crate alloc;
 
 pub mod heap;
+#[cfg(feature = "lib")]
+mod regions;
 pub mod syscalls;
 
 #[cfg(feature = "lib")]
 pub mod io {
+    pub use crate::regions::input_region;
     pub use sp1_lib::io::*;
 }
 
@@ -16,6 +19,14 @@ pub mod lib {
 #[cfg(all(target_os = "zkvm", feature = "libm"))]
 mod libm;
 
+/// Halt the program with the given exit code, after committing to the public values.
+///
+/// The host sees a non-zero exit code as an `ExecutionError::HaltWithNonZeroExitCode`, or an
+/// `ExecutionError::HaltWithReport` when executing with `SP1Prover::execute`.
+pub fn exit(exit_code: u8) -> ! {
+    syscalls::syscall_halt(exit_code)
+}
+
 /// The number of 32 bit words that the public values digest is composed of.
 pub const PV_DIGEST_NUM_WORDS: usize = 8;
 pub const POSEIDON_NUM_WORDS: usize = 8;
@@ -24,6 +35,7 @@ pub const POSEIDON_NUM_WORDS: usize = 8;
 mod zkvm {
     use crate::syscalls::syscall_halt;
 
+    use alloc::{collections::BTreeMap, string::String, vec::Vec};
     use cfg_if::cfg_if;
     use sha2::{Digest, Sha256};
 
@@ -38,10 +50,43 @@ mod zkvm {
 
     pub static mut PUBLIC_VALUES_HASHER: Option<Sha256> = None;
 
+    /// The hashers of the named channels of public values, by name.
+    pub static mut PUBLIC_VALUE_CHANNELS: BTreeMap<String, Sha256> = BTreeMap::new();
+
+    /// Report the message and location of a panic to the host before the default hook prints
+    /// it to stderr.
+    fn set_panic_hook() {
+        // Must match `sp1_core_executor::FD_PANIC`.
+        const FD_PANIC: u32 = 11;
+
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(alloc::boxed::Box::new(move |info| {
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<alloc::string::String>().map(|s| s.as_str()))
+                .unwrap_or_default();
+            let (file, line, column) =
+                info.location().map_or(("", 0, 0), |l| (l.file(), l.line(), l.column()));
+
+            let mut report = Vec::with_capacity(12 + file.len() + message.len());
+            report.extend_from_slice(&line.to_le_bytes());
+            report.extend_from_slice(&column.to_le_bytes());
+            report.extend_from_slice(&(file.len() as u32).to_le_bytes());
+            report.extend_from_slice(file.as_bytes());
+            report.extend_from_slice(message.as_bytes());
+            crate::syscalls::sys_write(FD_PANIC, report.as_ptr(), report.len());
+
+            default_hook(info);
+        }));
+    }
+
     #[no_mangle]
     unsafe extern "C" fn __start() {
         {
             PUBLIC_VALUES_HASHER = Some(Sha256::new());
+            set_panic_hook();
             #[cfg(feature = "verify")]
             {
                 DEFERRED_PROOFS_DIGEST = Some([BabyBear::zero(); 8]);
@@ -56,6 +101,7 @@ mod zkvm {
         syscall_halt(0);
     }
 
+    // Must match `sp1_primitives::consts::STACK_TOP`, which the loader checks ELFs against.
     static STACK_TOP: u32 = 0x0020_0400;
 
     core::arch::global_asm!(include_str!("memset.s"));
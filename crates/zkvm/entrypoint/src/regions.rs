@@ -0,0 +1,63 @@
+use alloc::collections::BTreeSet;
+
+use sha2::{Digest, Sha256};
+use sp1_channels::{encode_channel_write, input_region_channel};
+
+use crate::syscalls::{syscall_write, INPUT_REGIONS_PTR};
+
+// Must match `sp1_primitives::consts::STACK_TOP`, below which the host never maps regions.
+const STACK_TOP: u32 = 0x0020_0400;
+
+// Must match `sp1_lib::io::FD_PUBLIC_VALUE_CHANNELS`.
+const FD_PUBLIC_VALUE_CHANNELS: u32 = 10;
+
+/// The indices of the regions whose digest the program committed to.
+static mut COMMITTED_REGIONS: BTreeSet<usize> = BTreeSet::new();
+
+/// Get the read-only input region the host wrote with `SP1Stdin::write_region`, by the order it
+/// was written in, or `None` if there is no such region.
+///
+/// The region is mapped into memory before the program starts, so it is read without copying it.
+/// The host doesn't constrain its contents, so the first time the program gets a region this hashes
+/// it with SHA-256 and commits the digest to its own channel of public values,
+/// `sp1_channels::input_region_channel(index)`. The verifier checks it against the digest
+/// `SP1Stdin::write_region` returned with `SP1PublicValues::input_region_digest`.
+///
+/// Hashing takes one SHA-256 compression per 64 bytes of the region, which is paid once for each
+/// region the program gets. The channel Merkleizes the public values, so a program that gets an
+/// input region can't be proven with Plonk or Groth16.
+///
+/// ### Examples
+/// ```ignore
+/// let blob: &'static [u8] = sp1_zkvm::io::input_region(0).unwrap();
+/// ```
+pub fn input_region(index: usize) -> Option<&'static [u8]> {
+    let end = INPUT_REGIONS_PTR as usize;
+    // SAFETY: the host maps the table and the regions below the pointer to them, where the heap
+    // doesn't grow, and stores to them fail. The pointer reads as zero if there are no regions.
+    let region = unsafe {
+        let table = (INPUT_REGIONS_PTR as *const u32).read_volatile() as usize;
+        if table < STACK_TOP as usize || table >= end {
+            return None;
+        }
+        // The table can't extend past the pointer, whatever count it holds.
+        let count = (table as *const u32).read_volatile() as usize;
+        if index >= count.min((end - table - 4) / 8) {
+            return None;
+        }
+        let addr = (table as *const u32).add(1 + 2 * index).read_volatile() as usize;
+        let len = (table as *const u32).add(2 + 2 * index).read_volatile() as usize;
+        if addr < table || len > end.saturating_sub(addr) {
+            return None;
+        }
+        core::slice::from_raw_parts(addr as *const u8, len)
+    };
+    // SAFETY: the program is single threaded.
+    let committed = unsafe { &mut *core::ptr::addr_of_mut!(COMMITTED_REGIONS) };
+    if committed.insert(index) {
+        let digest = Sha256::digest(region);
+        let frame = encode_channel_write(&input_region_channel(index), &digest);
+        syscall_write(FD_PUBLIC_VALUE_CHANNELS, frame.as_ptr(), frame.len());
+    }
+    Some(region)
+}
@@ -2,6 +2,7 @@ cfg_if::cfg_if! {
     if #[cfg(target_os = "zkvm")] {
         use core::arch::asm;
         use sha2::Digest;
+        use sp1_channels::public_values_digest;
         use crate::zkvm;
         use crate::{PV_DIGEST_NUM_WORDS, POSEIDON_NUM_WORDS};
     }
@@ -22,10 +23,20 @@ pub extern "C" fn syscall_halt(exit_code: u8) -> ! {
     unsafe {
         // When we halt, we retrieve the public values finalized digest.  This is the hash of all
         // the bytes written to the public values fd.
-        let pv_digest_bytes =
+        let stream_digest =
             core::mem::take(&mut *core::ptr::addr_of_mut!(zkvm::PUBLIC_VALUES_HASHER))
                 .unwrap()
-                .finalize();
+                .finalize()
+                .into();
+
+        // If the program committed to named channels, the digest is instead the root of the
+        // Merkle tree of the public values stream and the channels, computed by the same function
+        // as `SP1PublicValues::hash` on the host.
+        let channels = core::mem::take(&mut *core::ptr::addr_of_mut!(zkvm::PUBLIC_VALUE_CHANNELS));
+        let pv_digest_bytes = public_values_digest(
+            stream_digest,
+            channels.iter().map(|(name, hasher)| (name.as_str(), hasher.clone().finalize().into())),
+        );
 
         // For each digest word, call COMMIT ecall.  In the runtime, this will store the digest
         // words into the runtime's execution record's public values digest.  In the AIR, it
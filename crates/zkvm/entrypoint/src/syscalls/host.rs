@@ -0,0 +1,26 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Invokes the syscall `code`, one of [`HOST_0`](crate::syscalls::HOST_0) to
+/// [`HOST_3`](crate::syscalls::HOST_3), with the given arguments and returns its result.
+///
+/// These syscalls are handled by the host that registered them when executing the program, and
+/// a program that invokes them can't be proven.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_host(code: u32, arg1: u32, arg2: u32) -> u32 {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let result;
+        asm!(
+            "ecall",
+            inlateout("t0") code => result,
+            in("a0") arg1,
+            in("a1") arg2,
+        );
+        result
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -3,6 +3,7 @@ cfg_if::cfg_if! {
         use core::arch::asm;
         use crate::zkvm;
         use sha2::digest::Update;
+        use sp1_channels::decode_channel_write;
     }
 }
 
@@ -13,6 +14,7 @@ pub extern "C" fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize) {
     cfg_if::cfg_if! {
         if #[cfg(target_os = "zkvm")] {
             const FD_PUBLIC_VALUES: u32 = 3;
+            const FD_PUBLIC_VALUE_CHANNELS: u32 = 10;
             unsafe {
                 asm!(
                     "ecall",
@@ -30,6 +32,18 @@ pub extern "C" fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize) {
                 let pi_slice: &[u8] = unsafe { core::slice::from_raw_parts(write_buf, nbytes) };
                 unsafe { zkvm::PUBLIC_VALUES_HASHER.as_mut().unwrap().update(pi_slice) };
             }
+
+            // Writes to the channels fd are frames of the length of the channel name, the name
+            // and the bytes, which update the hasher of that channel. The runtime rejects
+            // malformed frames, so they are skipped here.
+            if fd == FD_PUBLIC_VALUE_CHANNELS {
+                let frame: &[u8] = unsafe { core::slice::from_raw_parts(write_buf, nbytes) };
+                if let Some((name, data)) = decode_channel_write(frame) {
+                    let channels =
+                        unsafe { &mut *core::ptr::addr_of_mut!(zkvm::PUBLIC_VALUE_CHANNELS) };
+                    channels.entry(name.into()).or_default().update(data);
+                }
+            }
         } else {
             unreachable!()
         }
@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-// Memory addresses must be lower than BabyBear prime.
-const MAX_MEMORY: usize = 0x78000000;
+// Must match `sp1_primitives::consts::INPUT_REGIONS_PTR`, where the host writes the address of the
+// table of input regions.
+pub(crate) const INPUT_REGIONS_PTR: u32 = 0x7800_0000 - 4;
 
 #[allow(clippy::missing_safety_doc)]
 #[no_mangle]
@@ -42,8 +43,14 @@ pub unsafe extern "C" fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u
     let ptr = heap_pos as *mut u8;
     let (heap_pos, overflowed) = heap_pos.overflowing_add(bytes);
 
-    if overflowed || MAX_MEMORY < heap_pos {
-        panic!("Memory limit exceeded (0x78000000)");
+    // Memory addresses must be lower than BabyBear prime, and the heap ends where the host mapped
+    // the input regions, if it mapped any.
+    let max_memory = match unsafe { (INPUT_REGIONS_PTR as *const u32).read_volatile() } {
+        0 => INPUT_REGIONS_PTR,
+        table => table,
+    } as usize;
+    if overflowed || max_memory < heap_pos {
+        panic!("Memory limit exceeded");
     }
 
     unsafe { HEAP_POS = heap_pos };
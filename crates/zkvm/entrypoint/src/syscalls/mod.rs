@@ -4,6 +4,7 @@ mod bn254;
 mod ed25519;
 mod fptower;
 mod halt;
+mod host;
 mod io;
 mod keccak_permute;
 mod memory;
@@ -22,6 +23,7 @@ pub use bn254::*;
 pub use ed25519::*;
 pub use fptower::*;
 pub use halt::*;
+pub use host::*;
 pub use io::*;
 pub use keccak_permute::*;
 pub use memory::*;
@@ -150,3 +152,12 @@ pub const MEMCPY_64: u32 = 0x00_01_01_91;
 pub const BN254_SCALAR_MUL: u32 = 0x00_01_01_80;
 /// Executes the `BN254_SCALAR_MAC` precompile
 pub const BN254_SCALAR_MAC: u32 = 0x00_01_01_81;
+
+/// Reserved for a syscall registered on the host, which can be executed but not proven.
+pub const HOST_0: u32 = 0x00_00_00_E0;
+/// Reserved for a syscall registered on the host, which can be executed but not proven.
+pub const HOST_1: u32 = 0x00_00_00_E1;
+/// Reserved for a syscall registered on the host, which can be executed but not proven.
+pub const HOST_2: u32 = 0x00_00_00_E2;
+/// Reserved for a syscall registered on the host, which can be executed but not proven.
+pub const HOST_3: u32 = 0x00_00_00_E3;
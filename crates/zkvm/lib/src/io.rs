@@ -15,6 +15,24 @@ pub const FD_HINT: u32 = 4;
 /// The file descriptor for the `ecreover` hook.
 pub const FD_ECRECOVER_HOOK: u32 = 5;
 
+/// The file descriptor for the hook that checks which of a batch of Ed25519 signatures are valid.
+pub const FD_ED25519_VERIFY_HOOK: u32 = 6;
+
+/// The file descriptor for the hook that computes a modular inverse.
+pub const FD_MOD_INVERSE_HOOK: u32 = 7;
+
+/// The file descriptor for the hook that computes a modular square root.
+pub const FD_MOD_SQRT_HOOK: u32 = 8;
+
+/// The file descriptor for the hook that computes the permutation that sorts a list.
+pub const FD_SORT_PERMUTATION_HOOK: u32 = 9;
+
+/// The file descriptor for named channels of public values.
+pub const FD_PUBLIC_VALUE_CHANNELS: u32 = 10;
+
+/// The file descriptor the panic hook reports a panic to.
+pub const FD_PANIC: u32 = 11;
+
 /// A writer that writes to a file descriptor inside the zkVM.
 struct SyscallWriter {
     fd: u32,
@@ -121,6 +139,40 @@ pub fn commit_slice(buf: &[u8]) {
     my_writer.write_all(buf).unwrap();
 }
 
+/// Commit a serializable object to the named channel of public values.
+///
+/// Once a program commits to a named channel, its public values digest is the root of a Merkle
+/// tree over the public values stream and each channel, so the host can reveal some channels and
+/// only give the digest of the others. The empty name is the public values stream itself.
+///
+/// ### Examples
+/// ```ignore
+/// sp1_zkvm::io::commit_to("balance", &100u64);
+/// ```
+pub fn commit_to<T: Serialize>(channel: &str, value: &T) {
+    let buf = bincode::serialize(value).expect("serialization failed");
+    commit_slice_to(channel, &buf);
+}
+
+/// Commit bytes to the named channel of public values.
+///
+/// ### Examples
+/// ```ignore
+/// let data = vec![1, 2, 3, 4];
+/// sp1_zkvm::io::commit_slice_to("data", &data);
+/// ```
+pub fn commit_slice_to(channel: &str, buf: &[u8]) {
+    if channel.is_empty() {
+        return commit_slice(buf);
+    }
+    // The channel and the bytes are written at once, as one frame.
+    let mut frame = Vec::with_capacity(4 + channel.len() + buf.len());
+    frame.extend_from_slice(&(channel.len() as u32).to_le_bytes());
+    frame.extend_from_slice(channel.as_bytes());
+    frame.extend_from_slice(buf);
+    write(FD_PUBLIC_VALUE_CHANNELS, &frame);
+}
+
 /// Hint a serializable object to the hint stream.
 ///
 /// ### Examples
@@ -166,3 +218,22 @@ pub fn hint_slice(buf: &[u8]) {
 pub fn write(fd: u32, buf: &[u8]) {
     SyscallWriter { fd }.write_all(buf).unwrap();
 }
+
+/// Call the hook at the file descriptor `fd` with a serializable request, and read its
+/// deserializable response from the input stream.
+///
+/// The hook must be registered on the host as a typed hook with the same request and response
+/// types. Its response is a hint, so the program must check it.
+///
+/// ### Examples
+/// ```ignore
+/// // The square root of 4 modulo 7, with the numbers as little-endian bytes.
+/// let root: Option<Vec<u8>> =
+///     sp1_zkvm::io::hook(sp1_zkvm::io::FD_MOD_SQRT_HOOK, &(vec![4u8], vec![7u8]));
+/// ```
+pub fn hook<Req: Serialize, Resp: DeserializeOwned>(fd: u32, request: &Req) -> Resp {
+    // The request is written at once, since each write invokes the hook.
+    let buf = bincode::serialize(request).expect("serialization failed");
+    write(fd, &buf);
+    read()
+}
@@ -77,6 +77,9 @@ extern "C" {
     /// Reads the next element in the hint stream into the given buffer.
     pub fn syscall_hint_read(ptr: *mut u8, len: usize);
 
+    /// Invokes a syscall registered on the host, which can be executed but not proven.
+    pub fn syscall_host(code: u32, arg1: u32, arg2: u32) -> u32;
+
     /// Allocates a buffer aligned to the given alignment.
     pub fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u8;
 